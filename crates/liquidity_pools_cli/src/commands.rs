@@ -0,0 +1,580 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::{bail, Result};
+use liquidity_pools::math::FEE_DENOMINATOR;
+use liquidity_pools_client::instructions::{self, CreatePoolOptions, SwapDirection};
+use liquidity_pools_client::pda::{sort_mints, PoolKeys};
+use liquidity_pools_client::{
+    quote, FeeRecipient, LockDuration, PoolCreationMode, PoolRegistry, ProtocolFeeMode,
+};
+use solana_sdk::pubkey::Pubkey;
+
+use crate::context::Context;
+use crate::TradeArgs;
+
+pub fn init(ctx: &Context, recipient: Option<Pubkey>, fee_bp: u16) -> Result<()> {
+    let authority = ctx.signer();
+    let recipient = recipient.unwrap_or(authority);
+    ctx.submit(&[instructions::initialize(&authority, recipient, fee_bp)])
+}
+
+pub fn config_show(ctx: &Context) -> Result<()> {
+    let (address, config) = ctx.fetch_config()?;
+    println!("Config: {address}");
+    println!("{config:#?}");
+    if let Some((address, config_change)) = ctx.fetch_config_change()? {
+        println!("Queued change: {address}");
+        println!("{config_change:#?}");
+    }
+    Ok(())
+}
+
+pub fn config_queue_change(
+    ctx: &Context,
+    recipient: Option<Pubkey>,
+    fee_bp: Option<u16>,
+    timelock_delay: Option<i64>,
+    protocol_fee_mode: Option<ProtocolFeeMode>,
+    max_fee_bp_override: Option<u16>,
+    fee_recipients: Vec<FeeRecipient>,
+) -> Result<()> {
+    if recipient.is_none()
+        && fee_bp.is_none()
+        && timelock_delay.is_none()
+        && protocol_fee_mode.is_none()
+        && max_fee_bp_override.is_none()
+        && fee_recipients.is_empty()
+    {
+        bail!("nothing to change");
+    }
+    // The program sets the eta from the delay in force when the change is queued
+    let (_, config) = ctx.fetch_config()?;
+    let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64;
+    ctx.submit(&[instructions::queue_config_change(
+        &ctx.signer(),
+        recipient,
+        fee_bp,
+        timelock_delay,
+        protocol_fee_mode,
+        max_fee_bp_override,
+        (!fee_recipients.is_empty()).then_some(fee_recipients),
+    )])?;
+    println!(
+        "Executable after: {} (in {}s), apply it with `config execute-change`",
+        now + config.timelock_delay,
+        config.timelock_delay
+    );
+    Ok(())
+}
+
+pub fn config_set_fee(ctx: &Context, fee_bp: u16) -> Result<()> {
+    config_queue_change(ctx, None, Some(fee_bp), None, None, None, Vec::new())
+}
+
+pub fn config_set_recipient(ctx: &Context, recipient: Pubkey) -> Result<()> {
+    config_queue_change(ctx, Some(recipient), None, None, None, None, Vec::new())
+}
+
+pub fn config_execute_change(ctx: &Context) -> Result<()> {
+    ctx.submit(&[instructions::execute_config_change(&ctx.signer())])
+}
+
+pub fn config_cancel_change(ctx: &Context) -> Result<()> {
+    ctx.submit(&[instructions::cancel_config_change(&ctx.signer())])
+}
+
+pub fn config_set_max_referral_fee(ctx: &Context, max_referral_fee_bp: u16) -> Result<()> {
+    ctx.submit(&[instructions::update_config(
+        &ctx.signer(),
+        Some(max_referral_fee_bp),
+        None,
+        None,
+        None,
+        None,
+    )])
+}
+
+pub fn config_set_pool_fee_bounds(
+    ctx: &Context,
+    min_pool_fee_bp: Option<u16>,
+    max_pool_fee_bp: Option<u16>,
+) -> Result<()> {
+    if min_pool_fee_bp.is_none() && max_pool_fee_bp.is_none() {
+        bail!("nothing to change");
+    }
+    ctx.submit(&[instructions::update_config(
+        &ctx.signer(),
+        None,
+        min_pool_fee_bp,
+        max_pool_fee_bp,
+        None,
+        None,
+    )])
+}
+
+/// `mint` is the token of the fee, lamports when omitted
+pub fn config_set_pool_creation_fee(ctx: &Context, fee: u64, mint: Option<Pubkey>) -> Result<()> {
+    ctx.submit(&[instructions::update_config(
+        &ctx.signer(),
+        None,
+        None,
+        None,
+        Some(fee),
+        Some(mint.unwrap_or_default()),
+    )])
+}
+
+pub fn config_set_max_creator_fee(ctx: &Context, max_creator_fee_bp: u16) -> Result<()> {
+    ctx.submit(&[instructions::set_max_creator_fee(
+        &ctx.signer(),
+        max_creator_fee_bp,
+    )])
+}
+
+pub fn config_set_roles(
+    ctx: &Context,
+    fee_manager: Option<Pubkey>,
+    pause_guardian: Option<Pubkey>,
+    pool_admin: Option<Pubkey>,
+    rewards_admin: Option<Pubkey>,
+    pool_creation_admin: Option<Pubkey>,
+) -> Result<()> {
+    ctx.submit(&[instructions::update_roles(
+        &ctx.signer(),
+        fee_manager,
+        pause_guardian,
+        pool_admin,
+        rewards_admin,
+        pool_creation_admin,
+    )])
+}
+
+pub fn config_migrate(ctx: &Context) -> Result<()> {
+    ctx.submit(&[instructions::migrate_config(&ctx.signer())])
+}
+
+pub fn config_set_pool_creation_mode(ctx: &Context, mode: PoolCreationMode) -> Result<()> {
+    ctx.submit(&[instructions::set_pool_creation_mode(&ctx.signer(), mode)])
+}
+
+pub fn config_allow_creator(ctx: &Context, creator: &Pubkey) -> Result<()> {
+    ctx.submit(&[instructions::add_pool_creator(&ctx.signer(), creator)])
+}
+
+pub fn config_disallow_creator(ctx: &Context, creator: &Pubkey) -> Result<()> {
+    ctx.submit(&[instructions::remove_pool_creator(&ctx.signer(), creator)])
+}
+
+pub fn config_set_early_exit_penalty(ctx: &Context, early_exit_penalty_bp: u16) -> Result<()> {
+    ctx.submit(&[instructions::set_early_exit_penalty(
+        &ctx.signer(),
+        early_exit_penalty_bp,
+    )])
+}
+
+pub fn config_set_paused(ctx: &Context, paused: bool) -> Result<()> {
+    ctx.submit(&[instructions::set_paused(&ctx.signer(), paused)])
+}
+
+pub fn pool_create(
+    ctx: &Context,
+    mint_x: Pubkey,
+    mint_y: Pubkey,
+    index: u16,
+    fee_bp: u16,
+    lp_token_2022: bool,
+) -> Result<()> {
+    let (token_mint_a, token_mint_b) = sort_mints(mint_x, mint_y);
+    let token_program_lp = if lp_token_2022 {
+        anchor_spl::token_2022::ID
+    } else {
+        anchor_spl::token::ID
+    };
+    let keys = PoolKeys::new(
+        token_mint_a,
+        token_mint_b,
+        index,
+        ctx.token_program(&token_mint_a)?,
+        ctx.token_program(&token_mint_b)?,
+        token_program_lp,
+    );
+
+    let (_, config) = ctx.fetch_config()?;
+    let authority = ctx.signer();
+    let creation_fee_token_program = if config.charges_creation_fee_in_lamports() {
+        Pubkey::default()
+    } else {
+        ctx.token_program(&config.pool_creation_fee_mint)?
+    };
+    let pair_index_header = ctx.fetch_pair_index_header(&token_mint_a, &token_mint_b)?;
+    let options = CreatePoolOptions::new(
+        &config,
+        pair_index_header.as_ref(),
+        &authority,
+        creation_fee_token_program,
+    );
+
+    if config.pool_creation_fee > 0 {
+        println!("Creation fee: {}", config.pool_creation_fee);
+    }
+    println!("Pool: {}", keys.pool);
+    ctx.submit(&[instructions::create_pool_with_options(
+        &authority, &keys, fee_bp, &options,
+    )])
+}
+
+pub fn pool_show(ctx: &Context, address: &Pubkey) -> Result<()> {
+    let (pool, keys) = ctx.pool_keys(address)?;
+    let (reserve_a, reserve_b, lp_supply) = ctx.reserves(&pool, &keys)?;
+
+    println!("Pool: {address}");
+    println!("{pool:#?}");
+    println!("LP mint: {}", keys.lp_mint);
+    println!("Reserve A: {reserve_a}");
+    println!("Reserve B: {reserve_b}");
+    println!("LP supply: {lp_supply}");
+    Ok(())
+}
+
+pub fn pool_list(ctx: &Context) -> Result<()> {
+    let (_, config) = ctx.fetch_config()?;
+    let mut page = 0;
+    while u64::from(page) * (PoolRegistry::PAGE_SIZE as u64) < config.pool_count {
+        let Some(registry) = ctx.fetch_registry(page)? else {
+            break;
+        };
+        print_pools(ctx, &registry.pools)?;
+        page += 1;
+    }
+    Ok(())
+}
+
+pub fn pool_pair(ctx: &Context, mint_x: Pubkey, mint_y: Pubkey) -> Result<()> {
+    let (token_mint_a, token_mint_b) = sort_mints(mint_x, mint_y);
+    let pair_indexes = ctx.fetch_pair_indexes(&token_mint_a, &token_mint_b)?;
+    if pair_indexes.is_empty() {
+        bail!("no pool registered for {token_mint_a} and {token_mint_b}");
+    }
+    for pair_index in pair_indexes {
+        print_pools(ctx, &pair_index.pools)?;
+    }
+    Ok(())
+}
+
+pub fn pool_register(ctx: &Context, address: &Pubkey) -> Result<()> {
+    let (_, config) = ctx.fetch_config()?;
+    let (pool, keys) = ctx.pool_keys(address)?;
+    if pool.registered {
+        bail!("pool {address} is already registered");
+    }
+    let pair_index_header = ctx.fetch_pair_index_header(&keys.token_mint_a, &keys.token_mint_b)?;
+    ctx.submit(&[instructions::register_pool(
+        &ctx.signer(),
+        &keys,
+        config.registry_page(),
+        pair_index_header.map_or(0, |header| header.next_page()),
+    )])
+}
+
+pub fn pool_close(ctx: &Context, address: &Pubkey, recipient: Option<Pubkey>) -> Result<()> {
+    let (pool, keys) = ctx.pool_keys(address)?;
+    let authority = ctx.signer();
+    let recipient = recipient.unwrap_or(authority);
+    let with_lock_vault = ctx.account_exists(&keys.lock_vault())?;
+    ctx.submit(&[instructions::close_pool(
+        &authority,
+        &keys,
+        &recipient,
+        with_lock_vault,
+        pool.pair_index_page,
+    )])
+}
+
+pub fn pool_set_limits(
+    ctx: &Context,
+    address: &Pubkey,
+    max_price_impact_bp: Option<u16>,
+    max_trade_size_bp: Option<u16>,
+) -> Result<()> {
+    ctx.submit(&[instructions::update_pool(
+        &ctx.signer(),
+        address,
+        max_price_impact_bp,
+        max_trade_size_bp,
+        None,
+        None,
+    )])
+}
+
+pub fn pool_set_protocol_fee(
+    ctx: &Context,
+    address: &Pubkey,
+    protocol_fee_bp: Option<u16>,
+) -> Result<()> {
+    ctx.submit(&[instructions::update_pool(
+        &ctx.signer(),
+        address,
+        None,
+        None,
+        Some(protocol_fee_bp),
+        None,
+    )])
+}
+
+pub fn pool_set_fee(ctx: &Context, address: &Pubkey, fee_bp: u16) -> Result<()> {
+    ctx.submit(&[instructions::update_pool(
+        &ctx.signer(),
+        address,
+        None,
+        None,
+        None,
+        Some(fee_bp),
+    )])
+}
+
+pub fn pool_set_creator_fee(ctx: &Context, address: &Pubkey, creator_fee_bp: u16) -> Result<()> {
+    ctx.submit(&[instructions::set_creator_fee(
+        &ctx.signer(),
+        address,
+        creator_fee_bp,
+    )])
+}
+
+pub fn pool_migrate(ctx: &Context, address: &Pubkey) -> Result<()> {
+    ctx.submit(&[instructions::migrate_pool(&ctx.signer(), address)])
+}
+
+pub fn liquidity_add(
+    ctx: &Context,
+    address: &Pubkey,
+    amount_a: u64,
+    amount_b: u64,
+    recipient: Option<Pubkey>,
+    trade: &TradeArgs,
+) -> Result<()> {
+    let (_, config) = ctx.fetch_config()?;
+    let (pool, keys) = ctx.pool_keys(address)?;
+    let (reserve_a, reserve_b, lp_supply) = ctx.reserves(&pool, &keys)?;
+    let lp_supply =
+        quote::lp_supply_after_protocol_fee(&config, &pool, reserve_a, reserve_b, lp_supply)?;
+    let quote = quote::quote_add_liquidity(amount_a, amount_b, reserve_a, reserve_b, lp_supply)?;
+
+    println!(
+        "Depositing {} A and {} B for {} LP",
+        quote.amount_a, quote.amount_b, quote.lp_amount
+    );
+    let provider = ctx.signer();
+    // The program mints the pending protocol fee to every recipient LP account
+    let fee_recipients = instructions::protocol_fee_lp_recipients(&config, &pool);
+    let mut liquidity_instructions =
+        instructions::create_recipient_lp_accounts(&provider, &keys, &fee_recipients);
+    liquidity_instructions.push(instructions::add_liquidity_to(
+        &keys,
+        &provider,
+        &recipient.unwrap_or(provider),
+        amount_a,
+        amount_b,
+        min_amount(quote.amount_a, trade.slippage_bp)?,
+        min_amount(quote.amount_b, trade.slippage_bp)?,
+        deadline(trade)?,
+        &fee_recipients,
+        trade.native_sol,
+    ));
+    ctx.submit(&liquidity_instructions)
+}
+
+pub fn liquidity_remove(
+    ctx: &Context,
+    address: &Pubkey,
+    lp_amount: u64,
+    trade: &TradeArgs,
+) -> Result<()> {
+    let (_, config) = ctx.fetch_config()?;
+    let (pool, keys) = ctx.pool_keys(address)?;
+    let (reserve_a, reserve_b, lp_supply) = ctx.reserves(&pool, &keys)?;
+    let lp_supply =
+        quote::lp_supply_after_protocol_fee(&config, &pool, reserve_a, reserve_b, lp_supply)?;
+    let quote = quote::quote_remove_liquidity(lp_amount, reserve_a, reserve_b, lp_supply)?;
+
+    println!(
+        "Burning {lp_amount} LP for {} A and {} B",
+        quote.amount_a, quote.amount_b
+    );
+    // The program mints the pending protocol fee to every recipient LP account
+    let provider = ctx.signer();
+    let fee_recipients = instructions::protocol_fee_lp_recipients(&config, &pool);
+    let mut liquidity_instructions =
+        instructions::create_recipient_lp_accounts(&provider, &keys, &fee_recipients);
+    // The program requires non-zero minimums
+    liquidity_instructions.push(instructions::remove_liquidity(
+        &keys,
+        &provider,
+        lp_amount,
+        min_amount(quote.amount_a, trade.slippage_bp)?.max(1),
+        min_amount(quote.amount_b, trade.slippage_bp)?.max(1),
+        deadline(trade)?,
+        &fee_recipients,
+        trade.native_sol,
+    ));
+    ctx.submit(&liquidity_instructions)
+}
+
+pub fn swap(
+    ctx: &Context,
+    address: &Pubkey,
+    input_mint: &Pubkey,
+    amount_in: u64,
+    referrer: Option<(Pubkey, u16)>,
+    recipient: Option<Pubkey>,
+    trade: &TradeArgs,
+) -> Result<()> {
+    let (pool, keys) = ctx.pool_keys(address)?;
+    let (reserve_a, reserve_b, _) = ctx.reserves(&pool, &keys)?;
+    let (direction, reserve_in, reserve_out) = if *input_mint == pool.token_mint_a {
+        (SwapDirection::AToB, reserve_a, reserve_b)
+    } else if *input_mint == pool.token_mint_b {
+        (SwapDirection::BToA, reserve_b, reserve_a)
+    } else {
+        bail!("{input_mint} is not a token of pool {address}");
+    };
+    let quote = quote::quote_swap(&pool, amount_in, reserve_in, reserve_out)?;
+
+    println!(
+        "Swapping {amount_in} for {} (fee {})",
+        quote.amount_out, quote.fee_amount
+    );
+    // The program requires a non-zero minimum
+    let payer = ctx.signer();
+    ctx.submit(&[instructions::swap_to(
+        &keys,
+        &payer,
+        &recipient.unwrap_or(payer),
+        direction,
+        amount_in,
+        min_amount(quote.amount_out, trade.slippage_bp)?.max(1),
+        deadline(trade)?,
+        referrer,
+        trade.native_sol,
+    )])
+}
+
+pub fn fees_claim(ctx: &Context, address: &Pubkey) -> Result<()> {
+    let (_, config) = ctx.fetch_config()?;
+    let (pool, keys) = ctx.pool_keys(address)?;
+
+    println!(
+        "Claiming {} A and {} B",
+        pool.protocol_fees_a, pool.protocol_fees_b
+    );
+    for entry in config.active_fee_recipients() {
+        println!("  {} bp to {}", entry.weight_bp, entry.recipient);
+    }
+    ctx.submit(&[instructions::claim_fees(
+        &keys,
+        &instructions::fee_recipients(&config),
+    )])
+}
+
+pub fn fees_claim_creator(ctx: &Context, address: &Pubkey) -> Result<()> {
+    let (pool, keys) = ctx.pool_keys(address)?;
+
+    println!(
+        "Claiming {} A and {} B for {}",
+        pool.creator_fees_a, pool.creator_fees_b, pool.creator
+    );
+    ctx.submit(&[instructions::claim_creator_fees(&keys, &ctx.signer())])
+}
+
+fn print_pools(ctx: &Context, addresses: &[Pubkey]) -> Result<()> {
+    for (address, pool) in addresses.iter().zip(ctx.fetch_pools(addresses)?) {
+        match pool {
+            Some(pool) => println!(
+                "{address} {} {} #{} {} bp",
+                pool.token_mint_a, pool.token_mint_b, pool.index, pool.fee_bp
+            ),
+            None => println!("{address} closed or older layout, run `pool migrate` first"),
+        }
+    }
+    Ok(())
+}
+
+/// `amount` reduced by the accepted slippage, rounded down
+fn min_amount(amount: u64, slippage_bp: u16) -> Result<u64> {
+    if u128::from(slippage_bp) > FEE_DENOMINATOR {
+        bail!("slippage cannot exceed {FEE_DENOMINATOR} bp");
+    }
+    Ok((amount as u128 * (FEE_DENOMINATOR - slippage_bp as u128) / FEE_DENOMINATOR) as u64)
+}
+
+pub fn lock_create(
+    ctx: &Context,
+    address: &Pubkey,
+    amount: u64,
+    duration: LockDuration,
+    id: u64,
+) -> Result<()> {
+    let (_, keys) = ctx.pool_keys(address)?;
+
+    println!(
+        "Locking {amount} LP for {} days in position {id}",
+        duration.seconds() / 86_400
+    );
+    ctx.submit(&[instructions::lock_lp(
+        &keys,
+        &ctx.signer(),
+        id,
+        amount,
+        duration,
+    )])
+}
+
+pub fn lock_withdraw(ctx: &Context, address: &Pubkey, id: u64) -> Result<()> {
+    let (_, config) = ctx.fetch_config()?;
+    let (_, keys) = ctx.pool_keys(address)?;
+    let owner = ctx.signer();
+    let (_, position) = ctx.fetch_lock_position(address, &owner, id)?;
+
+    // Early exits split the penalty between the LP token accounts of the fee recipients
+    let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64;
+    let fee_recipients = if now < position.unlock_at {
+        if position.early_exit_penalty_bp == 0 {
+            bail!("position {id} unlocks at {}", position.unlock_at);
+        }
+        let fee_recipients = instructions::fee_recipients(&config);
+        println!(
+            "Withdrawing early, {} bp of {} LP go to {}",
+            position.early_exit_penalty_bp,
+            position.amount,
+            fee_recipients
+                .iter()
+                .map(Pubkey::to_string)
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+        fee_recipients
+    } else {
+        println!("Withdrawing {} LP", position.amount);
+        Vec::new()
+    };
+    ctx.submit(&[instructions::unlock_lp(&keys, &owner, id, &fee_recipients)])
+}
+
+pub fn lock_show(ctx: &Context, address: &Pubkey, owner: Option<Pubkey>, id: u64) -> Result<()> {
+    let owner = owner.unwrap_or_else(|| ctx.signer());
+    let (position_address, position) = ctx.fetch_lock_position(address, &owner, id)?;
+    let pool = ctx.fetch_pool(address)?;
+
+    println!("Lock position: {position_address}");
+    println!("{position:#?}");
+    println!("Locked in pool: {}", pool.total_locked_lp);
+    println!("Boosted in pool: {}", pool.total_boosted_lp);
+    Ok(())
+}
+
+fn deadline(trade: &TradeArgs) -> Result<Option<i64>> {
+    let Some(seconds) = trade.deadline_secs else {
+        return Ok(None);
+    };
+    let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64;
+    Ok(Some(now + seconds))
+}
@@ -0,0 +1,250 @@
+use anyhow::{anyhow, Context as _, Result};
+use base64::prelude::{Engine, BASE64_STANDARD};
+use liquidity_pools_client::pda::{
+    find_config_address, find_config_change_address, find_lock_position_address,
+    find_lp_mint_address, find_pair_index_address, find_pair_index_header_address,
+    find_registry_address, PoolKeys,
+};
+use liquidity_pools_client::{
+    accounts, Config, ConfigChange, LockPosition, PairIndex, PairIndexHeader, Pool, PoolRegistry,
+};
+use solana_rpc_client::rpc_client::RpcClient;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::hash::Hash;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::message::Message;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{read_keypair_file, Keypair, Signer};
+use solana_sdk::transaction::Transaction;
+
+/// Most accounts `getMultipleAccounts` returns in one request
+const MAX_MULTIPLE_ACCOUNTS: usize = 100;
+
+/// Who signs the transactions: a local keypair, or only a public key when
+/// transactions are printed for offline signing
+enum Identity {
+    Keypair(Keypair),
+    Pubkey(Pubkey),
+}
+
+pub struct Context {
+    pub rpc: RpcClient,
+    identity: Identity,
+    blockhash: Option<Hash>,
+}
+
+impl Context {
+    pub fn new(
+        url: &str,
+        keypair_path: &str,
+        unsigned: bool,
+        signer: Option<Pubkey>,
+        blockhash: Option<Hash>,
+    ) -> Result<Self> {
+        let identity = match signer {
+            Some(signer) => Identity::Pubkey(signer),
+            None => {
+                let path = expand_home(keypair_path);
+                let keypair = read_keypair_file(&path)
+                    .map_err(|err| anyhow!("failed to read keypair {path}: {err}"))?;
+                if unsigned {
+                    Identity::Pubkey(keypair.pubkey())
+                } else {
+                    Identity::Keypair(keypair)
+                }
+            }
+        };
+
+        Ok(Self {
+            rpc: RpcClient::new_with_commitment(url, CommitmentConfig::confirmed()),
+            identity,
+            blockhash,
+        })
+    }
+
+    pub fn signer(&self) -> Pubkey {
+        match &self.identity {
+            Identity::Keypair(keypair) => keypair.pubkey(),
+            Identity::Pubkey(pubkey) => *pubkey,
+        }
+    }
+
+    pub fn fetch_config(&self) -> Result<(Pubkey, Config)> {
+        let (address, _) = find_config_address();
+        let data = self.fetch_data(&address)?;
+        Ok((address, accounts::decode_config(&data)?))
+    }
+
+    /// The queued config change, if any
+    pub fn fetch_config_change(&self) -> Result<Option<(Pubkey, ConfigChange)>> {
+        let (address, _) = find_config_change_address();
+        let account = self
+            .rpc
+            .get_account_with_commitment(&address, self.rpc.commitment())?
+            .value;
+        match account {
+            Some(account) => Ok(Some((
+                address,
+                accounts::decode_config_change(&account.data)?,
+            ))),
+            None => Ok(None),
+        }
+    }
+
+    /// The registry page `page`, if a pool was registered in it
+    pub fn fetch_registry(&self, page: u32) -> Result<Option<PoolRegistry>> {
+        let (address, _) = find_registry_address(page);
+        self.fetch_optional_data(&address)?
+            .map(|data| Ok(accounts::decode_registry(&data)?))
+            .transpose()
+    }
+
+    /// Pair index header of two sorted mints, None until one of their pools is registered
+    pub fn fetch_pair_index_header(
+        &self,
+        token_mint_a: &Pubkey,
+        token_mint_b: &Pubkey,
+    ) -> Result<Option<PairIndexHeader>> {
+        let (address, _) = find_pair_index_header_address(token_mint_a, token_mint_b);
+        self.fetch_optional_data(&address)?
+            .map(|data| Ok(accounts::decode_pair_index_header(&data)?))
+            .transpose()
+    }
+
+    /// The pair index pages of two sorted mints holding a registered pool
+    pub fn fetch_pair_indexes(
+        &self,
+        token_mint_a: &Pubkey,
+        token_mint_b: &Pubkey,
+    ) -> Result<Vec<PairIndex>> {
+        let Some(header) = self.fetch_pair_index_header(token_mint_a, token_mint_b)? else {
+            return Ok(Vec::new());
+        };
+        let addresses: Vec<Pubkey> = (0..header.page_count())
+            .map(|page| find_pair_index_address(token_mint_a, token_mint_b, page).0)
+            .collect();
+        let mut pair_indexes = Vec::new();
+        for chunk in addresses.chunks(MAX_MULTIPLE_ACCOUNTS) {
+            for account in self.rpc.get_multiple_accounts(chunk)?.into_iter().flatten() {
+                pair_indexes.push(accounts::decode_pair_index(&account.data)?);
+            }
+        }
+        Ok(pair_indexes)
+    }
+
+    /// Decoded pools at `addresses`, None for closed pools and older layouts
+    pub fn fetch_pools(&self, addresses: &[Pubkey]) -> Result<Vec<Option<Pool>>> {
+        let accounts = self.rpc.get_multiple_accounts(addresses)?;
+        Ok(accounts
+            .into_iter()
+            .map(|account| {
+                account
+                    .and_then(|account| accounts::decode_pool(&account.data).ok())
+                    .filter(|pool| pool.version == Pool::VERSION)
+            })
+            .collect())
+    }
+
+    pub fn fetch_pool(&self, address: &Pubkey) -> Result<Pool> {
+        Ok(accounts::decode_pool(&self.fetch_data(address)?)?)
+    }
+
+    /// Lock position `id` of `owner` in `pool`
+    pub fn fetch_lock_position(
+        &self,
+        pool: &Pubkey,
+        owner: &Pubkey,
+        id: u64,
+    ) -> Result<(Pubkey, LockPosition)> {
+        let (address, _) = find_lock_position_address(pool, owner, id);
+        let data = self.fetch_data(&address)?;
+        Ok((address, accounts::decode_lock_position(&data)?))
+    }
+
+    /// Token program owning `mint`
+    pub fn token_program(&self, mint: &Pubkey) -> Result<Pubkey> {
+        let account = self
+            .rpc
+            .get_account(mint)
+            .with_context(|| format!("failed to fetch mint {mint}"))?;
+        Ok(account.owner)
+    }
+
+    /// Derives every address of the pool at `address`
+    pub fn pool_keys(&self, address: &Pubkey) -> Result<(Pool, PoolKeys)> {
+        let pool = self.fetch_pool(address)?;
+        let (lp_mint, _) = find_lp_mint_address(&pool.token_mint_a, &pool.token_mint_b, pool.index);
+        let keys = PoolKeys::new(
+            pool.token_mint_a,
+            pool.token_mint_b,
+            pool.index,
+            self.token_program(&pool.token_mint_a)?,
+            self.token_program(&pool.token_mint_b)?,
+            self.token_program(&lp_mint)?,
+        );
+        Ok((pool, keys))
+    }
+
+    /// Current reserve A, reserve B and LP supply of a pool, unclaimed
+    /// protocol fees are not part of the reserves
+    pub fn reserves(&self, pool: &Pool, keys: &PoolKeys) -> Result<(u64, u64, u64)> {
+        let vault_a = accounts::decode_token_account(&self.fetch_data(&keys.token_vault_a)?)?;
+        let vault_b = accounts::decode_token_account(&self.fetch_data(&keys.token_vault_b)?)?;
+        let lp_mint = accounts::decode_mint(&self.fetch_data(&keys.lp_mint)?)?;
+        let (reserve_a, reserve_b) = pool.reserves(vault_a.amount, vault_b.amount)?;
+        Ok((reserve_a, reserve_b, lp_mint.supply))
+    }
+
+    /// Sends the instructions in one transaction, or prints it unsigned
+    pub fn submit(&self, instructions: &[Instruction]) -> Result<()> {
+        let blockhash = match self.blockhash {
+            Some(blockhash) => blockhash,
+            None => self.rpc.get_latest_blockhash()?,
+        };
+        let message = Message::new_with_blockhash(instructions, Some(&self.signer()), &blockhash);
+
+        match &self.identity {
+            Identity::Keypair(keypair) => {
+                let transaction = Transaction::new(&[keypair], message, blockhash);
+                let signature = self.rpc.send_and_confirm_transaction(&transaction)?;
+                println!("Signature: {signature}");
+            }
+            Identity::Pubkey(_) => {
+                let transaction = Transaction::new_unsigned(message);
+                println!(
+                    "{}",
+                    BASE64_STANDARD.encode(bincode::serialize(&transaction)?)
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether `address` holds an account
+    pub fn account_exists(&self, address: &Pubkey) -> Result<bool> {
+        Ok(self.fetch_optional_data(address)?.is_some())
+    }
+
+    fn fetch_optional_data(&self, address: &Pubkey) -> Result<Option<Vec<u8>>> {
+        let account = self
+            .rpc
+            .get_account_with_commitment(address, self.rpc.commitment())?
+            .value;
+        Ok(account.map(|account| account.data))
+    }
+
+    fn fetch_data(&self, address: &Pubkey) -> Result<Vec<u8>> {
+        let account = self
+            .rpc
+            .get_account(address)
+            .with_context(|| format!("failed to fetch account {address}"))?;
+        Ok(account.data)
+    }
+}
+
+fn expand_home(path: &str) -> String {
+    match (path.strip_prefix("~/"), std::env::var("HOME")) {
+        (Some(rest), Ok(home)) => format!("{home}/{rest}"),
+        _ => path.to_string(),
+    }
+}
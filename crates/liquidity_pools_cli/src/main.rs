@@ -0,0 +1,568 @@
+//! Command-line tool to operate the liquidity_pools program
+//!
+//! Amounts are always in base units of the token (no decimals applied).
+
+mod commands;
+mod context;
+
+use anyhow::{anyhow, Result};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use liquidity_pools_client::{FeeRecipient, LockDuration, PoolCreationMode, ProtocolFeeMode};
+use solana_sdk::hash::Hash;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::context::Context;
+
+#[derive(Parser)]
+#[command(name = "liquidity-pools", version, about)]
+struct Cli {
+    /// JSON RPC URL of the cluster
+    #[arg(short, long, global = true, default_value = "http://127.0.0.1:8899")]
+    url: String,
+
+    /// Keypair file signing and paying for transactions
+    #[arg(short, long, global = true, default_value = "~/.config/solana/id.json")]
+    keypair: String,
+
+    /// Print transactions as unsigned base64 instead of sending them
+    #[arg(long, global = true)]
+    unsigned: bool,
+
+    /// Signer public key used with --unsigned instead of reading --keypair
+    #[arg(long, global = true, requires = "unsigned")]
+    signer: Option<Pubkey>,
+
+    /// Recent blockhash used with --unsigned instead of fetching it
+    #[arg(long, global = true, requires = "unsigned")]
+    blockhash: Option<Hash>,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Create the global config, the signer becomes its authority
+    Init {
+        /// Receiver of the protocol fees (defaults to the signer)
+        #[arg(long)]
+        recipient: Option<Pubkey>,
+        /// Protocol fee in basis points
+        #[arg(long, default_value_t = 0)]
+        fee_bp: u16,
+    },
+    /// Global config
+    #[command(subcommand)]
+    Config(ConfigCommand),
+    /// Pools
+    #[command(subcommand)]
+    Pool(PoolCommand),
+    /// Deposit or withdraw liquidity
+    #[command(subcommand)]
+    Liquidity(LiquidityCommand),
+    /// Swap an exact amount of one pool token for the other
+    Swap {
+        /// Pool address
+        pool: Pubkey,
+        /// Mint of the token sold
+        input_mint: Pubkey,
+        /// Amount sold in base units
+        amount_in: u64,
+        /// Referrer token account of the input mint paid a share of the fee
+        #[arg(long, requires = "referral_fee_bp")]
+        referrer: Option<Pubkey>,
+        /// Share of the swap fee paid to the referrer, in basis points
+        #[arg(long, requires = "referrer")]
+        referral_fee_bp: Option<u16>,
+        /// Owner receiving the output (defaults to the signer)
+        #[arg(long)]
+        recipient: Option<Pubkey>,
+        #[command(flatten)]
+        trade: TradeArgs,
+    },
+    /// Protocol fees
+    #[command(subcommand)]
+    Fees(FeesCommand),
+    /// Time-locked LP positions
+    #[command(subcommand)]
+    Lock(LockCommand),
+}
+
+#[derive(Subcommand)]
+enum ConfigCommand {
+    /// Print the decoded config
+    Show,
+    /// Queue a new protocol share of swap fees in basis points, executable once the
+    /// timelock delay has passed
+    SetFee { fee_bp: u16 },
+    /// Queue a new protocol fee recipient, executable once the timelock delay has passed
+    SetRecipient { recipient: Pubkey },
+    /// Queue a protocol fee change, executable once the timelock delay has passed
+    QueueChange {
+        /// Protocol share of swap fees in basis points
+        #[arg(long)]
+        fee_bp: Option<u16>,
+        /// Protocol fee recipient
+        #[arg(long)]
+        recipient: Option<Pubkey>,
+        /// Delay applied to the following changes
+        #[arg(long)]
+        timelock_delay_secs: Option<i64>,
+        /// How the protocol fee is collected
+        #[arg(long)]
+        protocol_fee_mode: Option<ProtocolFeeModeArg>,
+        /// Highest protocol fee override a pool can be given above the protocol fee
+        #[arg(long)]
+        max_fee_bp_override: Option<u16>,
+        /// Split of the claimed protocol fees as <RECIPIENT>:<WEIGHT_BP>, repeated up
+        /// to 4 times with weights adding up to 10000, the first recipient becomes
+        /// the protocol fee recipient
+        #[arg(long = "fee-recipient", value_parser = parse_fee_recipient)]
+        fee_recipients: Vec<FeeRecipient>,
+    },
+    /// Apply the queued change once its eta has passed
+    ExecuteChange,
+    /// Discard the queued change
+    CancelChange,
+    /// Set the maximum share of the swap fee paid to referrers in basis points
+    SetMaxReferralFee { max_referral_fee_bp: u16 },
+    /// Set the bounds of the pool swap fees in basis points, omitted bounds are unchanged
+    SetPoolFeeBounds {
+        #[arg(long)]
+        min_fee_bp: Option<u16>,
+        #[arg(long)]
+        max_fee_bp: Option<u16>,
+    },
+    /// Set the fee charged by pool creation, 0 disables it
+    SetPoolCreationFee {
+        /// Fee in base units of the token, or in lamports
+        fee: u64,
+        /// Token of the fee (defaults to lamports)
+        #[arg(long)]
+        mint: Option<Pubkey>,
+    },
+    /// Assign the operational roles, omitted roles are unchanged
+    SetRoles {
+        /// Updates the fee fields of the config
+        #[arg(long)]
+        fee_manager: Option<Pubkey>,
+        /// Pauses and unpauses swaps
+        #[arg(long)]
+        pause_guardian: Option<Pubkey>,
+        /// Updates and closes pools
+        #[arg(long)]
+        pool_admin: Option<Pubkey>,
+        /// Manages liquidity rewards
+        #[arg(long)]
+        rewards_admin: Option<Pubkey>,
+        /// Sets the pool creation mode and manages the creator allowlist
+        #[arg(long)]
+        pool_creation_admin: Option<Pubkey>,
+    },
+    /// Halt all swaps
+    Pause,
+    /// Resume swaps
+    Unpause,
+    /// Move the config written by an older layout to the current version
+    Migrate,
+    /// Choose who can create pools
+    SetPoolCreationMode { mode: PoolCreationModeArg },
+    /// Allow a creator to create pools in allowlist mode
+    AllowCreator { creator: Pubkey },
+    /// Remove a creator from the allowlist
+    DisallowCreator { creator: Pubkey },
+    /// Set the share of the LP tokens paid to unlock a position early, 0 disables early exits
+    SetEarlyExitPenalty { penalty_bp: u16 },
+    /// Set the highest share of the swap fee pool creators can take, 0 disables creator fees
+    SetMaxCreatorFee { max_creator_fee_bp: u16 },
+}
+
+fn parse_fee_recipient(entry: &str) -> Result<FeeRecipient> {
+    let (recipient, weight_bp) = entry
+        .split_once(':')
+        .ok_or_else(|| anyhow!("expected <RECIPIENT>:<WEIGHT_BP>"))?;
+    Ok(FeeRecipient {
+        recipient: recipient.parse()?,
+        weight_bp: weight_bp.parse()?,
+    })
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum PoolCreationModeArg {
+    /// Any signer creates pools
+    Permissionless,
+    /// Only the config authority and the pool creation admin create pools
+    AuthorityOnly,
+    /// The config authority, the pool creation admin and the allowlisted creators create pools
+    Allowlist,
+}
+
+impl From<PoolCreationModeArg> for PoolCreationMode {
+    fn from(mode: PoolCreationModeArg) -> Self {
+        match mode {
+            PoolCreationModeArg::Permissionless => PoolCreationMode::Permissionless,
+            PoolCreationModeArg::AuthorityOnly => PoolCreationMode::AuthorityOnly,
+            PoolCreationModeArg::Allowlist => PoolCreationMode::Allowlist,
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum ProtocolFeeModeArg {
+    /// Swaps set the protocol share of their fee aside, claimed with `fees claim`
+    Swap,
+    /// Liquidity changes mint the protocol share of the fee growth as LP tokens
+    LpTokens,
+}
+
+impl From<ProtocolFeeModeArg> for ProtocolFeeMode {
+    fn from(mode: ProtocolFeeModeArg) -> Self {
+        match mode {
+            ProtocolFeeModeArg::Swap => ProtocolFeeMode::Swap,
+            ProtocolFeeModeArg::LpTokens => ProtocolFeeMode::LpTokens,
+        }
+    }
+}
+
+#[derive(Subcommand)]
+enum PoolCommand {
+    /// Create a pool for two mints, in any order
+    Create {
+        mint_x: Pubkey,
+        mint_y: Pubkey,
+        fee_bp: u16,
+        /// Tells apart the pools of the same mints
+        #[arg(long, default_value_t = 0)]
+        index: u16,
+        /// Create the LP mint under Token-2022
+        #[arg(long)]
+        lp_token_2022: bool,
+    },
+    /// Print a decoded pool with its reserves and LP supply
+    Show {
+        /// Pool address
+        pool: Pubkey,
+    },
+    /// List every registered pool in registration order
+    List,
+    /// List the registered pools of two mints, in any order
+    Pair { mint_x: Pubkey, mint_y: Pubkey },
+    /// Add a pool created before the registry to it
+    Register {
+        /// Pool address
+        pool: Pubkey,
+    },
+    /// Close an empty pool and its vaults, returning their rent
+    Close {
+        /// Pool address
+        pool: Pubkey,
+        /// Receiver of the rent (defaults to the signer)
+        #[arg(long)]
+        recipient: Option<Pubkey>,
+    },
+    /// Set the swap limits of a pool, 0 disables a limit
+    SetLimits {
+        /// Pool address
+        pool: Pubkey,
+        /// Maximum price impact of one swap in basis points
+        #[arg(long)]
+        max_price_impact_bp: Option<u16>,
+        /// Maximum swap input as a fraction of the input reserve in basis points
+        #[arg(long)]
+        max_trade_size_bp: Option<u16>,
+    },
+    /// Override the protocol fee of a pool in basis points of the swap fee
+    SetProtocolFee {
+        /// Pool address
+        pool: Pubkey,
+        /// Protocol fee of the pool, omit it to use the config protocol fee again
+        fee_bp: Option<u16>,
+    },
+    /// Change the swap fee of a pool in basis points, within the config bounds
+    SetFee {
+        /// Pool address
+        pool: Pubkey,
+        fee_bp: u16,
+    },
+    /// Set the share of the swap fee accrued to the pool creator, signed by the creator
+    SetCreatorFee {
+        /// Pool address
+        pool: Pubkey,
+        creator_fee_bp: u16,
+    },
+    /// Move a pool written by an older layout to the current version
+    Migrate {
+        /// Pool address
+        pool: Pubkey,
+    },
+}
+
+#[derive(Subcommand)]
+enum LiquidityCommand {
+    /// Deposit up to the given amounts at the current pool ratio
+    Add {
+        /// Pool address
+        pool: Pubkey,
+        /// Maximum amount of token A in base units
+        amount_a: u64,
+        /// Maximum amount of token B in base units
+        amount_b: u64,
+        /// Owner receiving the LP tokens (defaults to the signer)
+        #[arg(long)]
+        recipient: Option<Pubkey>,
+        #[command(flatten)]
+        trade: TradeArgs,
+    },
+    /// Burn LP tokens for the underlying tokens
+    Remove {
+        /// Pool address
+        pool: Pubkey,
+        /// LP tokens burned in base units
+        lp_amount: u64,
+        #[command(flatten)]
+        trade: TradeArgs,
+    },
+}
+
+#[derive(Subcommand)]
+enum FeesCommand {
+    /// Claim the protocol fees accrued by a pool, split between the fee recipients
+    Claim {
+        /// Pool address
+        pool: Pubkey,
+    },
+    /// Claim the creator fees accrued by a pool to the creator token accounts
+    ClaimCreator {
+        /// Pool address
+        pool: Pubkey,
+    },
+}
+
+#[derive(Subcommand)]
+enum LockCommand {
+    /// Lock LP tokens of a pool for a boosted reward share
+    Create {
+        /// Pool address
+        pool: Pubkey,
+        /// LP tokens locked in base units
+        amount: u64,
+        /// Lock period
+        duration: LockDurationArg,
+        /// Position id, lets one owner hold several positions in a pool
+        #[arg(long, default_value_t = 0)]
+        id: u64,
+    },
+    /// Withdraw the LP tokens of a position and close it
+    Withdraw {
+        /// Pool address
+        pool: Pubkey,
+        /// Position id
+        #[arg(long, default_value_t = 0)]
+        id: u64,
+    },
+    /// Print a decoded lock position
+    Show {
+        /// Pool address
+        pool: Pubkey,
+        /// Owner of the position (defaults to the signer)
+        #[arg(long)]
+        owner: Option<Pubkey>,
+        /// Position id
+        #[arg(long, default_value_t = 0)]
+        id: u64,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum LockDurationArg {
+    #[value(name = "1m")]
+    OneMonth,
+    #[value(name = "3m")]
+    ThreeMonths,
+    #[value(name = "6m")]
+    SixMonths,
+    #[value(name = "12m")]
+    TwelveMonths,
+}
+
+impl From<LockDurationArg> for LockDuration {
+    fn from(duration: LockDurationArg) -> Self {
+        match duration {
+            LockDurationArg::OneMonth => LockDuration::OneMonth,
+            LockDurationArg::ThreeMonths => LockDuration::ThreeMonths,
+            LockDurationArg::SixMonths => LockDuration::SixMonths,
+            LockDurationArg::TwelveMonths => LockDuration::TwelveMonths,
+        }
+    }
+}
+
+/// Slippage, deadline and native SOL options shared by trading commands
+#[derive(Args)]
+struct TradeArgs {
+    /// Accepted slippage from the quote in basis points
+    #[arg(long, default_value_t = 50)]
+    slippage_bp: u16,
+    /// Reject the transaction if it lands more than this many seconds from now
+    #[arg(long)]
+    deadline_secs: Option<i64>,
+    /// Pay and receive the native mint side of the pool in SOL
+    #[arg(long)]
+    native_sol: bool,
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let ctx = Context::new(
+        &cli.url,
+        &cli.keypair,
+        cli.unsigned,
+        cli.signer,
+        cli.blockhash,
+    )?;
+
+    match cli.command {
+        Command::Init { recipient, fee_bp } => commands::init(&ctx, recipient, fee_bp),
+        Command::Config(ConfigCommand::Show) => commands::config_show(&ctx),
+        Command::Config(ConfigCommand::SetFee { fee_bp }) => commands::config_set_fee(&ctx, fee_bp),
+        Command::Config(ConfigCommand::SetRecipient { recipient }) => {
+            commands::config_set_recipient(&ctx, recipient)
+        }
+        Command::Config(ConfigCommand::QueueChange {
+            fee_bp,
+            recipient,
+            timelock_delay_secs,
+            protocol_fee_mode,
+            max_fee_bp_override,
+            fee_recipients,
+        }) => commands::config_queue_change(
+            &ctx,
+            recipient,
+            fee_bp,
+            timelock_delay_secs,
+            protocol_fee_mode.map(Into::into),
+            max_fee_bp_override,
+            fee_recipients,
+        ),
+        Command::Config(ConfigCommand::ExecuteChange) => commands::config_execute_change(&ctx),
+        Command::Config(ConfigCommand::CancelChange) => commands::config_cancel_change(&ctx),
+        Command::Config(ConfigCommand::SetPoolCreationFee { fee, mint }) => {
+            commands::config_set_pool_creation_fee(&ctx, fee, mint)
+        }
+        Command::Config(ConfigCommand::SetRoles {
+            fee_manager,
+            pause_guardian,
+            pool_admin,
+            rewards_admin,
+            pool_creation_admin,
+        }) => commands::config_set_roles(
+            &ctx,
+            fee_manager,
+            pause_guardian,
+            pool_admin,
+            rewards_admin,
+            pool_creation_admin,
+        ),
+        Command::Config(ConfigCommand::Pause) => commands::config_set_paused(&ctx, true),
+        Command::Config(ConfigCommand::Unpause) => commands::config_set_paused(&ctx, false),
+        Command::Config(ConfigCommand::Migrate) => commands::config_migrate(&ctx),
+        Command::Config(ConfigCommand::SetPoolCreationMode { mode }) => {
+            commands::config_set_pool_creation_mode(&ctx, mode.into())
+        }
+        Command::Config(ConfigCommand::AllowCreator { creator }) => {
+            commands::config_allow_creator(&ctx, &creator)
+        }
+        Command::Config(ConfigCommand::DisallowCreator { creator }) => {
+            commands::config_disallow_creator(&ctx, &creator)
+        }
+        Command::Config(ConfigCommand::SetEarlyExitPenalty { penalty_bp }) => {
+            commands::config_set_early_exit_penalty(&ctx, penalty_bp)
+        }
+        Command::Config(ConfigCommand::SetMaxCreatorFee { max_creator_fee_bp }) => {
+            commands::config_set_max_creator_fee(&ctx, max_creator_fee_bp)
+        }
+        Command::Config(ConfigCommand::SetMaxReferralFee {
+            max_referral_fee_bp,
+        }) => commands::config_set_max_referral_fee(&ctx, max_referral_fee_bp),
+        Command::Config(ConfigCommand::SetPoolFeeBounds {
+            min_fee_bp,
+            max_fee_bp,
+        }) => commands::config_set_pool_fee_bounds(&ctx, min_fee_bp, max_fee_bp),
+        Command::Pool(PoolCommand::Create {
+            mint_x,
+            mint_y,
+            fee_bp,
+            index,
+            lp_token_2022,
+        }) => commands::pool_create(&ctx, mint_x, mint_y, index, fee_bp, lp_token_2022),
+        Command::Pool(PoolCommand::Show { pool }) => commands::pool_show(&ctx, &pool),
+        Command::Pool(PoolCommand::List) => commands::pool_list(&ctx),
+        Command::Pool(PoolCommand::Pair { mint_x, mint_y }) => {
+            commands::pool_pair(&ctx, mint_x, mint_y)
+        }
+        Command::Pool(PoolCommand::Register { pool }) => commands::pool_register(&ctx, &pool),
+        Command::Pool(PoolCommand::Close { pool, recipient }) => {
+            commands::pool_close(&ctx, &pool, recipient)
+        }
+        Command::Pool(PoolCommand::SetLimits {
+            pool,
+            max_price_impact_bp,
+            max_trade_size_bp,
+        }) => commands::pool_set_limits(&ctx, &pool, max_price_impact_bp, max_trade_size_bp),
+        Command::Pool(PoolCommand::SetProtocolFee { pool, fee_bp }) => {
+            commands::pool_set_protocol_fee(&ctx, &pool, fee_bp)
+        }
+        Command::Pool(PoolCommand::SetFee { pool, fee_bp }) => {
+            commands::pool_set_fee(&ctx, &pool, fee_bp)
+        }
+        Command::Pool(PoolCommand::SetCreatorFee {
+            pool,
+            creator_fee_bp,
+        }) => commands::pool_set_creator_fee(&ctx, &pool, creator_fee_bp),
+        Command::Pool(PoolCommand::Migrate { pool }) => commands::pool_migrate(&ctx, &pool),
+        Command::Liquidity(LiquidityCommand::Add {
+            pool,
+            amount_a,
+            amount_b,
+            recipient,
+            trade,
+        }) => commands::liquidity_add(&ctx, &pool, amount_a, amount_b, recipient, &trade),
+        Command::Liquidity(LiquidityCommand::Remove {
+            pool,
+            lp_amount,
+            trade,
+        }) => commands::liquidity_remove(&ctx, &pool, lp_amount, &trade),
+        Command::Swap {
+            pool,
+            input_mint,
+            amount_in,
+            referrer,
+            referral_fee_bp,
+            recipient,
+            trade,
+        } => commands::swap(
+            &ctx,
+            &pool,
+            &input_mint,
+            amount_in,
+            referrer.zip(referral_fee_bp),
+            recipient,
+            &trade,
+        ),
+        Command::Fees(FeesCommand::Claim { pool }) => commands::fees_claim(&ctx, &pool),
+        Command::Fees(FeesCommand::ClaimCreator { pool }) => {
+            commands::fees_claim_creator(&ctx, &pool)
+        }
+        Command::Lock(LockCommand::Create {
+            pool,
+            amount,
+            duration,
+            id,
+        }) => commands::lock_create(&ctx, &pool, amount, duration.into(), id),
+        Command::Lock(LockCommand::Withdraw { pool, id }) => {
+            commands::lock_withdraw(&ctx, &pool, id)
+        }
+        Command::Lock(LockCommand::Show { pool, owner, id }) => {
+            commands::lock_show(&ctx, &pool, owner, id)
+        }
+    }
+}
@@ -0,0 +1,55 @@
+use anchor_lang::{AccountDeserialize, Result};
+use anchor_spl::token_interface::{Mint, TokenAccount};
+use liquidity_pools::state::{
+    Config, ConfigChange, LockPosition, PairIndex, PairIndexHeader, Pool, PoolCreator, PoolRegistry,
+};
+
+/// Decodes a `Config` account, checking its discriminator
+pub fn decode_config(data: &[u8]) -> Result<Config> {
+    Config::try_deserialize(&mut &data[..])
+}
+
+/// Decodes a `ConfigChange` account, checking its discriminator
+pub fn decode_config_change(data: &[u8]) -> Result<ConfigChange> {
+    ConfigChange::try_deserialize(&mut &data[..])
+}
+
+/// Decodes a `Pool` account, checking its discriminator
+pub fn decode_pool(data: &[u8]) -> Result<Pool> {
+    Pool::try_deserialize(&mut &data[..])
+}
+
+/// Decodes a `PoolCreator` allowlist entry, checking its discriminator
+pub fn decode_pool_creator(data: &[u8]) -> Result<PoolCreator> {
+    PoolCreator::try_deserialize(&mut &data[..])
+}
+
+/// Decodes a `PoolRegistry` page, checking its discriminator
+pub fn decode_registry(data: &[u8]) -> Result<PoolRegistry> {
+    PoolRegistry::try_deserialize(&mut &data[..])
+}
+
+/// Decodes a `PairIndexHeader` account, checking its discriminator
+pub fn decode_pair_index_header(data: &[u8]) -> Result<PairIndexHeader> {
+    PairIndexHeader::try_deserialize(&mut &data[..])
+}
+
+/// Decodes a `PairIndex` account, checking its discriminator
+pub fn decode_pair_index(data: &[u8]) -> Result<PairIndex> {
+    PairIndex::try_deserialize(&mut &data[..])
+}
+
+/// Decodes a `LockPosition` account, checking its discriminator
+pub fn decode_lock_position(data: &[u8]) -> Result<LockPosition> {
+    LockPosition::try_deserialize(&mut &data[..])
+}
+
+/// Decodes a Token or Token-2022 account (pool vaults and user ATAs)
+pub fn decode_token_account(data: &[u8]) -> Result<TokenAccount> {
+    TokenAccount::try_deserialize(&mut &data[..])
+}
+
+/// Decodes a Token or Token-2022 mint (LP mint supply)
+pub fn decode_mint(data: &[u8]) -> Result<Mint> {
+    Mint::try_deserialize(&mut &data[..])
+}
@@ -0,0 +1,1008 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::sysvar;
+use anchor_lang::{system_program, InstructionData, ToAccountMetas};
+use anchor_spl::associated_token;
+use anchor_spl::associated_token::spl_associated_token_account::instruction::create_associated_token_account_idempotent;
+use liquidity_pools::helpers::native_sol::is_native_mint;
+use liquidity_pools::state::{
+    Config, FeeRecipient, LockDuration, PairIndexHeader, Pool, PoolCreationMode, ProtocolFeeMode,
+};
+use liquidity_pools::{accounts, instruction};
+
+use crate::pda::{
+    find_config_address, find_config_change_address, find_lock_position_address,
+    find_pool_creator_address, find_registry_address, find_wsol_address, PoolKeys,
+};
+
+pub use liquidity_pools::types::SwapDirection;
+
+fn build(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+    Instruction {
+        program_id: liquidity_pools::ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// (token account, temporary wrapped SOL account) a side with `mint` moves through,
+/// the temporary account of `owner` when the side is paid in native SOL
+fn side_accounts(
+    native_sol: bool,
+    owner: &Pubkey,
+    mint: &Pubkey,
+    token_account: Pubkey,
+) -> (Option<Pubkey>, Option<Pubkey>) {
+    if native_sol && is_native_mint(mint) {
+        (None, Some(find_wsol_address(owner, mint).0))
+    } else {
+        (Some(token_account), None)
+    }
+}
+
+pub fn initialize(
+    authority: &Pubkey,
+    protocol_fee_recipient: Pubkey,
+    protocol_fee_bp: u16,
+) -> Instruction {
+    build(
+        accounts::Initialize {
+            authority: *authority,
+            config: find_config_address().0,
+            system_program: system_program::ID,
+        },
+        instruction::Initialize {
+            protocol_fee_recipient,
+            protocol_fee_bp,
+        },
+    )
+}
+
+/// Updates the config fields not behind the timelock, `None` leaves a field unchanged
+pub fn update_config(
+    authority: &Pubkey,
+    max_referral_fee_bp: Option<u16>,
+    min_pool_fee_bp: Option<u16>,
+    max_pool_fee_bp: Option<u16>,
+    pool_creation_fee: Option<u64>,
+    pool_creation_fee_mint: Option<Pubkey>,
+) -> Instruction {
+    build(
+        accounts::UpdateConfig {
+            authority: *authority,
+            config: find_config_address().0,
+            system_program: system_program::ID,
+        },
+        instruction::UpdateConfig {
+            max_referral_fee_bp,
+            min_pool_fee_bp,
+            max_pool_fee_bp,
+            pool_creation_fee,
+            pool_creation_fee_mint,
+        },
+    )
+}
+
+/// Queues a protocol fee change executable once `Config::timelock_delay` has
+/// passed, `None` leaves a field unchanged. A new `protocol_fee_recipient` takes
+/// all of the claimed fees, a new `fee_recipients` split makes its first entry
+/// the protocol fee recipient
+pub fn queue_config_change(
+    authority: &Pubkey,
+    protocol_fee_recipient: Option<Pubkey>,
+    protocol_fee_bp: Option<u16>,
+    timelock_delay: Option<i64>,
+    protocol_fee_mode: Option<ProtocolFeeMode>,
+    max_protocol_fee_bp_override: Option<u16>,
+    fee_recipients: Option<Vec<FeeRecipient>>,
+) -> Instruction {
+    build(
+        accounts::QueueConfigChange {
+            authority: *authority,
+            config: find_config_address().0,
+            config_change: find_config_change_address().0,
+            system_program: system_program::ID,
+        },
+        instruction::QueueConfigChange {
+            protocol_fee_recipient,
+            protocol_fee_bp,
+            timelock_delay,
+            protocol_fee_mode,
+            max_protocol_fee_bp_override,
+            fee_recipients,
+        },
+    )
+}
+
+pub fn execute_config_change(authority: &Pubkey) -> Instruction {
+    build(
+        accounts::ExecuteConfigChange {
+            authority: *authority,
+            config: find_config_address().0,
+            config_change: find_config_change_address().0,
+        },
+        instruction::ExecuteConfigChange {},
+    )
+}
+
+pub fn cancel_config_change(authority: &Pubkey) -> Instruction {
+    build(
+        accounts::CancelConfigChange {
+            authority: *authority,
+            config: find_config_address().0,
+            config_change: find_config_change_address().0,
+        },
+        instruction::CancelConfigChange {},
+    )
+}
+
+/// Assigns the operational roles of the config, `None` leaves a role unchanged
+pub fn update_roles(
+    authority: &Pubkey,
+    fee_manager: Option<Pubkey>,
+    pause_guardian: Option<Pubkey>,
+    pool_admin: Option<Pubkey>,
+    rewards_admin: Option<Pubkey>,
+    pool_creation_admin: Option<Pubkey>,
+) -> Instruction {
+    build(
+        accounts::UpdateRoles {
+            authority: *authority,
+            config: find_config_address().0,
+        },
+        instruction::UpdateRoles {
+            fee_manager,
+            pause_guardian,
+            pool_admin,
+            rewards_admin,
+            pool_creation_admin,
+        },
+    )
+}
+
+pub fn set_paused(pause_guardian: &Pubkey, paused: bool) -> Instruction {
+    build(
+        accounts::SetPaused {
+            authority: *pause_guardian,
+            config: find_config_address().0,
+        },
+        instruction::SetPaused { paused },
+    )
+}
+
+pub fn set_pool_creation_mode(
+    authority: &Pubkey,
+    pool_creation_mode: PoolCreationMode,
+) -> Instruction {
+    build(
+        accounts::SetPoolCreationMode {
+            authority: *authority,
+            config: find_config_address().0,
+        },
+        instruction::SetPoolCreationMode { pool_creation_mode },
+    )
+}
+
+/// Allows `creator` to create pools in allowlist mode
+pub fn add_pool_creator(authority: &Pubkey, creator: &Pubkey) -> Instruction {
+    build(
+        accounts::AddPoolCreator {
+            authority: *authority,
+            config: find_config_address().0,
+            pool_creator: find_pool_creator_address(creator).0,
+            system_program: system_program::ID,
+        },
+        instruction::AddPoolCreator { creator: *creator },
+    )
+}
+
+/// Removes `creator` from the allowlist, its rent goes back to the authority
+pub fn remove_pool_creator(authority: &Pubkey, creator: &Pubkey) -> Instruction {
+    build(
+        accounts::RemovePoolCreator {
+            authority: *authority,
+            config: find_config_address().0,
+            pool_creator: find_pool_creator_address(creator).0,
+        },
+        instruction::RemovePoolCreator {},
+    )
+}
+
+/// Recipients of the LP tokens minted for the protocol fee by `add_liquidity` and
+/// `remove_liquidity`, in fee split order. Empty while swaps collect the protocol fee.
+/// The program requires every recipient LP token account once a fee is pending,
+/// `create_recipient_lp_accounts` creates the missing ones
+pub fn protocol_fee_lp_recipients(config: &Config, pool: &Pool) -> Vec<Pubkey> {
+    if pool.mints_protocol_fee(config) {
+        fee_recipients(config)
+    } else {
+        Vec::new()
+    }
+}
+
+/// Creates the LP token accounts of `recipients` paid by `payer`, accounts that
+/// already exist are left as they are
+pub fn create_recipient_lp_accounts(
+    payer: &Pubkey,
+    keys: &PoolKeys,
+    recipients: &[Pubkey],
+) -> Vec<Instruction> {
+    recipients
+        .iter()
+        .map(|recipient| {
+            create_associated_token_account_idempotent(
+                payer,
+                recipient,
+                &keys.lp_mint,
+                &keys.token_program_lp,
+            )
+        })
+        .collect()
+}
+
+/// Accounts of `create_pool` that depend on the config
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CreatePoolOptions {
+    /// Page of the next registered pool, see `Config::registry_page`
+    pub registry_page: u32,
+    /// Pair index page of the next pool of the mints, see `PairIndexHeader::next_page`
+    pub pair_index_page: u16,
+    /// Passes the allowlist entry of the authority, needed by allowlisted
+    /// creators when pool creation is in allowlist mode
+    pub allowlisted: bool,
+    /// `Config::protocol_fee_recipient` when `Config::pool_creation_fee` is set
+    pub creation_fee_recipient: Option<Pubkey>,
+    /// Mint and token program of a creation fee paid in tokens, `None` for lamports
+    pub creation_fee_token: Option<(Pubkey, Pubkey)>,
+}
+
+impl CreatePoolOptions {
+    /// Options for `authority` under `config`, `creation_fee_token_program` owns
+    /// `Config::pool_creation_fee_mint` and is ignored for fees in lamports.
+    /// `pair_index_header` is None for mints without a registered pool
+    pub fn new(
+        config: &Config,
+        pair_index_header: Option<&PairIndexHeader>,
+        authority: &Pubkey,
+        creation_fee_token_program: Pubkey,
+    ) -> Self {
+        let charges_fee = config.pool_creation_fee > 0;
+        Self {
+            registry_page: config.registry_page(),
+            pair_index_page: pair_index_header.map_or(0, PairIndexHeader::next_page),
+            allowlisted: config.pool_creation_mode == PoolCreationMode::Allowlist
+                && *authority != config.authority,
+            creation_fee_recipient: charges_fee.then_some(config.protocol_fee_recipient),
+            creation_fee_token: (charges_fee && !config.charges_creation_fee_in_lamports())
+                .then_some((config.pool_creation_fee_mint, creation_fee_token_program)),
+        }
+    }
+}
+
+/// Creates the pool at `keys` (derived from its index) with a swap fee of `fee_bp`
+/// `registry_page` is the page of the next registered pool, see `Config::registry_page`,
+/// and `pair_index_page` the one of the pair, see `PairIndexHeader::next_page`
+pub fn create_pool(
+    authority: &Pubkey,
+    keys: &PoolKeys,
+    fee_bp: u16,
+    registry_page: u32,
+    pair_index_page: u16,
+) -> Instruction {
+    create_pool_with_options(
+        authority,
+        keys,
+        fee_bp,
+        &CreatePoolOptions {
+            registry_page,
+            pair_index_page,
+            ..Default::default()
+        },
+    )
+}
+
+/// `create_pool` with the allowlist entry and creation fee accounts of `options`
+pub fn create_pool_with_options(
+    authority: &Pubkey,
+    keys: &PoolKeys,
+    fee_bp: u16,
+    options: &CreatePoolOptions,
+) -> Instruction {
+    let creation_fee_ata = |owner: &Pubkey| {
+        options.creation_fee_token.map(|(mint, token_program)| {
+            associated_token::get_associated_token_address_with_program_id(
+                owner,
+                &mint,
+                &token_program,
+            )
+        })
+    };
+    let recipient_fee_ata = options
+        .creation_fee_recipient
+        .and_then(|recipient| creation_fee_ata(&recipient));
+
+    build(
+        accounts::CreatePool {
+            authority: *authority,
+            config: find_config_address().0,
+            pool_creator: options
+                .allowlisted
+                .then(|| find_pool_creator_address(authority).0),
+            pool: keys.pool,
+            token_mint_a: keys.token_mint_a,
+            token_vault_a: keys.token_vault_a,
+            token_mint_b: keys.token_mint_b,
+            token_vault_b: keys.token_vault_b,
+            lp_mint: keys.lp_mint,
+            registry: find_registry_address(options.registry_page).0,
+            pair_index_header: keys.pair_index_header(),
+            pair_index: keys.pair_index(options.pair_index_page),
+            token_program_a: keys.token_program_a,
+            token_program_b: keys.token_program_b,
+            token_program_lp: keys.token_program_lp,
+            system_program: system_program::ID,
+            associated_token_program: associated_token::ID,
+            protocol_fee_recipient: options
+                .creation_fee_recipient
+                .filter(|_| options.creation_fee_token.is_none()),
+            creation_fee_mint: options.creation_fee_token.map(|(mint, _)| mint),
+            authority_fee_ata: creation_fee_ata(authority),
+            recipient_fee_ata,
+            creation_fee_token_program: options
+                .creation_fee_token
+                .map(|(_, token_program)| token_program),
+        },
+        instruction::CreatePool {
+            index: keys.index,
+            fee_bp,
+        },
+    )
+}
+
+/// Callable by the pool creator or the config authority once the pool is empty
+/// `with_lock_vault` closes the LP lock vault as well, it only exists once a lock
+/// position was opened on the pool. `pair_index_page` is `Pool::pair_index_page`
+pub fn close_pool(
+    authority: &Pubkey,
+    keys: &PoolKeys,
+    recipient: &Pubkey,
+    with_lock_vault: bool,
+    pair_index_page: u16,
+) -> Instruction {
+    build(
+        accounts::ClosePool {
+            authority: *authority,
+            config: find_config_address().0,
+            pool: keys.pool,
+            token_mint_a: keys.token_mint_a,
+            pool_ata_a: keys.token_vault_a,
+            token_mint_b: keys.token_mint_b,
+            pool_ata_b: keys.token_vault_b,
+            lp_mint: keys.lp_mint,
+            lock_vault: with_lock_vault.then(|| keys.lock_vault()),
+            pair_index: keys.pair_index(pair_index_page),
+            recipient: *recipient,
+            token_program_a: keys.token_program_a,
+            token_program_b: keys.token_program_b,
+            token_program_lp: keys.token_program_lp,
+        },
+        instruction::ClosePool {},
+    )
+}
+
+/// `protocol_fee_recipients` receive the protocol fee when it is minted as LP
+/// tokens, see `protocol_fee_lp_recipients`
+#[allow(clippy::too_many_arguments)]
+pub fn add_liquidity(
+    keys: &PoolKeys,
+    provider: &Pubkey,
+    amount_a: u64,
+    amount_b: u64,
+    amount_a_min: u64,
+    amount_b_min: u64,
+    deadline: Option<i64>,
+    protocol_fee_recipients: &[Pubkey],
+) -> Instruction {
+    add_liquidity_to(
+        keys,
+        provider,
+        provider,
+        amount_a,
+        amount_b,
+        amount_a_min,
+        amount_b_min,
+        deadline,
+        protocol_fee_recipients,
+        false,
+    )
+}
+
+/// `add_liquidity` minting the LP tokens to the associated token account of
+/// `lp_recipient`, created by the provider when missing. With `native_sol` the
+/// native mint side is paid from the provider lamports through a temporary
+/// wrapped SOL account, see `find_wsol_address`
+#[allow(clippy::too_many_arguments)]
+pub fn add_liquidity_to(
+    keys: &PoolKeys,
+    provider: &Pubkey,
+    lp_recipient: &Pubkey,
+    amount_a: u64,
+    amount_b: u64,
+    amount_a_min: u64,
+    amount_b_min: u64,
+    deadline: Option<i64>,
+    protocol_fee_recipients: &[Pubkey],
+    native_sol: bool,
+) -> Instruction {
+    let (provider_ata_a, wsol_a) = side_accounts(
+        native_sol,
+        provider,
+        &keys.token_mint_a,
+        keys.user_ata_a(provider),
+    );
+    let (provider_ata_b, wsol_b) = side_accounts(
+        native_sol,
+        provider,
+        &keys.token_mint_b,
+        keys.user_ata_b(provider),
+    );
+    let mut instruction = build(
+        accounts::AddLiquidity {
+            provider: *provider,
+            provider_ata_a,
+            wsol_a,
+            provider_ata_b,
+            wsol_b,
+            lp_recipient: *lp_recipient,
+            lp_recipient_ata: keys.user_ata_lp(lp_recipient),
+            pool: keys.pool,
+            token_mint_a: keys.token_mint_a,
+            pool_ata_a: keys.token_vault_a,
+            token_mint_b: keys.token_mint_b,
+            pool_ata_b: keys.token_vault_b,
+            lp_mint: keys.lp_mint,
+            token_program_a: keys.token_program_a,
+            token_program_b: keys.token_program_b,
+            token_program_lp: keys.token_program_lp,
+            system_program: system_program::ID,
+            associated_token_program: associated_token::ID,
+            config: find_config_address().0,
+        },
+        instruction::AddLiquidity {
+            amount_a,
+            amount_b,
+            amount_a_min,
+            amount_b_min,
+            deadline,
+            native_sol,
+        },
+    );
+    instruction.accounts.extend(
+        protocol_fee_recipients
+            .iter()
+            .map(|recipient| AccountMeta::new(keys.user_ata_lp(recipient), false)),
+    );
+    instruction
+}
+
+/// `protocol_fee_recipients` receive the protocol fee when it is minted as LP
+/// tokens, see `protocol_fee_lp_recipients`. With `native_sol` the native mint
+/// side is paid out to the provider lamports through a temporary wrapped SOL
+/// account, see `find_wsol_address`
+#[allow(clippy::too_many_arguments)]
+pub fn remove_liquidity(
+    keys: &PoolKeys,
+    provider: &Pubkey,
+    lp_amount: u64,
+    amount_a_min: u64,
+    amount_b_min: u64,
+    deadline: Option<i64>,
+    protocol_fee_recipients: &[Pubkey],
+    native_sol: bool,
+) -> Instruction {
+    let (provider_ata_a, wsol_a) = side_accounts(
+        native_sol,
+        provider,
+        &keys.token_mint_a,
+        keys.user_ata_a(provider),
+    );
+    let (provider_ata_b, wsol_b) = side_accounts(
+        native_sol,
+        provider,
+        &keys.token_mint_b,
+        keys.user_ata_b(provider),
+    );
+    let mut instruction = build(
+        accounts::RemoveLiquidity {
+            provider: *provider,
+            provider_ata_a,
+            wsol_a,
+            provider_ata_b,
+            wsol_b,
+            provider_ata_lp: keys.user_ata_lp(provider),
+            pool: keys.pool,
+            token_mint_a: keys.token_mint_a,
+            pool_ata_a: keys.token_vault_a,
+            token_mint_b: keys.token_mint_b,
+            pool_ata_b: keys.token_vault_b,
+            lp_mint: keys.lp_mint,
+            token_program_a: keys.token_program_a,
+            token_program_b: keys.token_program_b,
+            token_program_lp: keys.token_program_lp,
+            system_program: system_program::ID,
+            associated_token_program: associated_token::ID,
+            config: find_config_address().0,
+        },
+        instruction::RemoveLiquidity {
+            lp_amount,
+            amount_a_min,
+            amount_b_min,
+            deadline,
+            native_sol,
+        },
+    );
+    instruction.accounts.extend(
+        protocol_fee_recipients
+            .iter()
+            .map(|recipient| AccountMeta::new(keys.user_ata_lp(recipient), false)),
+    );
+    instruction
+}
+
+pub fn swap(
+    keys: &PoolKeys,
+    payer: &Pubkey,
+    direction: SwapDirection,
+    amount_in: u64,
+    min_amount_out: u64,
+    deadline: Option<i64>,
+) -> Instruction {
+    swap_with_referrer(
+        keys,
+        payer,
+        direction,
+        amount_in,
+        min_amount_out,
+        deadline,
+        None,
+    )
+}
+
+/// Swap paying `referral_fee_bp` of the swap fee to the `referrer_ata` token
+/// account (for the input mint), capped by `Config::max_referral_fee_bp`
+pub fn swap_with_referrer(
+    keys: &PoolKeys,
+    payer: &Pubkey,
+    direction: SwapDirection,
+    amount_in: u64,
+    min_amount_out: u64,
+    deadline: Option<i64>,
+    referrer: Option<(Pubkey, u16)>,
+) -> Instruction {
+    swap_to(
+        keys,
+        payer,
+        payer,
+        direction,
+        amount_in,
+        min_amount_out,
+        deadline,
+        referrer,
+        false,
+    )
+}
+
+/// `swap_with_referrer` paying the output to the associated token account of
+/// `recipient`, created by the payer when missing. With `native_sol` a native
+/// mint input is paid from the payer lamports and a native mint output is paid
+/// out to them, which needs `recipient` to be the payer. Either goes through a
+/// temporary wrapped SOL account, see `find_wsol_address`
+#[allow(clippy::too_many_arguments)]
+pub fn swap_to(
+    keys: &PoolKeys,
+    payer: &Pubkey,
+    recipient: &Pubkey,
+    direction: SwapDirection,
+    amount_in: u64,
+    min_amount_out: u64,
+    deadline: Option<i64>,
+    referrer: Option<(Pubkey, u16)>,
+    native_sol: bool,
+) -> Instruction {
+    let (mint_input, vault_input, program_input, mint_output, vault_output, program_output) =
+        match direction {
+            SwapDirection::AToB => (
+                keys.token_mint_a,
+                keys.token_vault_a,
+                keys.token_program_a,
+                keys.token_mint_b,
+                keys.token_vault_b,
+                keys.token_program_b,
+            ),
+            SwapDirection::BToA => (
+                keys.token_mint_b,
+                keys.token_vault_b,
+                keys.token_program_b,
+                keys.token_mint_a,
+                keys.token_vault_a,
+                keys.token_program_a,
+            ),
+        };
+    let (payer_ata_input, wsol_input) = side_accounts(
+        native_sol,
+        payer,
+        &mint_input,
+        associated_token::get_associated_token_address_with_program_id(
+            payer,
+            &mint_input,
+            &program_input,
+        ),
+    );
+    let (recipient_ata_output, wsol_output) = side_accounts(
+        native_sol,
+        payer,
+        &mint_output,
+        associated_token::get_associated_token_address_with_program_id(
+            recipient,
+            &mint_output,
+            &program_output,
+        ),
+    );
+
+    build(
+        accounts::Swap {
+            payer: *payer,
+            pool: keys.pool,
+            payer_ata_input,
+            recipient: *recipient,
+            recipient_ata_output,
+            wsol_input,
+            wsol_output,
+            pool_ata_input: vault_input,
+            pool_ata_output: vault_output,
+            token_mint_input: mint_input,
+            token_mint_output: mint_output,
+            token_program_input: program_input,
+            token_program_output: program_output,
+            token_program_lp: keys.token_program_lp,
+            system_program: system_program::ID,
+            associated_token_program: associated_token::ID,
+            config: find_config_address().0,
+            referrer_ata: referrer.map(|(referrer_ata, _)| referrer_ata),
+        },
+        instruction::Swap {
+            amount_in,
+            min_amount_out,
+            deadline,
+            referral_fee_bp: referrer.map_or(0, |(_, referral_fee_bp)| referral_fee_bp),
+            native_sol,
+        },
+    )
+}
+
+/// Must be followed by a `flash_repay` for the same pool in the same transaction
+pub fn flash_loan(keys: &PoolKeys, borrower: &Pubkey, amount_a: u64, amount_b: u64) -> Instruction {
+    build(
+        accounts::FlashLoan {
+            borrower: *borrower,
+            pool: keys.pool,
+            token_mint_a: keys.token_mint_a,
+            pool_ata_a: keys.token_vault_a,
+            token_mint_b: keys.token_mint_b,
+            pool_ata_b: keys.token_vault_b,
+            borrower_ata_a: keys.user_ata_a(borrower),
+            borrower_ata_b: keys.user_ata_b(borrower),
+            config: find_config_address().0,
+            instructions_sysvar: sysvar::instructions::ID,
+            token_program_a: keys.token_program_a,
+            token_program_b: keys.token_program_b,
+            system_program: system_program::ID,
+            associated_token_program: associated_token::ID,
+        },
+        instruction::FlashLoan { amount_a, amount_b },
+    )
+}
+
+pub fn flash_repay(keys: &PoolKeys, payer: &Pubkey, amount_a: u64, amount_b: u64) -> Instruction {
+    build(
+        accounts::FlashRepay {
+            payer: *payer,
+            pool: keys.pool,
+            token_mint_a: keys.token_mint_a,
+            pool_ata_a: keys.token_vault_a,
+            token_mint_b: keys.token_mint_b,
+            pool_ata_b: keys.token_vault_b,
+            payer_ata_a: keys.user_ata_a(payer),
+            payer_ata_b: keys.user_ata_b(payer),
+            config: find_config_address().0,
+            token_program_a: keys.token_program_a,
+            token_program_b: keys.token_program_b,
+            system_program: system_program::ID,
+            associated_token_program: associated_token::ID,
+        },
+        instruction::FlashRepay { amount_a, amount_b },
+    )
+}
+
+fn quote_accounts(keys: &PoolKeys) -> accounts::Quote {
+    accounts::Quote {
+        pool: keys.pool,
+        pool_ata_a: keys.token_vault_a,
+        pool_ata_b: keys.token_vault_b,
+        lp_mint: keys.lp_mint,
+        config: find_config_address().0,
+    }
+}
+
+/// Read-only, simulate it and decode the return data as a `SwapQuote`
+pub fn quote_swap(keys: &PoolKeys, amount_in: u64, direction: SwapDirection) -> Instruction {
+    build(
+        quote_accounts(keys),
+        instruction::QuoteSwap {
+            amount_in,
+            direction,
+        },
+    )
+}
+
+/// Read-only, simulate it and decode the return data as a `SwapQuote`
+pub fn quote_swap_exact_out(
+    keys: &PoolKeys,
+    amount_out: u64,
+    direction: SwapDirection,
+) -> Instruction {
+    build(
+        quote_accounts(keys),
+        instruction::QuoteSwapExactOut {
+            amount_out,
+            direction,
+        },
+    )
+}
+
+/// Read-only, simulate it and decode the return data as an `AddLiquidityQuote`
+pub fn quote_add_liquidity(keys: &PoolKeys, amount_a: u64, amount_b: u64) -> Instruction {
+    build(
+        quote_accounts(keys),
+        instruction::QuoteAddLiquidity { amount_a, amount_b },
+    )
+}
+
+/// Read-only, simulate it and decode the return data as a `RemoveLiquidityQuote`
+pub fn quote_remove_liquidity(keys: &PoolKeys, lp_amount: u64) -> Instruction {
+    build(
+        quote_accounts(keys),
+        instruction::QuoteRemoveLiquidity { lp_amount },
+    )
+}
+
+/// Read-only, simulate it and decode the return data as a `PoolState`
+pub fn get_pool_state(keys: &PoolKeys) -> Instruction {
+    build(quote_accounts(keys), instruction::GetPoolState {})
+}
+
+/// Splits the protocol fees accrued by the pool between the associated token
+/// accounts of `fee_recipients`, given in the order of `Config::fee_recipients`
+pub fn claim_fees(keys: &PoolKeys, fee_recipients: &[Pubkey]) -> Instruction {
+    let mut instruction = build(
+        accounts::ClaimFees {
+            config: find_config_address().0,
+            pool: keys.pool,
+            token_mint_a: keys.token_mint_a,
+            pool_ata_a: keys.token_vault_a,
+            token_mint_b: keys.token_mint_b,
+            pool_ata_b: keys.token_vault_b,
+            token_program_a: keys.token_program_a,
+            token_program_b: keys.token_program_b,
+        },
+        instruction::ClaimFees {},
+    );
+    for recipient in fee_recipients {
+        instruction.accounts.extend([
+            AccountMeta::new(keys.user_ata_a(recipient), false),
+            AccountMeta::new(keys.user_ata_b(recipient), false),
+        ]);
+    }
+    instruction
+}
+
+/// Recipients of `claim_fees`, in the order the program expects their accounts
+pub fn fee_recipients(config: &Config) -> Vec<Pubkey> {
+    config
+        .active_fee_recipients()
+        .iter()
+        .map(|entry| entry.recipient)
+        .collect()
+}
+
+/// Sets the highest creator fee pools can take, 0 disables creator fees
+pub fn set_max_creator_fee(authority: &Pubkey, max_creator_fee_bp: u16) -> Instruction {
+    build(
+        accounts::SetMaxCreatorFee {
+            authority: *authority,
+            config: find_config_address().0,
+        },
+        instruction::SetMaxCreatorFee { max_creator_fee_bp },
+    )
+}
+
+/// Sets the share of the swap fee accrued to the creator of `pool`
+pub fn set_creator_fee(creator: &Pubkey, pool: &Pubkey, creator_fee_bp: u16) -> Instruction {
+    build(
+        accounts::SetCreatorFee {
+            creator: *creator,
+            config: find_config_address().0,
+            pool: *pool,
+        },
+        instruction::SetCreatorFee { creator_fee_bp },
+    )
+}
+
+/// Sends the creator fees accrued by the pool to the associated token accounts
+/// of its creator
+pub fn claim_creator_fees(keys: &PoolKeys, creator: &Pubkey) -> Instruction {
+    build(
+        accounts::ClaimCreatorFees {
+            creator: *creator,
+            pool: keys.pool,
+            token_mint_a: keys.token_mint_a,
+            pool_ata_a: keys.token_vault_a,
+            token_mint_b: keys.token_mint_b,
+            pool_ata_b: keys.token_vault_b,
+            creator_ata_a: keys.user_ata_a(creator),
+            creator_ata_b: keys.user_ata_b(creator),
+            token_program_a: keys.token_program_a,
+            token_program_b: keys.token_program_b,
+        },
+        instruction::ClaimCreatorFees {},
+    )
+}
+
+/// Sets the swap limits of `pool`, `None` leaves a limit unchanged and 0 disables it.
+/// `Some(None)` removes the protocol fee override of the pool
+pub fn update_pool(
+    authority: &Pubkey,
+    pool: &Pubkey,
+    max_price_impact_bp: Option<u16>,
+    max_trade_size_bp: Option<u16>,
+    protocol_fee_bp_override: Option<Option<u16>>,
+    fee_bp: Option<u16>,
+) -> Instruction {
+    build(
+        accounts::UpdatePool {
+            authority: *authority,
+            config: find_config_address().0,
+            pool: *pool,
+        },
+        instruction::UpdatePool {
+            max_price_impact_bp,
+            max_trade_size_bp,
+            protocol_fee_bp_override,
+            fee_bp,
+        },
+    )
+}
+
+/// Moves the config written by an older layout to the current version
+pub fn migrate_config(authority: &Pubkey) -> Instruction {
+    build(
+        accounts::MigrateConfig {
+            authority: *authority,
+            config: find_config_address().0,
+            system_program: system_program::ID,
+        },
+        instruction::MigrateConfig {},
+    )
+}
+
+/// Moves a pool written by an older layout to the current version
+pub fn migrate_pool(authority: &Pubkey, pool: &Pubkey) -> Instruction {
+    build(
+        accounts::MigratePool {
+            authority: *authority,
+            config: find_config_address().0,
+            pool: *pool,
+            system_program: system_program::ID,
+        },
+        instruction::MigratePool {},
+    )
+}
+
+/// Adds a pool created before the registry, callable by anyone
+/// `registry_page` and `pair_index_page` are the pages of the next registered pool,
+/// see `Config::registry_page` and `PairIndexHeader::next_page`
+pub fn register_pool(
+    payer: &Pubkey,
+    keys: &PoolKeys,
+    registry_page: u32,
+    pair_index_page: u16,
+) -> Instruction {
+    build(
+        accounts::RegisterPool {
+            payer: *payer,
+            config: find_config_address().0,
+            pool: keys.pool,
+            registry: find_registry_address(registry_page).0,
+            pair_index_header: keys.pair_index_header(),
+            pair_index: keys.pair_index(pair_index_page),
+            system_program: system_program::ID,
+        },
+        instruction::RegisterPool {},
+    )
+}
+
+/// Locks `amount` LP tokens of `owner` in position `id` for `duration`
+pub fn lock_lp(
+    keys: &PoolKeys,
+    owner: &Pubkey,
+    id: u64,
+    amount: u64,
+    duration: LockDuration,
+) -> Instruction {
+    build(
+        accounts::LockLp {
+            owner: *owner,
+            owner_ata_lp: keys.user_ata_lp(owner),
+            config: find_config_address().0,
+            pool: keys.pool,
+            lp_mint: keys.lp_mint,
+            lock_vault: keys.lock_vault(),
+            lock_position: find_lock_position_address(&keys.pool, owner, id).0,
+            token_program_lp: keys.token_program_lp,
+            system_program: system_program::ID,
+            associated_token_program: associated_token::ID,
+        },
+        instruction::LockLp {
+            id,
+            amount,
+            duration,
+        },
+    )
+}
+
+/// Withdraws lock position `id` of `owner` and closes it. Exiting before the
+/// position unlocks splits the early exit penalty between the LP token accounts of
+/// `fee_recipients`, given in the order of `Config::fee_recipients`
+pub fn unlock_lp(
+    keys: &PoolKeys,
+    owner: &Pubkey,
+    id: u64,
+    fee_recipients: &[Pubkey],
+) -> Instruction {
+    let mut instruction = build(
+        accounts::UnlockLp {
+            owner: *owner,
+            owner_ata_lp: keys.user_ata_lp(owner),
+            config: find_config_address().0,
+            pool: keys.pool,
+            lp_mint: keys.lp_mint,
+            lock_vault: keys.lock_vault(),
+            lock_position: find_lock_position_address(&keys.pool, owner, id).0,
+            token_program_lp: keys.token_program_lp,
+        },
+        instruction::UnlockLp {},
+    );
+    instruction.accounts.extend(
+        fee_recipients
+            .iter()
+            .map(|recipient| AccountMeta::new(keys.user_ata_lp(recipient), false)),
+    );
+    instruction
+}
+
+/// Sets the share of the LP tokens paid to unlock a position early, 0 disables early exits
+pub fn set_early_exit_penalty(authority: &Pubkey, early_exit_penalty_bp: u16) -> Instruction {
+    build(
+        accounts::SetEarlyExitPenalty {
+            authority: *authority,
+            config: find_config_address().0,
+        },
+        instruction::SetEarlyExitPenalty {
+            early_exit_penalty_bp,
+        },
+    )
+}
+
+pub fn claim_rewards() -> Instruction {
+    build(accounts::ClaimRewards {}, instruction::ClaimRewards {})
+}
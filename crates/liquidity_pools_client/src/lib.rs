@@ -0,0 +1,15 @@
+//! Off-chain client for the liquidity_pools program
+//!
+//! PDA derivation, instruction builders, account decoding and quotes
+//! that mirror the on-chain math.
+
+pub mod accounts;
+pub mod instructions;
+pub mod pda;
+pub mod quote;
+
+pub use liquidity_pools::state::{
+    Config, ConfigChange, FeeRecipient, LockDuration, LockPosition, PairIndex, PairIndexHeader,
+    Pool, PoolCreationMode, PoolCreator, PoolRegistry, ProtocolFeeMode,
+};
+pub use liquidity_pools::ID as PROGRAM_ID;
@@ -0,0 +1,205 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_spl::associated_token::get_associated_token_address_with_program_id;
+use liquidity_pools::helpers::native_sol::WSOL_SEED;
+use liquidity_pools::state::{
+    Config, ConfigChange, LockPosition, PairIndex, PairIndexHeader, Pool, PoolCreator, PoolRegistry,
+};
+
+pub fn find_config_address() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[Config::SEED], &liquidity_pools::ID)
+}
+
+pub fn find_config_change_address() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[ConfigChange::SEED], &liquidity_pools::ID)
+}
+
+pub fn find_pool_address(token_mint_a: &Pubkey, token_mint_b: &Pubkey, index: u16) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            Pool::SEED,
+            token_mint_a.as_ref(),
+            token_mint_b.as_ref(),
+            index.to_le_bytes().as_ref(),
+        ],
+        &liquidity_pools::ID,
+    )
+}
+
+pub fn find_lp_mint_address(
+    token_mint_a: &Pubkey,
+    token_mint_b: &Pubkey,
+    index: u16,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            Pool::MINT_SEED,
+            token_mint_a.as_ref(),
+            token_mint_b.as_ref(),
+            index.to_le_bytes().as_ref(),
+        ],
+        &liquidity_pools::ID,
+    )
+}
+
+pub fn find_pool_creator_address(creator: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[PoolCreator::SEED, creator.as_ref()], &liquidity_pools::ID)
+}
+
+pub fn find_registry_address(page: u32) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[PoolRegistry::SEED, page.to_le_bytes().as_ref()],
+        &liquidity_pools::ID,
+    )
+}
+
+/// Header of the pair index of two sorted mints, holding its page count
+pub fn find_pair_index_header_address(
+    token_mint_a: &Pubkey,
+    token_mint_b: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            PairIndexHeader::SEED,
+            token_mint_a.as_ref(),
+            token_mint_b.as_ref(),
+        ],
+        &liquidity_pools::ID,
+    )
+}
+
+/// Page `page` of the pair index of two sorted mints
+pub fn find_pair_index_address(
+    token_mint_a: &Pubkey,
+    token_mint_b: &Pubkey,
+    page: u16,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            PairIndex::SEED,
+            token_mint_a.as_ref(),
+            token_mint_b.as_ref(),
+            page.to_le_bytes().as_ref(),
+        ],
+        &liquidity_pools::ID,
+    )
+}
+
+/// Lock position `id` of `owner` in `pool`
+pub fn find_lock_position_address(pool: &Pubkey, owner: &Pubkey, id: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            LockPosition::SEED,
+            pool.as_ref(),
+            owner.as_ref(),
+            id.to_le_bytes().as_ref(),
+        ],
+        &liquidity_pools::ID,
+    )
+}
+
+/// Temporary wrapped SOL account the native SOL of `owner` moves through when an
+/// instruction is called with `native_sol`
+pub fn find_wsol_address(owner: &Pubkey, native_mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[WSOL_SEED, owner.as_ref(), native_mint.as_ref()],
+        &liquidity_pools::ID,
+    )
+}
+
+/// Orders two mints the way `create_pool` expects them (token A is the larger key)
+pub fn sort_mints(mint_x: Pubkey, mint_y: Pubkey) -> (Pubkey, Pubkey) {
+    if mint_x > mint_y {
+        (mint_x, mint_y)
+    } else {
+        (mint_y, mint_x)
+    }
+}
+
+/// Every address tied to a pool, derived from its mints, index and token programs
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PoolKeys {
+    pub pool: Pubkey,
+    pub lp_mint: Pubkey,
+    pub token_mint_a: Pubkey,
+    pub token_mint_b: Pubkey,
+    pub token_vault_a: Pubkey,
+    pub token_vault_b: Pubkey,
+    pub token_program_a: Pubkey,
+    pub token_program_b: Pubkey,
+    pub token_program_lp: Pubkey,
+    pub index: u16,
+}
+
+impl PoolKeys {
+    pub fn new(
+        token_mint_a: Pubkey,
+        token_mint_b: Pubkey,
+        index: u16,
+        token_program_a: Pubkey,
+        token_program_b: Pubkey,
+        token_program_lp: Pubkey,
+    ) -> Self {
+        let (pool, _) = find_pool_address(&token_mint_a, &token_mint_b, index);
+        let (lp_mint, _) = find_lp_mint_address(&token_mint_a, &token_mint_b, index);
+
+        Self {
+            pool,
+            lp_mint,
+            token_mint_a,
+            token_mint_b,
+            token_vault_a: get_associated_token_address_with_program_id(
+                &pool,
+                &token_mint_a,
+                &token_program_a,
+            ),
+            token_vault_b: get_associated_token_address_with_program_id(
+                &pool,
+                &token_mint_b,
+                &token_program_b,
+            ),
+            token_program_a,
+            token_program_b,
+            token_program_lp,
+            index,
+        }
+    }
+
+    /// Associated token account of `owner` for token A
+    pub fn user_ata_a(&self, owner: &Pubkey) -> Pubkey {
+        get_associated_token_address_with_program_id(
+            owner,
+            &self.token_mint_a,
+            &self.token_program_a,
+        )
+    }
+
+    /// Associated token account of `owner` for token B
+    pub fn user_ata_b(&self, owner: &Pubkey) -> Pubkey {
+        get_associated_token_address_with_program_id(
+            owner,
+            &self.token_mint_b,
+            &self.token_program_b,
+        )
+    }
+
+    /// Associated token account of `owner` for the LP mint
+    pub fn user_ata_lp(&self, owner: &Pubkey) -> Pubkey {
+        get_associated_token_address_with_program_id(owner, &self.lp_mint, &self.token_program_lp)
+    }
+
+    /// Pair index header of the pool mints
+    pub fn pair_index_header(&self) -> Pubkey {
+        find_pair_index_header_address(&self.token_mint_a, &self.token_mint_b).0
+    }
+
+    /// Page `page` of the pair index of the pool mints, `Pool::pair_index_page`
+    /// once the pool is registered
+    pub fn pair_index(&self, page: u16) -> Pubkey {
+        find_pair_index_address(&self.token_mint_a, &self.token_mint_b, page).0
+    }
+
+    /// LP token account of the pool holding every lock position
+    pub fn lock_vault(&self) -> Pubkey {
+        self.user_ata_lp(&self.pool)
+    }
+}
@@ -0,0 +1,109 @@
+//! Quotes computed with the same math as the on-chain handlers, given the
+//! current vault balances (reserves) and LP mint supply
+
+use anchor_lang::{AnchorDeserialize, Result};
+use liquidity_pools::error::ErrorCode;
+use liquidity_pools::helpers::limits::check_trade_limits;
+use liquidity_pools::helpers::protocol_fee::pending_protocol_lp_fee;
+use liquidity_pools::math;
+use liquidity_pools::state::{Config, Pool};
+
+pub use liquidity_pools::types::{AddLiquidityQuote, PoolState, RemoveLiquidityQuote, SwapQuote};
+
+/// Decodes the return data of a simulated quote instruction
+/// (`instructions::quote_swap` and friends)
+pub fn decode_return_data<T: AnchorDeserialize>(data: &[u8]) -> Result<T> {
+    Ok(T::try_from_slice(data)?)
+}
+
+/// Mirrors `instructions::swap` on `pool`, including its trade limits
+pub fn quote_swap(
+    pool: &Pool,
+    amount_in: u64,
+    reserve_in: u64,
+    reserve_out: u64,
+) -> Result<SwapQuote> {
+    let amounts = math::get_amount_out(amount_in, reserve_in, reserve_out, pool.fee_bp)
+        .map_err(ErrorCode::from)?;
+    check_trade_limits(
+        pool,
+        amounts.amount_in,
+        amounts.amount_out,
+        reserve_in,
+        reserve_out,
+    )?;
+    Ok(amounts.into())
+}
+
+/// Smallest input for which `quote_swap` returns at least `amount_out`
+pub fn quote_swap_exact_out(
+    pool: &Pool,
+    amount_out: u64,
+    reserve_in: u64,
+    reserve_out: u64,
+) -> Result<SwapQuote> {
+    let amounts = math::get_amount_in(amount_out, reserve_in, reserve_out, pool.fee_bp)
+        .map_err(ErrorCode::from)?;
+    check_trade_limits(
+        pool,
+        amounts.amount_in,
+        amounts.amount_out,
+        reserve_in,
+        reserve_out,
+    )?;
+    Ok(amounts.into())
+}
+
+/// Mirrors `instructions::add_liquidity`: amounts actually transferred and LP minted
+pub fn quote_add_liquidity(
+    amount_a_desired: u64,
+    amount_b_desired: u64,
+    reserve_a: u64,
+    reserve_b: u64,
+    lp_supply: u64,
+) -> Result<AddLiquidityQuote> {
+    let (amount_a, amount_b) =
+        math::quote_deposit(amount_a_desired, amount_b_desired, reserve_a, reserve_b)
+            .map_err(ErrorCode::from)?;
+    let lp_amount = math::lp_to_mint(amount_a, amount_b, reserve_a, reserve_b, lp_supply)
+        .map_err(ErrorCode::from)?;
+
+    Ok(AddLiquidityQuote {
+        amount_a,
+        amount_b,
+        lp_amount,
+    })
+}
+
+/// Mirrors `instructions::remove_liquidity`
+pub fn quote_remove_liquidity(
+    lp_amount: u64,
+    reserve_a: u64,
+    reserve_b: u64,
+    lp_supply: u64,
+) -> Result<RemoveLiquidityQuote> {
+    let (amount_a, amount_b) =
+        math::lp_to_burn(lp_amount, reserve_a, reserve_b, lp_supply).map_err(ErrorCode::from)?;
+
+    Ok(RemoveLiquidityQuote { amount_a, amount_b })
+}
+
+/// LP supply seen by `add_liquidity` and `remove_liquidity` once they minted the
+/// protocol fee, pass it to `quote_add_liquidity` and `quote_remove_liquidity`
+pub fn lp_supply_after_protocol_fee(
+    config: &Config,
+    pool: &Pool,
+    reserve_a: u64,
+    reserve_b: u64,
+    lp_supply: u64,
+) -> Result<u64> {
+    let protocol_lp_fee = pending_protocol_lp_fee(config, pool, lp_supply, reserve_a, reserve_b)?;
+    Ok(lp_supply
+        .checked_add(protocol_lp_fee)
+        .ok_or(ErrorCode::Overflow)?)
+}
+
+/// Fee owed on top of a flash loan of `amount`, mirrors `instructions::flash_repay`
+pub fn flash_loan_fee(amount: u64, fee_bp: u16) -> Result<u64> {
+    Ok(math::calculate_fee(amount as u128, fee_bp).map_err(ErrorCode::from)? as u64)
+}
@@ -0,0 +1,332 @@
+//! Pool math shared by the liquidity_pools program and its clients
+//!
+//! Pure integer arithmetic with no Anchor or runtime dependency. Every
+//! rounding step favors the pool: amounts paid out round down, amounts
+//! owed to the pool round up.
+
+// Represents 100%
+pub const FEE_DENOMINATOR: u128 = 10000;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MathError {
+    Overflow,
+    Underflow,
+    DivisionByZero,
+    // Pool has no liquidity
+    EmptyPool,
+    // Trade would drain or exceed the pool reserves
+    InsufficientLiquidity,
+}
+
+pub type MathResult<T> = Result<T, MathError>;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SwapAmounts {
+    // Gross amount paid in, fee included
+    pub amount_in: u64,
+    pub amount_out: u64,
+    pub fee_amount: u64,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FeeSplit {
+    // Share carved out of the fee (protocol, referrer, ...)
+    pub share: u64,
+    // What is left of the fee for liquidity providers
+    pub remainder: u64,
+}
+
+fn to_u64(value: u128) -> MathResult<u64> {
+    u64::try_from(value).map_err(|_| MathError::Overflow)
+}
+
+fn mul_div_floor(a: u128, b: u128, denominator: u128) -> MathResult<u128> {
+    if denominator == 0 {
+        return Err(MathError::DivisionByZero);
+    }
+
+    Ok(a.checked_mul(b).ok_or(MathError::Overflow)? / denominator)
+}
+
+fn mul_div_ceil(a: u128, b: u128, denominator: u128) -> MathResult<u128> {
+    if denominator == 0 {
+        return Err(MathError::DivisionByZero);
+    }
+
+    Ok(a.checked_mul(b)
+        .ok_or(MathError::Overflow)?
+        .div_ceil(denominator))
+}
+
+/// Integer square root rounded down, same digit-by-digit algorithm as spl-math `approximations::sqrt`
+pub fn sqrt(radicand: u128) -> u128 {
+    if radicand == 0 {
+        return 0;
+    }
+
+    // Largest power of 4 <= radicand
+    let shift = (127 - radicand.leading_zeros()) & !1;
+    let mut bit = 1u128 << shift;
+
+    let mut n = radicand;
+    let mut result = 0u128;
+    while bit != 0 {
+        let result_with_bit = result + bit;
+        if n >= result_with_bit {
+            n -= result_with_bit;
+            result = (result >> 1) + bit;
+        } else {
+            result >>= 1;
+        }
+        bit >>= 2;
+    }
+
+    result
+}
+
+/// ceil((amount * fee_bp) / 10000)
+pub fn calculate_fee(amount: u128, fee_bp: u16) -> MathResult<u128> {
+    mul_div_ceil(amount, fee_bp as u128, FEE_DENOMINATOR)
+}
+
+/// Splits `fee_amount` into floor((fee_amount * share_bp) / 10000) and the remainder
+pub fn split_fee(fee_amount: u64, share_bp: u16) -> MathResult<FeeSplit> {
+    if share_bp as u128 > FEE_DENOMINATOR {
+        return Err(MathError::Overflow);
+    }
+
+    let share = to_u64(mul_div_floor(
+        fee_amount as u128,
+        share_bp as u128,
+        FEE_DENOMINATOR,
+    )?)?;
+
+    Ok(FeeSplit {
+        share,
+        remainder: fee_amount - share,
+    })
+}
+
+/// Exact input swap
+/// amount_out = floor((amount_in_after_fee * reserve_out) / (reserve_in + amount_in_after_fee))
+pub fn get_amount_out(
+    amount_in: u64,
+    reserve_in: u64,
+    reserve_out: u64,
+    fee_bp: u16,
+) -> MathResult<SwapAmounts> {
+    if reserve_in == 0 || reserve_out == 0 {
+        return Err(MathError::EmptyPool);
+    }
+
+    let fee_amount = to_u64(calculate_fee(amount_in as u128, fee_bp)?)?;
+    let amount_in_after_fee = amount_in
+        .checked_sub(fee_amount)
+        .ok_or(MathError::Underflow)?;
+
+    let amount_out = to_u64(mul_div_floor(
+        amount_in_after_fee as u128,
+        reserve_out as u128,
+        reserve_in as u128 + amount_in_after_fee as u128,
+    )?)?;
+
+    // Never drain the pool
+    if amount_out >= reserve_out {
+        return Err(MathError::InsufficientLiquidity);
+    }
+
+    Ok(SwapAmounts {
+        amount_in,
+        amount_out,
+        fee_amount,
+    })
+}
+
+/// Exact output swap, smallest `amount_in` for which `get_amount_out` returns at least `amount_out`
+/// amount_in_after_fee = ceil((amount_out * reserve_in) / (reserve_out - amount_out))
+/// amount_in = ceil((amount_in_after_fee * 10000) / (10000 - fee_bp))
+pub fn get_amount_in(
+    amount_out: u64,
+    reserve_in: u64,
+    reserve_out: u64,
+    fee_bp: u16,
+) -> MathResult<SwapAmounts> {
+    if reserve_in == 0 || reserve_out == 0 {
+        return Err(MathError::EmptyPool);
+    }
+    if amount_out >= reserve_out {
+        return Err(MathError::InsufficientLiquidity);
+    }
+    if fee_bp as u128 >= FEE_DENOMINATOR {
+        return Err(MathError::Overflow);
+    }
+
+    let amount_in_after_fee = mul_div_ceil(
+        amount_out as u128,
+        reserve_in as u128,
+        (reserve_out - amount_out) as u128,
+    )?;
+    let amount_in = to_u64(mul_div_ceil(
+        amount_in_after_fee,
+        FEE_DENOMINATOR,
+        FEE_DENOMINATOR - fee_bp as u128,
+    )?)?;
+
+    get_amount_out(amount_in, reserve_in, reserve_out, fee_bp)
+}
+
+/// Move of the spot price (reserve_out / reserve_in) against the trader caused by a swap, in bp
+/// impact = 1 - (reserve_out_after * reserve_in) / (reserve_out * reserve_in_after), rounded up
+pub fn price_impact_bp(
+    amount_in: u64,
+    amount_out: u64,
+    reserve_in: u64,
+    reserve_out: u64,
+) -> MathResult<u16> {
+    if reserve_in == 0 || reserve_out == 0 {
+        return Err(MathError::EmptyPool);
+    }
+    if amount_out > reserve_out {
+        return Err(MathError::InsufficientLiquidity);
+    }
+
+    let mut reserve_in = reserve_in as u128;
+    let mut reserve_in_after = reserve_in + amount_in as u128;
+    let reserve_out_after = (reserve_out - amount_out) as u128;
+
+    // reserve_in_after can reach 2^65 - 2, halve both input reserves so each product
+    // fits in u128. reserve_in rounds down and reserve_in_after up, which can only
+    // lower price_after / price_before and so never underestimates the impact
+    if reserve_in_after > u64::MAX as u128 {
+        reserve_in >>= 1;
+        reserve_in_after = reserve_in_after.div_ceil(2);
+    }
+
+    // Both factors of each product are now at most 2^64 - 1 and 2^64
+    let price_after = reserve_out_after * reserve_in;
+    let price_before = reserve_out as u128 * reserve_in_after;
+    if price_after >= price_before {
+        return Ok(0);
+    }
+
+    // Scale both down until the numerator can be multiplied by 10000, the numerator
+    // rounds up and the denominator down so the impact is never underestimated
+    let mut numerator = price_before - price_after;
+    let mut denominator = price_before;
+    let shift = (128 - numerator.leading_zeros()).saturating_sub(128 - 14);
+    if shift > 0 {
+        let rounding = u128::from(numerator & ((1 << shift) - 1) != 0);
+        numerator = (numerator >> shift) + rounding;
+        denominator >>= shift;
+    }
+
+    let impact = mul_div_ceil(numerator, FEE_DENOMINATOR, denominator)?;
+    Ok(impact.min(FEE_DENOMINATOR) as u16)
+}
+
+/// Amounts actually deposited for the desired amounts, keeping the pool ratio
+/// https://github.com/Uniswap/v2-periphery/blob/master/contracts/UniswapV2Router01.sol#L46
+pub fn quote_deposit(
+    amount_a_desired: u64,
+    amount_b_desired: u64,
+    reserve_a: u64,
+    reserve_b: u64,
+) -> MathResult<(u64, u64)> {
+    // Initial liquidity provision, any ratio is accepted
+    if reserve_a == 0 || reserve_b == 0 {
+        return Ok((amount_a_desired, amount_b_desired));
+    }
+
+    // a_desired * reserve_b / reserve_a
+    let amount_b_optimal = mul_div_floor(
+        amount_a_desired as u128,
+        reserve_b as u128,
+        reserve_a as u128,
+    )?;
+    if amount_b_optimal <= amount_b_desired as u128 {
+        return Ok((amount_a_desired, to_u64(amount_b_optimal)?));
+    }
+
+    // b_desired * reserve_a / reserve_b, always <= a_desired here
+    let amount_a_optimal = to_u64(mul_div_floor(
+        amount_b_desired as u128,
+        reserve_a as u128,
+        reserve_b as u128,
+    )?)?;
+
+    Ok((amount_a_optimal, amount_b_desired))
+}
+
+/// LP tokens minted for a deposit of `amount_a` and `amount_b`
+/// Initial deposit: sqrt(amount_a * amount_b)
+/// Otherwise: min(amount_a * lp_supply / reserve_a, amount_b * lp_supply / reserve_b)
+/// https://github.com/Uniswap/v2-core/blob/master/contracts/UniswapV2Pair.sol#L110
+pub fn lp_to_mint(
+    amount_a: u64,
+    amount_b: u64,
+    reserve_a: u64,
+    reserve_b: u64,
+    lp_supply: u64,
+) -> MathResult<u64> {
+    if reserve_a == 0 || reserve_b == 0 {
+        return to_u64(sqrt(amount_a as u128 * amount_b as u128));
+    }
+
+    let lp_amount_by_a = mul_div_floor(amount_a as u128, lp_supply as u128, reserve_a as u128)?;
+    let lp_amount_by_b = mul_div_floor(amount_b as u128, lp_supply as u128, reserve_b as u128)?;
+
+    to_u64(std::cmp::min(lp_amount_by_a, lp_amount_by_b))
+}
+
+/// Tokens returned for burning `lp_amount`, proportional to the share of LP supply
+/// https://github.com/Uniswap/v2-core/blob/master/contracts/UniswapV2Pair.sol#L134
+pub fn lp_to_burn(
+    lp_amount: u64,
+    reserve_a: u64,
+    reserve_b: u64,
+    lp_supply: u64,
+) -> MathResult<(u64, u64)> {
+    if lp_supply == 0 {
+        return Err(MathError::EmptyPool);
+    }
+    if lp_amount > lp_supply {
+        return Err(MathError::InsufficientLiquidity);
+    }
+
+    let amount_a = mul_div_floor(lp_amount as u128, reserve_a as u128, lp_supply as u128)?;
+    let amount_b = mul_div_floor(lp_amount as u128, reserve_b as u128, lp_supply as u128)?;
+
+    Ok((to_u64(amount_a)?, to_u64(amount_b)?))
+}
+
+/// LP tokens minted to the protocol for `share_bp` of the growth of sqrt(k) since `k_last`,
+/// so that its share of the new supply is worth `share_bp` of the fees earned since then
+/// lp_supply * share * (sqrt(k) - sqrt(k_last)) / (sqrt(k) * (1 - share) + sqrt(k_last) * share)
+/// https://github.com/Uniswap/v2-core/blob/master/contracts/UniswapV2Pair.sol#L89
+pub fn protocol_lp_fee(
+    reserve_a: u64,
+    reserve_b: u64,
+    k_last: u128,
+    lp_supply: u64,
+    share_bp: u16,
+) -> MathResult<u64> {
+    if share_bp as u128 > FEE_DENOMINATOR {
+        return Err(MathError::Overflow);
+    }
+
+    let root_k = sqrt(reserve_a as u128 * reserve_b as u128);
+    let root_k_last = sqrt(k_last);
+    if k_last == 0 || share_bp == 0 || root_k <= root_k_last {
+        return Ok(0);
+    }
+
+    let share_bp = share_bp as u128;
+    let denominator = root_k * (FEE_DENOMINATOR - share_bp) + root_k_last * share_bp;
+    // lp_supply and sqrt(k) both fit in u64, their product fits in u128. Dividing
+    // before multiplying by share_bp keeps it in range without losing precision
+    let growth = lp_supply as u128 * (root_k - root_k_last);
+    let quotient = growth / denominator * share_bp;
+    let remainder = growth % denominator * share_bp / denominator;
+
+    to_u64(quotient + remainder)
+}
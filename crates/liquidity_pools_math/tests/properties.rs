@@ -0,0 +1,233 @@
+use liquidity_pools_math::*;
+use proptest::prelude::*;
+
+// Keeps reserve products well inside u128
+const MAX_AMOUNT: u64 = 1_000_000_000_000_000_000;
+
+proptest! {
+    #[test]
+    fn swap_k_never_decreases(
+        reserve_in in 1..MAX_AMOUNT,
+        reserve_out in 1..MAX_AMOUNT,
+        amount_in in 1..MAX_AMOUNT,
+        fee_bp in 0u16..=10000,
+    ) {
+        if let Ok(swap) = get_amount_out(amount_in, reserve_in, reserve_out, fee_bp) {
+            let k_before = reserve_in as u128 * reserve_out as u128;
+            let k_after = (reserve_in as u128 + amount_in as u128)
+                * (reserve_out as u128 - swap.amount_out as u128);
+            prop_assert!(k_after >= k_before);
+            prop_assert!(swap.amount_out < reserve_out);
+        }
+    }
+
+    #[test]
+    fn swap_output_rounds_down(
+        reserve_in in 1..MAX_AMOUNT,
+        reserve_out in 1..MAX_AMOUNT,
+        amount_in in 1..MAX_AMOUNT,
+        fee_bp in 0u16..=1000,
+    ) {
+        if let Ok(swap) = get_amount_out(amount_in, reserve_in, reserve_out, fee_bp) {
+            let amount_in_after_fee = (amount_in - swap.fee_amount) as u128;
+            prop_assert!(
+                swap.amount_out as u128 * (reserve_in as u128 + amount_in_after_fee)
+                    <= amount_in_after_fee * reserve_out as u128
+            );
+            // fee rounds up
+            prop_assert!(swap.fee_amount as u128 * 10000 >= amount_in as u128 * fee_bp as u128);
+        }
+    }
+
+    #[test]
+    fn price_impact_is_bounded_and_grows_with_size(
+        reserve_in in 1..MAX_AMOUNT,
+        reserve_out in 1..MAX_AMOUNT,
+        amount_in in 1..MAX_AMOUNT / 2,
+        fee_bp in 0u16..=1000,
+    ) {
+        let small = get_amount_out(amount_in, reserve_in, reserve_out, fee_bp);
+        let large = get_amount_out(amount_in * 2, reserve_in, reserve_out, fee_bp);
+        if let (Ok(small), Ok(large)) = (small, large) {
+            let small_impact =
+                price_impact_bp(small.amount_in, small.amount_out, reserve_in, reserve_out).unwrap();
+            let large_impact =
+                price_impact_bp(large.amount_in, large.amount_out, reserve_in, reserve_out).unwrap();
+            prop_assert!(small_impact <= large_impact);
+            prop_assert!(large_impact <= 10000);
+        }
+    }
+
+    #[test]
+    fn price_impact_matches_exact_formula(
+        reserve_in in 1..1u64 << 40,
+        reserve_out in 1..1u64 << 40,
+        amount_in in 1..1u64 << 40,
+        fee_bp in 0u16..=1000,
+    ) {
+        // Small enough for 10000 * price to fit in u128 without scaling
+        if let Ok(swap) = get_amount_out(amount_in, reserve_in, reserve_out, fee_bp) {
+            let price_after = (reserve_out - swap.amount_out) as u128 * reserve_in as u128;
+            let price_before = reserve_out as u128 * (reserve_in + amount_in) as u128;
+            let exact = (price_before - price_after.min(price_before)) * 10000;
+            prop_assert_eq!(
+                price_impact_bp(amount_in, swap.amount_out, reserve_in, reserve_out).unwrap() as u128,
+                exact.div_ceil(price_before)
+            );
+        }
+    }
+
+    #[test]
+    fn price_impact_handles_the_full_u64_range(
+        amount_in in any::<u64>(),
+        amount_out in any::<u64>(),
+        reserve_in in 1..=u64::MAX,
+        reserve_out in 1..=u64::MAX,
+    ) {
+        let amount_out = amount_out.min(reserve_out);
+        let impact = price_impact_bp(amount_in, amount_out, reserve_in, reserve_out).unwrap();
+        prop_assert!(impact <= 10000);
+        if amount_out == reserve_out {
+            prop_assert_eq!(impact, 10000);
+        }
+        if amount_in == 0 && amount_out == 0 {
+            prop_assert_eq!(impact, 0);
+        }
+    }
+
+    #[test]
+    fn exact_out_is_the_smallest_sufficient_input(
+        reserve_in in 1..MAX_AMOUNT,
+        reserve_out in 2..MAX_AMOUNT,
+        amount_out in 1..MAX_AMOUNT,
+        fee_bp in 0u16..=1000,
+    ) {
+        prop_assume!(amount_out < reserve_out);
+        if let Ok(swap) = get_amount_in(amount_out, reserve_in, reserve_out, fee_bp) {
+            prop_assert!(swap.amount_out >= amount_out);
+            match get_amount_out(swap.amount_in - 1, reserve_in, reserve_out, fee_bp) {
+                Ok(cheaper) => prop_assert!(cheaper.amount_out < amount_out),
+                Err(error) => prop_assert_eq!(error, MathError::Underflow),
+            }
+        }
+    }
+
+    #[test]
+    fn round_trip_swap_never_profits(
+        reserve_a in 1..MAX_AMOUNT,
+        reserve_b in 1..MAX_AMOUNT,
+        amount_in in 1..MAX_AMOUNT,
+        fee_bp in 0u16..=1000,
+    ) {
+        if let Ok(first) = get_amount_out(amount_in, reserve_a, reserve_b, fee_bp) {
+            if let Ok(second) = get_amount_out(
+                first.amount_out,
+                reserve_b - first.amount_out,
+                reserve_a + amount_in,
+                fee_bp,
+            ) {
+                prop_assert!(second.amount_out <= amount_in);
+            }
+        }
+    }
+
+    #[test]
+    fn deposit_respects_desired_amounts(
+        reserve_a in 1..MAX_AMOUNT,
+        reserve_b in 1..MAX_AMOUNT,
+        amount_a_desired in 1..MAX_AMOUNT,
+        amount_b_desired in 1..MAX_AMOUNT,
+    ) {
+        let (amount_a, amount_b) =
+            quote_deposit(amount_a_desired, amount_b_desired, reserve_a, reserve_b).unwrap();
+        prop_assert!(amount_a <= amount_a_desired);
+        prop_assert!(amount_b <= amount_b_desired);
+    }
+
+    #[test]
+    fn deposit_then_withdraw_never_profits(
+        reserve_a in 1..MAX_AMOUNT,
+        reserve_b in 1..MAX_AMOUNT,
+        amount_a_desired in 1..MAX_AMOUNT,
+        amount_b_desired in 1..MAX_AMOUNT,
+    ) {
+        let lp_supply = sqrt(reserve_a as u128 * reserve_b as u128) as u64;
+        prop_assume!(lp_supply > 0);
+
+        let (amount_a, amount_b) =
+            quote_deposit(amount_a_desired, amount_b_desired, reserve_a, reserve_b).unwrap();
+        let lp_amount = lp_to_mint(amount_a, amount_b, reserve_a, reserve_b, lp_supply).unwrap();
+
+        let (amount_a_out, amount_b_out) = lp_to_burn(
+            lp_amount,
+            reserve_a + amount_a,
+            reserve_b + amount_b,
+            lp_supply + lp_amount,
+        )
+        .unwrap();
+        prop_assert!(amount_a_out <= amount_a);
+        prop_assert!(amount_b_out <= amount_b);
+    }
+
+    #[test]
+    fn withdraw_rounds_down(
+        reserve_a in 0..MAX_AMOUNT,
+        reserve_b in 0..MAX_AMOUNT,
+        lp_supply in 1..MAX_AMOUNT,
+        lp_amount in 0..MAX_AMOUNT,
+    ) {
+        prop_assume!(lp_amount <= lp_supply);
+        let (amount_a, amount_b) = lp_to_burn(lp_amount, reserve_a, reserve_b, lp_supply).unwrap();
+        prop_assert!(amount_a as u128 * lp_supply as u128 <= lp_amount as u128 * reserve_a as u128);
+        prop_assert!(amount_b as u128 * lp_supply as u128 <= lp_amount as u128 * reserve_b as u128);
+        prop_assert!(amount_a <= reserve_a && amount_b <= reserve_b);
+    }
+
+    #[test]
+    fn split_fee_adds_up(fee_amount in any::<u64>(), share_bp in 0u16..=10000) {
+        let split = split_fee(fee_amount, share_bp).unwrap();
+        prop_assert_eq!(split.share + split.remainder, fee_amount);
+        prop_assert!(split.share as u128 * 10000 <= fee_amount as u128 * share_bp as u128);
+    }
+
+    #[test]
+    fn sqrt_is_floor_sqrt(radicand in any::<u128>()) {
+        let root = sqrt(radicand);
+        prop_assert!(root * root <= radicand);
+        prop_assert!((root + 1).checked_mul(root + 1).is_none_or(|next| next > radicand));
+    }
+
+    #[test]
+    fn protocol_lp_fee_is_at_most_its_share_of_the_growth(
+        reserve_a in 1..MAX_AMOUNT / 1_000_000,
+        reserve_b in 1..MAX_AMOUNT / 1_000_000,
+        last_a in 1..MAX_AMOUNT / 1_000_000,
+        last_b in 1..MAX_AMOUNT / 1_000_000,
+        lp_supply in 1..MAX_AMOUNT / 1_000_000,
+        share_bp in 0u16..=10000,
+    ) {
+        let k_last = last_a as u128 * last_b as u128;
+        let fee = protocol_lp_fee(reserve_a, reserve_b, k_last, lp_supply, share_bp).unwrap();
+        let root_k = sqrt(reserve_a as u128 * reserve_b as u128);
+        let root_k_last = sqrt(k_last);
+        if root_k <= root_k_last {
+            prop_assert_eq!(fee, 0);
+        } else {
+            // fee / (lp_supply + fee) <= share * (root_k - root_k_last) / root_k
+            prop_assert!(
+                fee as u128 * root_k * 10000
+                    <= share_bp as u128 * (root_k - root_k_last) * (lp_supply as u128 + fee as u128)
+            );
+        }
+    }
+}
+
+#[test]
+fn price_impact_does_not_overflow_at_the_u64_bounds() {
+    // 1 - (2^64 - 2) / (2 * (2^64 - 1)) is just above one half
+    assert_eq!(price_impact_bp(u64::MAX, 1, u64::MAX, u64::MAX), Ok(5001));
+    assert_eq!(
+        price_impact_bp(u64::MAX, u64::MAX, u64::MAX, u64::MAX),
+        Ok(10000)
+    );
+}
@@ -0,0 +1,53 @@
+//! Builtin that forwards its instruction to another program through a CPI
+//!
+//! Lets the tests call the program from a non top level instruction, as another
+//! program would. The first account is the program to call, the remaining accounts
+//! and the data are passed through with the privileges they have in the transaction.
+
+use anchor_lang::prelude::{AccountMeta, Pubkey};
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::stable_layout::stable_instruction::StableInstruction;
+use solana_program_runtime::declare_process_instruction;
+use solana_sdk::instruction::InstructionError;
+
+/// Program id of the builtin, registered by `TestEnv::new`
+pub const ID: Pubkey = Pubkey::new_from_array([0xc9; 32]);
+
+declare_process_instruction!(Entrypoint, 1, |invoke_context| {
+    let transaction_context = &invoke_context.transaction_context;
+    let instruction_context = transaction_context.get_current_instruction_context()?;
+    let key_at = |index| {
+        instruction_context
+            .get_index_of_instruction_account_in_transaction(index)
+            .and_then(|index| transaction_context.get_key_of_account_at_index(index))
+    };
+
+    let program_id = *key_at(0)?;
+    let accounts = (1..instruction_context.get_number_of_instruction_accounts())
+        .map(|index| {
+            Ok(AccountMeta {
+                pubkey: *key_at(index)?,
+                is_signer: instruction_context.is_instruction_account_signer(index)?,
+                is_writable: instruction_context.is_instruction_account_writable(index)?,
+            })
+        })
+        .collect::<Result<Vec<_>, InstructionError>>()?;
+    let instruction = Instruction {
+        program_id,
+        accounts,
+        data: instruction_context.get_instruction_data().to_vec(),
+    };
+
+    invoke_context.native_invoke(StableInstruction::from(instruction), &[])
+});
+
+/// `instruction` wrapped to be made through the proxy
+pub fn wrap(instruction: Instruction) -> Instruction {
+    let mut accounts = vec![AccountMeta::new_readonly(instruction.program_id, false)];
+    accounts.extend(instruction.accounts);
+    Instruction {
+        program_id: ID,
+        accounts,
+        data: instruction.data,
+    }
+}
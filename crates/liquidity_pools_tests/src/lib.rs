@@ -0,0 +1,561 @@
+//! LiteSVM fixtures for the liquidity_pools program
+//!
+//! The tests run against the SBF build of the program, `LIQUIDITY_POOLS_SO` or the
+//! output of `anchor build`. Without one, the program falls back to running natively
+//! as a LiteSVM builtin (see `processor`), see the crate README. Token, Token-2022
+//! and Associated Token programs are the ones bundled with LiteSVM.
+
+// `TransactionResult` is LiteSVM's own result type
+#![allow(clippy::result_large_err)]
+
+pub mod cpi_proxy;
+pub mod processor;
+
+use std::panic::AssertUnwindSafe;
+use std::path::{Path, PathBuf};
+
+use anchor_lang::prelude::{Clock, Pubkey};
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::program_pack::Pack;
+use anchor_lang::{AccountDeserialize, AnchorDeserialize, Event};
+use anchor_spl::associated_token::get_associated_token_address_with_program_id;
+use anchor_spl::token::spl_token;
+use anchor_spl::token_2022::spl_token_2022;
+use base64::prelude::{Engine, BASE64_STANDARD};
+use liquidity_pools::state::{Config, ConfigChange, PairIndexHeader, Pool};
+use liquidity_pools_client::instructions::{self, CreatePoolOptions, SwapDirection};
+use liquidity_pools_client::pda::{
+    find_config_address, find_config_change_address, sort_mints, PoolKeys,
+};
+use liquidity_pools_client::quote::decode_return_data;
+use litesvm::types::{FailedTransactionMetadata, TransactionMetadata, TransactionResult};
+use litesvm::LiteSVM;
+use solana_program_runtime::invoke_context::BuiltinFunctionWithContext;
+use solana_sdk::account::Account;
+use solana_sdk::instruction::InstructionError;
+use solana_sdk::native_loader;
+use solana_sdk::native_token::LAMPORTS_PER_SOL;
+use solana_sdk::signature::{Keypair, Signer};
+use solana_sdk::transaction::{Transaction, TransactionError};
+
+pub use anchor_spl::token::ID as TOKEN_PROGRAM_ID;
+pub use anchor_spl::token_2022::ID as TOKEN_2022_PROGRAM_ID;
+
+pub struct TestEnv {
+    pub svm: LiteSVM,
+}
+
+/// Pool from `TestEnv::create_funded_pool` with the accounts that set it up
+pub struct FundedPool {
+    pub keys: PoolKeys,
+    pub creator: Keypair,
+    // Holds all the LP tokens
+    pub provider: Keypair,
+}
+
+// LiteSVM 0.6 registers the builtin, then panics writing its program account as an
+// SBF program of the BPF loader. Builtins are only dispatched by program id when
+// owned by the native loader, so that account is written here instead
+fn add_builtin(svm: &mut LiteSVM, program_id: Pubkey, entrypoint: BuiltinFunctionWithContext) {
+    let _ = std::panic::catch_unwind(AssertUnwindSafe(|| svm.add_builtin(program_id, entrypoint)));
+    svm.set_account(
+        program_id,
+        Account {
+            lamports: 1,
+            data: Vec::new(),
+            owner: native_loader::ID,
+            executable: true,
+            rent_epoch: 0,
+        },
+    )
+    .unwrap();
+}
+
+impl Default for TestEnv {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TestEnv {
+    /// SBF build of the program to load, `LIQUIDITY_POOLS_SO` when set, else the
+    /// `anchor build` output if there is one. None runs the program natively, which
+    /// `LIQUIDITY_POOLS_NATIVE` forces
+    pub fn program_path() -> Option<PathBuf> {
+        if std::env::var_os("LIQUIDITY_POOLS_NATIVE").is_some() {
+            return None;
+        }
+        if let Some(path) = std::env::var_os("LIQUIDITY_POOLS_SO") {
+            return Some(PathBuf::from(path));
+        }
+        let path =
+            Path::new(env!("CARGO_MANIFEST_DIR")).join("../../target/deploy/liquidity_pools.so");
+        path.exists().then_some(path)
+    }
+
+    /// Fresh LiteSVM with the program loaded
+    pub fn new() -> Self {
+        let mut svm = LiteSVM::new();
+        match Self::program_path() {
+            Some(path) => svm
+                .add_program_from_file(liquidity_pools::ID, &path)
+                .unwrap_or_else(|error| panic!("failed to load {}: {error}", path.display())),
+            None => {
+                processor::install_syscall_stubs();
+                add_builtin(&mut svm, liquidity_pools::ID, processor::Entrypoint::vm);
+            }
+        }
+        add_builtin(&mut svm, cpi_proxy::ID, cpi_proxy::Entrypoint::vm);
+
+        Self { svm }
+    }
+
+    /// New keypair funded with 1000 SOL
+    pub fn create_user(&mut self) -> Keypair {
+        let user = Keypair::new();
+        self.svm
+            .airdrop(&user.pubkey(), 1000 * LAMPORTS_PER_SOL)
+            .expect("airdrop failed");
+        user
+    }
+
+    /// Writes an initialized mint owned by `token_program`, with a throwaway mint authority
+    pub fn create_mint(&mut self, decimals: u8, token_program: Pubkey) -> Pubkey {
+        let mint = Pubkey::new_unique();
+        let mut data = vec![0u8; spl_token_2022::state::Mint::LEN];
+        spl_token_2022::state::Mint::pack(
+            spl_token_2022::state::Mint {
+                mint_authority: Some(Pubkey::new_unique()).into(),
+                supply: 0,
+                decimals,
+                is_initialized: true,
+                freeze_authority: None.into(),
+            },
+            &mut data,
+        )
+        .unwrap();
+
+        self.set_token_owned_account(mint, data, token_program);
+        mint
+    }
+
+    /// Wrapped SOL mint of `token_program`, written if the SVM does not have it yet
+    pub fn native_mint(&mut self, token_program: Pubkey) -> Pubkey {
+        let mint = if token_program == TOKEN_PROGRAM_ID {
+            spl_token::native_mint::ID
+        } else {
+            spl_token_2022::native_mint::ID
+        };
+        if self.svm.get_account(&mint).is_none() {
+            let mut data = vec![0u8; spl_token_2022::state::Mint::LEN];
+            spl_token_2022::state::Mint::pack(
+                spl_token_2022::state::Mint {
+                    decimals: 9,
+                    is_initialized: true,
+                    ..Default::default()
+                },
+                &mut data,
+            )
+            .unwrap();
+            self.set_token_owned_account(mint, data, token_program);
+        }
+        mint
+    }
+
+    /// Writes an empty associated token account of `owner` for `mint`
+    pub fn create_ata(&mut self, mint: &Pubkey, owner: &Pubkey) -> Pubkey {
+        let token_program = self.owner(mint);
+        let ata = get_associated_token_address_with_program_id(owner, mint, &token_program);
+        self.create_token_account(ata, mint, owner);
+        ata
+    }
+
+    /// Writes an empty token account of `owner` for `mint` at `address`
+    pub fn create_token_account(&mut self, address: Pubkey, mint: &Pubkey, owner: &Pubkey) {
+        let token_program = self.owner(mint);
+        let mut data = vec![0u8; spl_token_2022::state::Account::LEN];
+        spl_token_2022::state::Account::pack(
+            spl_token_2022::state::Account {
+                mint: *mint,
+                owner: *owner,
+                state: spl_token_2022::state::AccountState::Initialized,
+                ..Default::default()
+            },
+            &mut data,
+        )
+        .unwrap();
+
+        self.set_token_owned_account(address, data, token_program);
+    }
+
+    /// Credits `amount` to a token account and the mint supply, bypassing the mint authority
+    pub fn mint_to(&mut self, ata: &Pubkey, amount: u64) {
+        let mut account = self.svm.get_account(ata).expect("token account not found");
+        // Base state only, Token-2022 accounts can carry extensions after it
+        let mut state = spl_token_2022::state::Account::unpack_from_slice(&account.data).unwrap();
+        state.amount += amount;
+        state.pack_into_slice(&mut account.data);
+        self.svm.set_account(*ata, account).unwrap();
+
+        let mut mint_account = self.svm.get_account(&state.mint).unwrap();
+        let mut mint = spl_token_2022::state::Mint::unpack_from_slice(&mint_account.data).unwrap();
+        mint.supply += amount;
+        mint.pack_into_slice(&mut mint_account.data);
+        self.svm.set_account(state.mint, mint_account).unwrap();
+    }
+
+    pub fn owner(&self, address: &Pubkey) -> Pubkey {
+        self.svm
+            .get_account(address)
+            .expect("account not found")
+            .owner
+    }
+
+    /// Moves the clock `seconds` forward, with a new blockhash so repeated
+    /// transactions are not rejected as duplicates
+    pub fn warp(&mut self, seconds: i64) {
+        let mut clock = self.svm.get_sysvar::<Clock>();
+        clock.unix_timestamp += seconds;
+        self.svm.set_sysvar(&clock);
+        self.svm.expire_blockhash();
+    }
+
+    /// Lamports of `address`, 0 once it is closed
+    pub fn lamports(&self, address: &Pubkey) -> u64 {
+        self.svm
+            .get_account(address)
+            .map_or(0, |account| account.lamports)
+    }
+
+    pub fn token_balance(&self, ata: &Pubkey) -> u64 {
+        self.svm
+            .get_account(ata)
+            .map(|account| {
+                spl_token_2022::state::Account::unpack_from_slice(&account.data)
+                    .unwrap()
+                    .amount
+            })
+            .unwrap_or_default()
+    }
+
+    pub fn mint_supply(&self, mint: &Pubkey) -> u64 {
+        let account = self.svm.get_account(mint).expect("mint not found");
+        spl_token_2022::state::Mint::unpack_from_slice(&account.data)
+            .unwrap()
+            .supply
+    }
+
+    pub fn fetch_config(&self) -> Config {
+        let account = self.svm.get_account(&find_config_address().0).unwrap();
+        Config::try_deserialize(&mut account.data.as_slice()).unwrap()
+    }
+
+    pub fn fetch_config_change(&self) -> ConfigChange {
+        let account = self
+            .svm
+            .get_account(&find_config_change_address().0)
+            .expect("config change not found");
+        ConfigChange::try_deserialize(&mut account.data.as_slice()).unwrap()
+    }
+
+    pub fn fetch_pool(&self, pool: &Pubkey) -> Pool {
+        let account = self.svm.get_account(pool).expect("pool not found");
+        Pool::try_deserialize(&mut account.data.as_slice()).unwrap()
+    }
+
+    /// Pair index header of the pool mints, None until one of their pools is registered
+    pub fn fetch_pair_index_header(&self, keys: &PoolKeys) -> Option<PairIndexHeader> {
+        let account = self.svm.get_account(&keys.pair_index_header())?;
+        Some(PairIndexHeader::try_deserialize(&mut account.data.as_slice()).unwrap())
+    }
+
+    /// Pair index page of the next registered pool of the pool mints
+    pub fn pair_index_page(&self, keys: &PoolKeys) -> u16 {
+        self.fetch_pair_index_header(keys)
+            .map_or(0, |header| header.next_page())
+    }
+
+    /// Signs with `signers`, the first one pays the transaction fee
+    pub fn send(
+        &mut self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> TransactionResult {
+        self.svm.expire_blockhash();
+        let tx = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&signers[0].pubkey()),
+            signers,
+            self.svm.latest_blockhash(),
+        );
+        self.svm.send_transaction(tx)
+    }
+
+    /// Simulates a read-only instruction and decodes its return data
+    pub fn view<T: AnchorDeserialize>(
+        &mut self,
+        instruction: Instruction,
+        payer: &Keypair,
+    ) -> Result<T, FailedTransactionMetadata> {
+        self.svm.expire_blockhash();
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&payer.pubkey()),
+            &[payer],
+            self.svm.latest_blockhash(),
+        );
+        let simulated = self.svm.simulate_transaction(tx)?;
+        Ok(decode_return_data(&simulated.meta.return_data.data).expect("invalid return data"))
+    }
+
+    /// Initializes the config with a fresh admin and returns it
+    pub fn initialize(&mut self, protocol_fee_bp: u16) -> Keypair {
+        let admin = self.create_user();
+        let protocol_fee_recipient = Pubkey::new_unique();
+        self.send(
+            &[instructions::initialize(
+                &admin.pubkey(),
+                protocol_fee_recipient,
+                protocol_fee_bp,
+            )],
+            &[&admin],
+        )
+        .expect("initialize failed");
+        admin
+    }
+
+    /// Creates two mints with the given token programs, ordered the way the program expects
+    pub fn create_mint_pair(
+        &mut self,
+        decimals: (u8, u8),
+        token_programs: (Pubkey, Pubkey),
+    ) -> (Pubkey, Pubkey) {
+        let mint_x = self.create_mint(decimals.0, token_programs.0);
+        let mint_y = self.create_mint(decimals.1, token_programs.1);
+        sort_mints(mint_x, mint_y)
+    }
+
+    pub fn pool_keys(&self, token_mint_a: Pubkey, token_mint_b: Pubkey, index: u16) -> PoolKeys {
+        PoolKeys::new(
+            token_mint_a,
+            token_mint_b,
+            index,
+            self.owner(&token_mint_a),
+            self.owner(&token_mint_b),
+            TOKEN_2022_PROGRAM_ID,
+        )
+    }
+
+    /// Options of `creator` creating the pool at `keys` under the current config,
+    /// paying the creation fee if any
+    pub fn create_pool_options(&self, creator: &Pubkey, keys: &PoolKeys) -> CreatePoolOptions {
+        let config = self.fetch_config();
+        let creation_fee_token_program = if config.charges_creation_fee_in_lamports() {
+            Pubkey::default()
+        } else {
+            self.owner(&config.pool_creation_fee_mint)
+        };
+        CreatePoolOptions::new(
+            &config,
+            self.fetch_pair_index_header(keys).as_ref(),
+            creator,
+            creation_fee_token_program,
+        )
+    }
+
+    /// Creates the pool with index 0 for fresh mints and returns its keys.
+    /// Initializes the config without protocol fee if it does not exist yet
+    pub fn create_pool(
+        &mut self,
+        creator: &Keypair,
+        decimals: (u8, u8),
+        token_programs: (Pubkey, Pubkey),
+        fee_bp: u16,
+    ) -> PoolKeys {
+        let (token_mint_a, token_mint_b) = self.create_mint_pair(decimals, token_programs);
+        self.create_pool_for_mints(creator, token_mint_a, token_mint_b, fee_bp)
+    }
+
+    /// Creates the pool with index 0 for existing mints, ordered the way the program expects
+    pub fn create_pool_for_mints(
+        &mut self,
+        creator: &Keypair,
+        token_mint_a: Pubkey,
+        token_mint_b: Pubkey,
+        fee_bp: u16,
+    ) -> PoolKeys {
+        let keys = self.pool_keys(token_mint_a, token_mint_b, 0);
+        if self.svm.get_account(&find_config_address().0).is_none() {
+            self.initialize(0);
+        }
+        let options = self.create_pool_options(&creator.pubkey(), &keys);
+        self.send(
+            &[instructions::create_pool_with_options(
+                &creator.pubkey(),
+                &keys,
+                fee_bp,
+                &options,
+            )],
+            &[creator],
+        )
+        .expect("create_pool failed");
+        keys
+    }
+
+    /// Creates a pool of 9 decimals mints like `create_pool`, and deposits `reserves`
+    /// from a new provider
+    pub fn create_funded_pool(
+        &mut self,
+        token_programs: (Pubkey, Pubkey),
+        fee_bp: u16,
+        reserves: (u64, u64),
+    ) -> FundedPool {
+        let creator = self.create_user();
+        let keys = self.create_pool(&creator, (9, 9), token_programs, fee_bp);
+        let provider = self.fund_user(&keys, reserves.0, reserves.1);
+        self.add_liquidity(&keys, &provider, reserves.0, reserves.1)
+            .expect("add_liquidity failed");
+        FundedPool {
+            keys,
+            creator,
+            provider,
+        }
+    }
+
+    /// `create_funded_pool` of a Token mint and a Token-2022 mint, the pair most tests trade
+    pub fn create_test_pool(&mut self, fee_bp: u16, reserves: (u64, u64)) -> FundedPool {
+        self.create_funded_pool((TOKEN_PROGRAM_ID, TOKEN_2022_PROGRAM_ID), fee_bp, reserves)
+    }
+
+    /// Initializes the config with `protocol_fee_bp` and creates a test pool, returns
+    /// the pool and the config admin
+    pub fn setup_pool(
+        &mut self,
+        protocol_fee_bp: u16,
+        fee_bp: u16,
+        reserves: (u64, u64),
+    ) -> (FundedPool, Keypair) {
+        let admin = self.initialize(protocol_fee_bp);
+        (self.create_test_pool(fee_bp, reserves), admin)
+    }
+
+    /// New user holding `amount_a` of token A and `amount_b` of token B
+    pub fn fund_user(&mut self, keys: &PoolKeys, amount_a: u64, amount_b: u64) -> Keypair {
+        let user = self.create_user();
+        let ata_a = self.create_ata(&keys.token_mint_a, &user.pubkey());
+        let ata_b = self.create_ata(&keys.token_mint_b, &user.pubkey());
+        self.mint_to(&ata_a, amount_a);
+        self.mint_to(&ata_b, amount_b);
+        user
+    }
+
+    /// Deposits exactly `amount_a` and `amount_b` from `provider`
+    pub fn add_liquidity(
+        &mut self,
+        keys: &PoolKeys,
+        provider: &Keypair,
+        amount_a: u64,
+        amount_b: u64,
+    ) -> TransactionResult {
+        let config = self.fetch_config();
+        let pool = self.fetch_pool(&keys.pool);
+        self.send(
+            &[instructions::add_liquidity(
+                keys,
+                &provider.pubkey(),
+                amount_a,
+                amount_b,
+                0,
+                0,
+                None,
+                &instructions::protocol_fee_lp_recipients(&config, &pool),
+            )],
+            &[provider],
+        )
+    }
+
+    pub fn swap(
+        &mut self,
+        keys: &PoolKeys,
+        user: &Keypair,
+        direction: SwapDirection,
+        amount_in: u64,
+        min_amount_out: u64,
+        deadline: Option<i64>,
+    ) -> TransactionResult {
+        self.send(
+            &[instructions::swap(
+                keys,
+                &user.pubkey(),
+                direction,
+                amount_in,
+                min_amount_out,
+                deadline,
+            )],
+            &[user],
+        )
+    }
+
+    pub fn update_pool(
+        &mut self,
+        keys: &PoolKeys,
+        authority: &Keypair,
+        max_price_impact_bp: Option<u16>,
+        max_trade_size_bp: Option<u16>,
+    ) -> TransactionResult {
+        self.send(
+            &[instructions::update_pool(
+                &authority.pubkey(),
+                &keys.pool,
+                max_price_impact_bp,
+                max_trade_size_bp,
+                None,
+                None,
+            )],
+            &[authority],
+        )
+    }
+
+    /// Token A and token B held by the pool vaults, unclaimed protocol fees included
+    pub fn reserves(&self, keys: &PoolKeys) -> (u64, u64) {
+        (
+            self.token_balance(&keys.token_vault_a),
+            self.token_balance(&keys.token_vault_b),
+        )
+    }
+
+    fn set_token_owned_account(&mut self, address: Pubkey, data: Vec<u8>, token_program: Pubkey) {
+        let lamports = self.svm.minimum_balance_for_rent_exemption(data.len());
+        self.svm
+            .set_account(
+                address,
+                Account {
+                    lamports,
+                    data,
+                    owner: token_program,
+                    executable: false,
+                    rent_epoch: 0,
+                },
+            )
+            .unwrap();
+    }
+}
+
+/// Anchor events emitted with `emit!` by a successful transaction
+pub fn events<T: Event>(meta: &TransactionMetadata) -> Vec<T> {
+    meta.logs
+        .iter()
+        .filter_map(|log| log.strip_prefix("Program data: "))
+        .filter_map(|data| BASE64_STANDARD.decode(data).ok())
+        .filter(|data| data.starts_with(T::DISCRIMINATOR))
+        .map(|data| T::try_from_slice(&data[T::DISCRIMINATOR.len()..]).expect("invalid event"))
+        .collect()
+}
+
+/// Custom program error code of a failed transaction (6000 + `ErrorCode` for program errors)
+pub fn error_code(result: &FailedTransactionMetadata) -> Option<u32> {
+    match result.err {
+        TransactionError::InstructionError(_, InstructionError::Custom(code)) => Some(code),
+        _ => None,
+    }
+}
@@ -0,0 +1,255 @@
+//! Runs the program natively inside LiteSVM
+//!
+//! The program is registered as a builtin whose entrypoint serializes the
+//! instruction accounts the way the SBF loader does, calls `liquidity_pools::entry`
+//! on the host and writes the account changes back. Syscalls made by the program
+//! (CPI, sysvars, return data, event logs) are routed to the running
+//! `InvokeContext` through `SyscallStubs`, so the token programs bundled with
+//! LiteSVM still run as SBF.
+
+use std::cell::Cell;
+use std::panic::AssertUnwindSafe;
+use std::sync::Once;
+
+use anchor_lang::prelude::{AccountInfo, Clock, ProgramError, Pubkey, Rent};
+use anchor_lang::solana_program::entrypoint::{deserialize, ProgramResult, SUCCESS};
+use anchor_lang::solana_program::epoch_schedule::EpochSchedule;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::program_stubs::{set_syscall_stubs, SyscallStubs};
+use anchor_lang::solana_program::stable_layout::stable_instruction::StableInstruction;
+use anchor_lang::solana_program::sysvar::Sysvar;
+use solana_bpf_loader_program::serialization::serialize_parameters;
+use solana_program_runtime::invoke_context::InvokeContext;
+use solana_program_runtime::{declare_process_instruction, stable_log};
+use solana_sdk::instruction::InstructionError;
+
+thread_local! {
+    // InvokeContext of the instruction currently executing on this thread
+    static INVOKE_CONTEXT: Cell<usize> = const { Cell::new(0) };
+}
+
+declare_process_instruction!(Entrypoint, 1, |invoke_context| {
+    process_instruction(invoke_context)
+});
+
+/// Routes the program syscalls to LiteSVM, only needs to happen once per process
+pub fn install_syscall_stubs() {
+    static INSTALL: Once = Once::new();
+    INSTALL.call_once(|| {
+        set_syscall_stubs(Box::new(LiteSvmSyscallStubs));
+    });
+}
+
+fn set_invoke_context(invoke_context: &mut InvokeContext) {
+    INVOKE_CONTEXT.with(|cell| cell.set(invoke_context as *mut InvokeContext as usize));
+}
+
+fn get_invoke_context<'a, 'b>() -> &'a mut InvokeContext<'b> {
+    let pointer = INVOKE_CONTEXT.with(Cell::get);
+    assert_ne!(pointer, 0, "syscall made outside of the program");
+    // Only set by `process_instruction`, which outlives every syscall of the program
+    unsafe { &mut *(pointer as *mut InvokeContext) }
+}
+
+fn process_instruction(invoke_context: &mut InvokeContext) -> Result<(), InstructionError> {
+    set_invoke_context(invoke_context);
+
+    let transaction_context = &invoke_context.transaction_context;
+    let instruction_context = transaction_context.get_current_instruction_context()?;
+    // Same input layout as the SBF loader hands to the program entrypoint
+    let (mut parameters, _, _) =
+        serialize_parameters(transaction_context, instruction_context, true)?;
+    let (program_id, account_infos, data) =
+        unsafe { deserialize(parameters.as_slice_mut().as_mut_ptr()) };
+
+    match std::panic::catch_unwind(AssertUnwindSafe(|| {
+        liquidity_pools::entry(program_id, &account_infos, data)
+    })) {
+        Ok(result) => result.map_err(|error| InstructionError::from(u64::from(error)))?,
+        Err(_) => return Err(InstructionError::ProgramFailedToComplete),
+    }
+
+    // A CPI may have replaced the instruction context, fetch it again
+    let transaction_context = &invoke_context.transaction_context;
+    let instruction_context = transaction_context.get_current_instruction_context()?;
+    for index in 0..instruction_context.get_number_of_instruction_accounts() {
+        let mut account =
+            instruction_context.try_borrow_instruction_account(transaction_context, index)?;
+        if !account.is_writable() {
+            continue;
+        }
+        let Some(account_info) = account_infos
+            .iter()
+            .find(|account_info| account_info.key == account.get_key())
+        else {
+            continue;
+        };
+
+        if account.get_lamports() != account_info.lamports() {
+            account.set_lamports(account_info.lamports())?;
+        }
+        if account.get_data() != *account_info.try_borrow_data().unwrap() {
+            account.set_data_from_slice(&account_info.try_borrow_data().unwrap())?;
+        }
+        // Last, the runtime only lets the owner change with zeroed data
+        if account.get_owner() != account_info.owner {
+            account.set_owner(account_info.owner.as_ref())?;
+        }
+    }
+
+    Ok(())
+}
+
+fn to_program_error(error: InstructionError) -> ProgramError {
+    ProgramError::try_from(error).unwrap_or_else(|error| panic!("{error}"))
+}
+
+fn invoke_signed(
+    instruction: &Instruction,
+    account_infos: &[AccountInfo],
+    signers_seeds: &[&[&[u8]]],
+) -> Result<(), InstructionError> {
+    let invoke_context = get_invoke_context();
+    let transaction_context = &invoke_context.transaction_context;
+    let instruction_context = transaction_context.get_current_instruction_context()?;
+    let caller = *instruction_context.get_last_program_key(transaction_context)?;
+
+    let signers = signers_seeds
+        .iter()
+        .map(|seeds| Pubkey::create_program_address(seeds, &caller))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| InstructionError::InvalidSeeds)?;
+    let instruction = StableInstruction::from(instruction.clone());
+    let (instruction_accounts, _) = invoke_context.prepare_instruction(&instruction, &signers)?;
+
+    // Changes the caller made to its accounts so far are visible to the callee
+    let transaction_context = &invoke_context.transaction_context;
+    let instruction_context = transaction_context.get_current_instruction_context()?;
+    let mut writable_accounts = Vec::new();
+    for instruction_account in &instruction_accounts {
+        let key = transaction_context
+            .get_key_of_account_at_index(instruction_account.index_in_transaction)?;
+        let account_info = account_infos
+            .iter()
+            .find(|account_info| account_info.key == key)
+            .ok_or(InstructionError::MissingAccount)?;
+        let mut account = instruction_context.try_borrow_instruction_account(
+            transaction_context,
+            instruction_account.index_in_caller,
+        )?;
+
+        if account.get_lamports() != account_info.lamports() {
+            account.set_lamports(account_info.lamports())?;
+        }
+        if account.get_data() != *account_info.try_borrow_data().unwrap() {
+            account.set_data_from_slice(&account_info.try_borrow_data().unwrap())?;
+        }
+        if account.get_owner() != account_info.owner {
+            account.set_owner(account_info.owner.as_ref())?;
+        }
+        if instruction_account.is_writable {
+            writable_accounts.push((instruction_account.index_in_caller, account_info));
+        }
+    }
+
+    invoke_context.native_invoke(instruction, &signers)?;
+
+    // And the callee changes are visible to the caller
+    let transaction_context = &invoke_context.transaction_context;
+    let instruction_context = transaction_context.get_current_instruction_context()?;
+    for (index_in_caller, account_info) in writable_accounts {
+        let account = instruction_context
+            .try_borrow_instruction_account(transaction_context, index_in_caller)?;
+
+        **account_info.try_borrow_mut_lamports().unwrap() = account.get_lamports();
+        if account_info.owner != account.get_owner() {
+            account_info.assign(account.get_owner());
+        }
+        if account_info.data_len() != account.get_data().len() {
+            account_info
+                .realloc(account.get_data().len(), false)
+                .map_err(|_| InstructionError::InvalidRealloc)?;
+        }
+        account_info
+            .try_borrow_mut_data()
+            .unwrap()
+            .copy_from_slice(account.get_data());
+    }
+
+    Ok(())
+}
+
+fn write_sysvar<T: Sysvar + Clone>(
+    sysvar: Result<std::sync::Arc<T>, InstructionError>,
+    var_addr: *mut u8,
+) -> u64 {
+    match sysvar {
+        Ok(sysvar) => {
+            unsafe { *(var_addr as *mut T) = T::clone(&sysvar) };
+            SUCCESS
+        }
+        Err(error) => u64::from(to_program_error(error)),
+    }
+}
+
+struct LiteSvmSyscallStubs;
+
+impl SyscallStubs for LiteSvmSyscallStubs {
+    fn sol_log(&self, message: &str) {
+        stable_log::program_log(&get_invoke_context().get_log_collector(), message);
+    }
+
+    fn sol_log_data(&self, fields: &[&[u8]]) {
+        stable_log::program_data(&get_invoke_context().get_log_collector(), fields);
+    }
+
+    fn sol_invoke_signed(
+        &self,
+        instruction: &Instruction,
+        account_infos: &[AccountInfo],
+        signers_seeds: &[&[&[u8]]],
+    ) -> ProgramResult {
+        invoke_signed(instruction, account_infos, signers_seeds).map_err(to_program_error)
+    }
+
+    fn sol_get_clock_sysvar(&self, var_addr: *mut u8) -> u64 {
+        write_sysvar::<Clock>(
+            get_invoke_context().get_sysvar_cache().get_clock(),
+            var_addr,
+        )
+    }
+
+    fn sol_get_rent_sysvar(&self, var_addr: *mut u8) -> u64 {
+        write_sysvar::<Rent>(get_invoke_context().get_sysvar_cache().get_rent(), var_addr)
+    }
+
+    fn sol_get_epoch_schedule_sysvar(&self, var_addr: *mut u8) -> u64 {
+        write_sysvar::<EpochSchedule>(
+            get_invoke_context().get_sysvar_cache().get_epoch_schedule(),
+            var_addr,
+        )
+    }
+
+    fn sol_get_return_data(&self) -> Option<(Pubkey, Vec<u8>)> {
+        let (program_id, data) = get_invoke_context().transaction_context.get_return_data();
+        (!data.is_empty()).then(|| (*program_id, data.to_vec()))
+    }
+
+    fn sol_set_return_data(&self, data: &[u8]) {
+        let invoke_context = get_invoke_context();
+        let transaction_context = &mut invoke_context.transaction_context;
+        let program_id = *transaction_context
+            .get_current_instruction_context()
+            .and_then(|instruction_context| {
+                instruction_context.get_last_program_key(transaction_context)
+            })
+            .unwrap();
+        transaction_context
+            .set_return_data(program_id, data.to_vec())
+            .unwrap();
+    }
+
+    fn sol_get_stack_height(&self) -> u64 {
+        get_invoke_context().get_stack_height() as u64
+    }
+}
@@ -0,0 +1,124 @@
+use liquidity_pools::error::ErrorCode;
+use liquidity_pools::math;
+use liquidity_pools_client::instructions;
+use liquidity_pools_client::quote::quote_add_liquidity;
+use liquidity_pools_tests::{error_code, TestEnv, TOKEN_2022_PROGRAM_ID, TOKEN_PROGRAM_ID};
+use solana_sdk::signature::Signer;
+
+#[test]
+fn pool_creator_can_add_initial_liquidity() {
+    let mut env = TestEnv::new();
+    let creator = env.create_user();
+    let keys = env.create_pool(
+        &creator,
+        (9, 6),
+        (TOKEN_PROGRAM_ID, TOKEN_2022_PROGRAM_ID),
+        100,
+    );
+
+    let amount_a = 544_145 * 10u64.pow(9);
+    let amount_b = 23_144 * 10u64.pow(6);
+    let provider = env.fund_user(&keys, amount_a, amount_b);
+
+    env.add_liquidity(&keys, &provider, amount_a, amount_b)
+        .expect("add_liquidity failed");
+
+    assert_eq!(env.reserves(&keys), (amount_a, amount_b));
+    assert_eq!(env.token_balance(&keys.user_ata_a(&provider.pubkey())), 0);
+    assert_eq!(env.token_balance(&keys.user_ata_b(&provider.pubkey())), 0);
+
+    // first deposit mints sqrt(amount_a * amount_b)
+    let lp_amount = math::sqrt(amount_a as u128 * amount_b as u128) as u64;
+    assert_eq!(
+        env.token_balance(&keys.user_ata_lp(&provider.pubkey())),
+        lp_amount
+    );
+    assert_eq!(env.mint_supply(&keys.lp_mint), lp_amount);
+}
+
+#[test]
+fn pool_can_receive_subsequent_liquidity() {
+    let mut env = TestEnv::new();
+    let creator = env.create_user();
+    let keys = env.create_pool(
+        &creator,
+        (9, 9),
+        (TOKEN_2022_PROGRAM_ID, TOKEN_PROGRAM_ID),
+        100,
+    );
+
+    let initial_provider = env.fund_user(&keys, 1_000_000 * 10u64.pow(9), 1_000_000 * 10u64.pow(9));
+    env.add_liquidity(
+        &keys,
+        &initial_provider,
+        1_000_000 * 10u64.pow(9),
+        1_000_000 * 10u64.pow(9),
+    )
+    .expect("initial add_liquidity failed");
+
+    let amount_a_desired = 1_500 * 10u64.pow(9);
+    let amount_b_desired = 1_000 * 10u64.pow(9);
+    let provider = env.fund_user(&keys, amount_a_desired, amount_b_desired);
+
+    let (reserve_a, reserve_b) = env.reserves(&keys);
+    let lp_supply = env.mint_supply(&keys.lp_mint);
+    let quote = quote_add_liquidity(
+        amount_a_desired,
+        amount_b_desired,
+        reserve_a,
+        reserve_b,
+        lp_supply,
+    )
+    .unwrap();
+
+    env.add_liquidity(&keys, &provider, amount_a_desired, amount_b_desired)
+        .expect("add_liquidity failed");
+
+    // token B limits the deposit, only the optimal amount of token A is taken
+    assert_eq!(quote.amount_b, amount_b_desired);
+    assert_eq!(
+        env.reserves(&keys),
+        (reserve_a + quote.amount_a, reserve_b + quote.amount_b)
+    );
+    assert_eq!(
+        env.token_balance(&keys.user_ata_a(&provider.pubkey())),
+        amount_a_desired - quote.amount_a
+    );
+    assert_eq!(
+        env.token_balance(&keys.user_ata_lp(&provider.pubkey())),
+        quote.lp_amount
+    );
+    assert_eq!(env.mint_supply(&keys.lp_mint), lp_supply + quote.lp_amount);
+}
+
+#[test]
+fn fails_if_optimal_amount_is_below_minimum() {
+    let mut env = TestEnv::new();
+    let creator = env.create_user();
+    let keys = env.create_pool(&creator, (9, 9), (TOKEN_PROGRAM_ID, TOKEN_PROGRAM_ID), 100);
+
+    let initial_provider = env.fund_user(&keys, 1_000_000, 2_000_000);
+    env.add_liquidity(&keys, &initial_provider, 1_000_000, 2_000_000)
+        .expect("initial add_liquidity failed");
+
+    // the pool ratio only takes 2_000 of token B for 1_000 of token A
+    let provider = env.fund_user(&keys, 1_000, 5_000);
+    let result = env.send(
+        &[instructions::add_liquidity(
+            &keys,
+            &provider.pubkey(),
+            1_000,
+            5_000,
+            1_000,
+            2_001,
+            None,
+            &[],
+        )],
+        &[&provider],
+    );
+
+    assert_eq!(
+        error_code(&result.unwrap_err()),
+        Some(ErrorCode::InsufficientBAmount.into())
+    );
+}
@@ -0,0 +1,201 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::AccountDeserialize;
+use liquidity_pools::error::ErrorCode;
+use liquidity_pools::state::{LockDuration, PairIndex};
+use liquidity_pools_client::instructions;
+use liquidity_pools_client::pda::PoolKeys;
+use liquidity_pools_tests::{error_code, TestEnv, TOKEN_2022_PROGRAM_ID, TOKEN_PROGRAM_ID};
+use solana_sdk::signature::{Keypair, Signer};
+
+const AMOUNT: u64 = 1_000 * 1_000_000_000;
+
+fn setup_pool(env: &mut TestEnv) -> (PoolKeys, Keypair, Keypair) {
+    let admin = env.initialize(0);
+    let creator = env.create_user();
+    let keys = env.create_pool(
+        &creator,
+        (9, 6),
+        (TOKEN_PROGRAM_ID, TOKEN_2022_PROGRAM_ID),
+        100,
+    );
+    (keys, creator, admin)
+}
+
+#[test]
+fn creator_can_close_an_unused_pool() {
+    let mut env = TestEnv::new();
+    let (keys, creator, _) = setup_pool(&mut env);
+    let recipient = Pubkey::new_unique();
+    let rent = env.lamports(&keys.pool)
+        + env.lamports(&keys.token_vault_a)
+        + env.lamports(&keys.token_vault_b);
+
+    env.send(
+        &[instructions::close_pool(
+            &creator.pubkey(),
+            &keys,
+            &recipient,
+            false,
+            env.fetch_pool(&keys.pool).pair_index_page,
+        )],
+        &[&creator],
+    )
+    .expect("close_pool failed");
+
+    assert_eq!(env.lamports(&keys.pool), 0);
+    assert_eq!(env.lamports(&keys.token_vault_a), 0);
+    assert_eq!(env.lamports(&keys.token_vault_b), 0);
+    assert_eq!(env.lamports(&recipient), rent);
+
+    // The pair index no longer lists the pool
+    let account = env.svm.get_account(&keys.pair_index(0)).unwrap();
+    let pair_index = PairIndex::try_deserialize(&mut account.data.as_slice()).unwrap();
+    assert!(pair_index.pools.is_empty());
+}
+
+#[test]
+fn authority_can_close_a_drained_pool() {
+    let mut env = TestEnv::new();
+    let (keys, _, admin) = setup_pool(&mut env);
+    let provider = env.fund_user(&keys, AMOUNT, AMOUNT);
+    env.add_liquidity(&keys, &provider, AMOUNT, AMOUNT)
+        .expect("add_liquidity failed");
+
+    // Burning the whole LP supply empties both vaults
+    let lp_amount = env.token_balance(&keys.user_ata_lp(&provider.pubkey()));
+    env.send(
+        &[instructions::remove_liquidity(
+            &keys,
+            &provider.pubkey(),
+            lp_amount,
+            1,
+            1,
+            None,
+            &[],
+            false,
+        )],
+        &[&provider],
+    )
+    .expect("remove_liquidity failed");
+    assert_eq!(env.reserves(&keys), (0, 0));
+
+    env.send(
+        &[instructions::close_pool(
+            &admin.pubkey(),
+            &keys,
+            &admin.pubkey(),
+            false,
+            env.fetch_pool(&keys.pool).pair_index_page,
+        )],
+        &[&admin],
+    )
+    .expect("close_pool failed");
+    assert_eq!(env.lamports(&keys.pool), 0);
+}
+
+#[test]
+fn closing_a_pool_refunds_its_lock_vault() {
+    let mut env = TestEnv::new();
+    let (keys, creator, _) = setup_pool(&mut env);
+    let provider = env.fund_user(&keys, AMOUNT, AMOUNT);
+    env.add_liquidity(&keys, &provider, AMOUNT, AMOUNT)
+        .expect("add_liquidity failed");
+
+    // A lock position creates the lock vault, it stays once the position is unlocked
+    let lp_amount = env.token_balance(&keys.user_ata_lp(&provider.pubkey()));
+    env.send(
+        &[instructions::lock_lp(
+            &keys,
+            &provider.pubkey(),
+            0,
+            lp_amount,
+            LockDuration::OneMonth,
+        )],
+        &[&provider],
+    )
+    .expect("lock_lp failed");
+    env.warp(LockDuration::OneMonth.seconds());
+    env.send(
+        &[
+            instructions::unlock_lp(&keys, &provider.pubkey(), 0, &[]),
+            instructions::remove_liquidity(
+                &keys,
+                &provider.pubkey(),
+                lp_amount,
+                1,
+                1,
+                None,
+                &[],
+                false,
+            ),
+        ],
+        &[&provider],
+    )
+    .expect("withdrawal failed");
+
+    let recipient = Pubkey::new_unique();
+    let rent = env.lamports(&keys.pool)
+        + env.lamports(&keys.token_vault_a)
+        + env.lamports(&keys.token_vault_b)
+        + env.lamports(&keys.lock_vault());
+    env.send(
+        &[instructions::close_pool(
+            &creator.pubkey(),
+            &keys,
+            &recipient,
+            true,
+            env.fetch_pool(&keys.pool).pair_index_page,
+        )],
+        &[&creator],
+    )
+    .expect("close_pool failed");
+
+    assert_eq!(env.lamports(&keys.lock_vault()), 0);
+    assert_eq!(env.lamports(&recipient), rent);
+}
+
+#[test]
+fn cannot_close_a_pool_with_liquidity() {
+    let mut env = TestEnv::new();
+    let (keys, creator, _) = setup_pool(&mut env);
+    let provider = env.fund_user(&keys, AMOUNT, AMOUNT);
+    env.add_liquidity(&keys, &provider, AMOUNT, AMOUNT)
+        .expect("add_liquidity failed");
+
+    let result = env.send(
+        &[instructions::close_pool(
+            &creator.pubkey(),
+            &keys,
+            &creator.pubkey(),
+            false,
+            env.fetch_pool(&keys.pool).pair_index_page,
+        )],
+        &[&creator],
+    );
+    assert_eq!(
+        error_code(&result.unwrap_err()),
+        Some(6000 + ErrorCode::PoolNotEmpty as u32)
+    );
+}
+
+#[test]
+fn random_user_cannot_close_a_pool() {
+    let mut env = TestEnv::new();
+    let (keys, _, _) = setup_pool(&mut env);
+    let user = env.create_user();
+
+    let result = env.send(
+        &[instructions::close_pool(
+            &user.pubkey(),
+            &keys,
+            &user.pubkey(),
+            false,
+            env.fetch_pool(&keys.pool).pair_index_page,
+        )],
+        &[&user],
+    );
+    assert_eq!(
+        error_code(&result.unwrap_err()),
+        Some(6000 + ErrorCode::InvalidAuthority as u32)
+    );
+}
@@ -0,0 +1,129 @@
+use anchor_lang::error::ErrorCode as AnchorErrorCode;
+use anchor_lang::solana_program::program_pack::Pack;
+use anchor_spl::token_2022::spl_token_2022;
+use liquidity_pools::error::ErrorCode;
+use liquidity_pools_client::instructions;
+use liquidity_pools_tests::{error_code, TestEnv, TOKEN_2022_PROGRAM_ID, TOKEN_PROGRAM_ID};
+use solana_sdk::signature::Signer;
+
+#[test]
+fn can_create_a_pool() {
+    let mut env = TestEnv::new();
+    let creator = env.create_user();
+
+    let keys = env.create_pool(
+        &creator,
+        (9, 6),
+        (TOKEN_PROGRAM_ID, TOKEN_2022_PROGRAM_ID),
+        100,
+    );
+
+    let pool = env.fetch_pool(&keys.pool);
+    assert_eq!(pool.token_mint_a, keys.token_mint_a);
+    assert_eq!(pool.token_mint_b, keys.token_mint_b);
+    assert_eq!(pool.token_vault_a, keys.token_vault_a);
+    assert_eq!(pool.token_vault_b, keys.token_vault_b);
+    assert_eq!(pool.fee_bp, 100);
+    assert_eq!(pool.creator, creator.pubkey());
+
+    let lp_mint_account = env.svm.get_account(&keys.lp_mint).unwrap();
+    assert_eq!(lp_mint_account.owner, TOKEN_2022_PROGRAM_ID);
+    let lp_mint = spl_token_2022::state::Mint::unpack(&lp_mint_account.data).unwrap();
+    assert_eq!(lp_mint.decimals, 9);
+    assert_eq!(lp_mint.supply, 0);
+    assert_eq!(lp_mint.mint_authority, Some(keys.pool).into());
+    assert_eq!(lp_mint.freeze_authority, Some(keys.pool).into());
+
+    assert_eq!(
+        env.owner(&keys.token_vault_a),
+        env.owner(&keys.token_mint_a)
+    );
+    assert_eq!(
+        env.owner(&keys.token_vault_b),
+        env.owner(&keys.token_mint_b)
+    );
+}
+
+#[test]
+fn fails_if_token_a_is_smaller_than_token_b() {
+    let mut env = TestEnv::new();
+    env.initialize(0);
+    let creator = env.create_user();
+    let (token_mint_a, token_mint_b) =
+        env.create_mint_pair((9, 9), (TOKEN_PROGRAM_ID, TOKEN_2022_PROGRAM_ID));
+
+    // Pool derived with the mints in the wrong order
+    let keys = env.pool_keys(token_mint_b, token_mint_a, 0);
+    let result = env.send(
+        &[instructions::create_pool(
+            &creator.pubkey(),
+            &keys,
+            100,
+            env.fetch_config().registry_page(),
+            env.pair_index_page(&keys),
+        )],
+        &[&creator],
+    );
+
+    assert_eq!(
+        error_code(&result.unwrap_err()),
+        Some(ErrorCode::InvalidTokenOrder.into())
+    );
+}
+
+#[test]
+fn fails_if_seeds_order_is_incorrect() {
+    let mut env = TestEnv::new();
+    env.initialize(0);
+    let creator = env.create_user();
+    let (token_mint_a, token_mint_b) =
+        env.create_mint_pair((9, 9), (TOKEN_PROGRAM_ID, TOKEN_PROGRAM_ID));
+
+    // Pool derived in the right order, mints passed in the wrong order
+    let mut keys = env.pool_keys(token_mint_a, token_mint_b, 0);
+    keys.token_mint_a = token_mint_b;
+    keys.token_mint_b = token_mint_a;
+    let result = env.send(
+        &[instructions::create_pool(
+            &creator.pubkey(),
+            &keys,
+            100,
+            env.fetch_config().registry_page(),
+            env.pair_index_page(&keys),
+        )],
+        &[&creator],
+    );
+
+    assert_eq!(
+        error_code(&result.unwrap_err()),
+        Some(AnchorErrorCode::ConstraintSeeds.into())
+    );
+}
+
+#[test]
+fn can_create_multiple_pools_with_the_same_tokens() {
+    let mut env = TestEnv::new();
+    env.initialize(0);
+    let creator = env.create_user();
+    let (token_mint_a, token_mint_b) =
+        env.create_mint_pair((9, 6), (TOKEN_2022_PROGRAM_ID, TOKEN_PROGRAM_ID));
+
+    // Pools of a pair are told apart by their index, their fee can be the same
+    for index in [0, 1] {
+        let keys = env.pool_keys(token_mint_a, token_mint_b, index);
+        env.send(
+            &[instructions::create_pool(
+                &creator.pubkey(),
+                &keys,
+                100,
+                env.fetch_config().registry_page(),
+                env.pair_index_page(&keys),
+            )],
+            &[&creator],
+        )
+        .expect("create_pool failed");
+
+        let pool = env.fetch_pool(&keys.pool);
+        assert_eq!((pool.index, pool.fee_bp), (index, 100));
+    }
+}
@@ -0,0 +1,178 @@
+use anchor_lang::error::ErrorCode as AnchorErrorCode;
+use anchor_lang::prelude::Pubkey;
+use liquidity_pools::error::ErrorCode;
+use liquidity_pools_client::instructions::{self, CreatePoolOptions};
+use liquidity_pools_client::pda::PoolKeys;
+use liquidity_pools_tests::{error_code, TestEnv, TOKEN_2022_PROGRAM_ID, TOKEN_PROGRAM_ID};
+use solana_sdk::instruction::Instruction;
+use solana_sdk::native_token::LAMPORTS_PER_SOL;
+use solana_sdk::signature::{Keypair, Signer};
+
+const TOKEN_FEE: u64 = 50 * 1_000_000;
+
+fn set_creation_fee(authority: &Keypair, fee: u64, mint: Pubkey) -> Instruction {
+    instructions::update_config(&authority.pubkey(), None, None, None, Some(fee), Some(mint))
+}
+
+fn new_pool_keys(env: &mut TestEnv) -> PoolKeys {
+    let (token_mint_a, token_mint_b) =
+        env.create_mint_pair((9, 9), (TOKEN_PROGRAM_ID, TOKEN_PROGRAM_ID));
+    env.pool_keys(token_mint_a, token_mint_b, 0)
+}
+
+#[test]
+fn creation_fee_in_lamports_goes_to_the_fee_recipient() {
+    let mut env = TestEnv::new();
+    let admin = env.initialize(0);
+    env.send(
+        &[set_creation_fee(
+            &admin,
+            LAMPORTS_PER_SOL,
+            Pubkey::default(),
+        )],
+        &[&admin],
+    )
+    .expect("update_config failed");
+    let config = env.fetch_config();
+    assert_eq!(config.pool_creation_fee, LAMPORTS_PER_SOL);
+    assert!(config.charges_creation_fee_in_lamports());
+
+    let recipient = config.protocol_fee_recipient;
+    let before = env.lamports(&recipient);
+    let creator = env.create_user();
+    env.create_pool(&creator, (9, 9), (TOKEN_PROGRAM_ID, TOKEN_PROGRAM_ID), 30);
+    assert_eq!(env.lamports(&recipient), before + LAMPORTS_PER_SOL);
+}
+
+#[test]
+fn creation_fee_in_tokens_goes_to_the_fee_recipient() {
+    let mut env = TestEnv::new();
+    let admin = env.initialize(0);
+    let fee_mint = env.create_mint(6, TOKEN_2022_PROGRAM_ID);
+    env.send(&[set_creation_fee(&admin, TOKEN_FEE, fee_mint)], &[&admin])
+        .expect("update_config failed");
+
+    let recipient_ata = env.create_ata(&fee_mint, &env.fetch_config().protocol_fee_recipient);
+    let creator = env.create_user();
+    let creator_ata = env.create_ata(&fee_mint, &creator.pubkey());
+    env.mint_to(&creator_ata, TOKEN_FEE);
+
+    env.create_pool(&creator, (9, 9), (TOKEN_PROGRAM_ID, TOKEN_PROGRAM_ID), 30);
+    assert_eq!(env.token_balance(&creator_ata), 0);
+    assert_eq!(env.token_balance(&recipient_ata), TOKEN_FEE);
+
+    // The next pool cannot be paid for
+    let keys = new_pool_keys(&mut env);
+    let options = env.create_pool_options(&creator.pubkey(), &keys);
+    let result = env.send(
+        &[instructions::create_pool_with_options(
+            &creator.pubkey(),
+            &keys,
+            30,
+            &options,
+        )],
+        &[&creator],
+    );
+    assert!(result.is_err());
+}
+
+#[test]
+fn creation_fee_accounts_are_checked() {
+    let mut env = TestEnv::new();
+    let admin = env.initialize(0);
+    env.send(
+        &[set_creation_fee(
+            &admin,
+            LAMPORTS_PER_SOL,
+            Pubkey::default(),
+        )],
+        &[&admin],
+    )
+    .expect("update_config failed");
+    let creator = env.create_user();
+
+    let keys = new_pool_keys(&mut env);
+    let registry_page = env.fetch_config().registry_page();
+    let pair_index_page = env.pair_index_page(&keys);
+    let result = env.send(
+        &[instructions::create_pool(
+            &creator.pubkey(),
+            &keys,
+            30,
+            registry_page,
+            pair_index_page,
+        )],
+        &[&creator],
+    );
+    assert_eq!(
+        error_code(&result.unwrap_err()),
+        Some(6000 + ErrorCode::MissingCreationFeeAccounts as u32)
+    );
+
+    let options = CreatePoolOptions {
+        creation_fee_recipient: Some(creator.pubkey()),
+        ..env.create_pool_options(&creator.pubkey(), &keys)
+    };
+    let result = env.send(
+        &[instructions::create_pool_with_options(
+            &creator.pubkey(),
+            &keys,
+            30,
+            &options,
+        )],
+        &[&creator],
+    );
+    assert_eq!(
+        error_code(&result.unwrap_err()),
+        Some(6000 + ErrorCode::InvalidFeeRecipient as u32)
+    );
+}
+
+#[test]
+fn creation_fee_is_paid_from_the_creator_token_account() {
+    let mut env = TestEnv::new();
+    let admin = env.initialize(0);
+    let fee_mint = env.create_mint(6, TOKEN_2022_PROGRAM_ID);
+    env.send(&[set_creation_fee(&admin, TOKEN_FEE, fee_mint)], &[&admin])
+        .expect("update_config failed");
+
+    env.create_ata(&fee_mint, &env.fetch_config().protocol_fee_recipient);
+    let creator = env.create_user();
+    let creator_ata = env.create_ata(&fee_mint, &creator.pubkey());
+    let other = env.create_user();
+    let other_ata = env.create_ata(&fee_mint, &other.pubkey());
+    env.mint_to(&other_ata, TOKEN_FEE);
+
+    let keys = new_pool_keys(&mut env);
+    let options = env.create_pool_options(&creator.pubkey(), &keys);
+    let mut ix = instructions::create_pool_with_options(&creator.pubkey(), &keys, 30, &options);
+    let meta = ix
+        .accounts
+        .iter_mut()
+        .find(|meta| meta.pubkey == creator_ata)
+        .expect("creator fee account");
+    meta.pubkey = other_ata;
+    let result = env.send(&[ix], &[&creator]);
+    assert_eq!(
+        error_code(&result.unwrap_err()),
+        Some(AnchorErrorCode::ConstraintTokenOwner as u32)
+    );
+    assert_eq!(env.token_balance(&other_ata), TOKEN_FEE);
+}
+
+#[test]
+fn only_the_fee_manager_sets_the_creation_fee() {
+    let mut env = TestEnv::new();
+    env.initialize(0);
+    let user = env.create_user();
+
+    let result = env.send(
+        &[set_creation_fee(&user, LAMPORTS_PER_SOL, Pubkey::default())],
+        &[&user],
+    );
+    assert_eq!(
+        error_code(&result.unwrap_err()),
+        Some(6000 + ErrorCode::NotFeeManager as u32)
+    );
+    assert_eq!(env.fetch_config().pool_creation_fee, 0);
+}
@@ -0,0 +1,165 @@
+use liquidity_pools::error::ErrorCode;
+use liquidity_pools::math;
+use liquidity_pools_client::instructions::{self, SwapDirection};
+use liquidity_pools_client::pda::PoolKeys;
+use liquidity_pools_client::quote::{quote_swap, PoolState};
+use liquidity_pools_tests::{error_code, FundedPool, TestEnv};
+use solana_sdk::signature::{Keypair, Signer};
+
+const RESERVE: u64 = 1_000_000 * 1_000_000_000;
+const AMOUNT_IN: u64 = 1_000 * 1_000_000_000;
+
+// Pool with a 1% swap fee, 20% of which goes to the protocol, under a config
+// letting creators take up to 50% of the rest
+fn setup_pool(env: &mut TestEnv) -> (PoolKeys, Keypair, Keypair) {
+    let admin = env.initialize(2_000);
+    env.send(
+        &[instructions::set_max_creator_fee(&admin.pubkey(), 5_000)],
+        &[&admin],
+    )
+    .expect("set_max_creator_fee failed");
+    let FundedPool { keys, creator, .. } = env.create_test_pool(100, (RESERVE, RESERVE));
+    (keys, admin, creator)
+}
+
+fn set_creator_fee(env: &mut TestEnv, keys: &PoolKeys, creator: &Keypair, creator_fee_bp: u16) {
+    env.send(
+        &[instructions::set_creator_fee(
+            &creator.pubkey(),
+            &keys.pool,
+            creator_fee_bp,
+        )],
+        &[creator],
+    )
+    .expect("set_creator_fee failed");
+}
+
+#[test]
+fn swap_accrues_the_creator_share_of_the_lp_fee() {
+    let mut env = TestEnv::new();
+    let (keys, admin, creator) = setup_pool(&mut env);
+    set_creator_fee(&mut env, &keys, &creator, 2_500);
+    let pool = env.fetch_pool(&keys.pool);
+    assert_eq!((pool.creator_fee_bp, pool.fee_bp), (2_500, 100));
+
+    let quote = quote_swap(&pool, AMOUNT_IN, RESERVE, RESERVE).unwrap();
+    let trader = env.fund_user(&keys, AMOUNT_IN, 0);
+    env.swap(&keys, &trader, SwapDirection::AToB, AMOUNT_IN, 1, None)
+        .expect("swap failed");
+
+    let protocol_fee = math::split_fee(quote.fee_amount, 2_000).unwrap();
+    let creator_fee = math::split_fee(protocol_fee.remainder, 2_500)
+        .unwrap()
+        .share;
+    assert!(creator_fee > 0);
+    let pool = env.fetch_pool(&keys.pool);
+    assert_eq!(pool.protocol_fees_a, protocol_fee.share);
+    assert_eq!((pool.creator_fees_a, pool.creator_fees_b), (creator_fee, 0));
+
+    // Creator fees are not part of the reserves either
+    let state: PoolState = env
+        .view(instructions::get_pool_state(&keys), &admin)
+        .expect("get_pool_state failed");
+    assert_eq!(
+        state.reserve_a,
+        env.token_balance(&keys.token_vault_a) - protocol_fee.share - creator_fee
+    );
+}
+
+#[test]
+fn creator_claims_the_creator_fees() {
+    let mut env = TestEnv::new();
+    let (keys, _, creator) = setup_pool(&mut env);
+    set_creator_fee(&mut env, &keys, &creator, 5_000);
+    let trader = env.fund_user(&keys, AMOUNT_IN, AMOUNT_IN);
+    for direction in [SwapDirection::AToB, SwapDirection::BToA] {
+        env.swap(&keys, &trader, direction, AMOUNT_IN, 1, None)
+            .expect("swap failed");
+    }
+    let pool = env.fetch_pool(&keys.pool);
+    let (creator_fees_a, creator_fees_b) = (pool.creator_fees_a, pool.creator_fees_b);
+    let protocol_fees_a = pool.protocol_fees_a;
+
+    // Nobody else withdraws them
+    let creator_ata_a = env.create_ata(&keys.token_mint_a, &creator.pubkey());
+    let creator_ata_b = env.create_ata(&keys.token_mint_b, &creator.pubkey());
+    let result = env.send(
+        &[instructions::claim_creator_fees(&keys, &trader.pubkey())],
+        &[&trader],
+    );
+    assert_eq!(
+        error_code(&result.unwrap_err()),
+        Some(6000 + ErrorCode::InvalidAuthority as u32)
+    );
+
+    env.send(
+        &[instructions::claim_creator_fees(&keys, &creator.pubkey())],
+        &[&creator],
+    )
+    .expect("claim_creator_fees failed");
+    assert_eq!(env.token_balance(&creator_ata_a), creator_fees_a);
+    assert_eq!(env.token_balance(&creator_ata_b), creator_fees_b);
+    let pool = env.fetch_pool(&keys.pool);
+    assert_eq!((pool.creator_fees_a, pool.creator_fees_b), (0, 0));
+    assert_eq!(pool.protocol_fees_a, protocol_fees_a);
+}
+
+#[test]
+fn creator_fee_is_bounded_by_the_config() {
+    let mut env = TestEnv::new();
+    let (keys, admin, creator) = setup_pool(&mut env);
+
+    let result = env.send(
+        &[instructions::set_creator_fee(
+            &creator.pubkey(),
+            &keys.pool,
+            5_001,
+        )],
+        &[&creator],
+    );
+    assert_eq!(
+        error_code(&result.unwrap_err()),
+        Some(6000 + ErrorCode::InvalidCreatorFee as u32)
+    );
+
+    // Only the creator sets it
+    let result = env.send(
+        &[instructions::set_creator_fee(
+            &admin.pubkey(),
+            &keys.pool,
+            100,
+        )],
+        &[&admin],
+    );
+    assert_eq!(
+        error_code(&result.unwrap_err()),
+        Some(6000 + ErrorCode::InvalidAuthority as u32)
+    );
+    assert_eq!(env.fetch_pool(&keys.pool).creator_fee_bp, 0);
+}
+
+#[test]
+fn only_the_fee_manager_sets_the_max_creator_fee() {
+    let mut env = TestEnv::new();
+    let admin = env.initialize(0);
+    assert_eq!(env.fetch_config().max_creator_fee_bp, 0);
+    let user = env.create_user();
+
+    let result = env.send(
+        &[instructions::set_max_creator_fee(&user.pubkey(), 1_000)],
+        &[&user],
+    );
+    assert_eq!(
+        error_code(&result.unwrap_err()),
+        Some(6000 + ErrorCode::NotFeeManager as u32)
+    );
+
+    let result = env.send(
+        &[instructions::set_max_creator_fee(&admin.pubkey(), 10_001)],
+        &[&admin],
+    );
+    assert_eq!(
+        error_code(&result.unwrap_err()),
+        Some(6000 + ErrorCode::InvalidCreatorFee as u32)
+    );
+}
@@ -0,0 +1,233 @@
+use liquidity_pools::error::ErrorCode;
+use liquidity_pools::state::FeeRecipient;
+use liquidity_pools_client::instructions::{self, SwapDirection};
+use liquidity_pools_client::pda::PoolKeys;
+use liquidity_pools_tests::{error_code, FundedPool, TestEnv};
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+
+const RESERVE: u64 = 1_000_000 * 1_000_000_000;
+const AMOUNT_IN: u64 = 1_000 * 1_000_000_000;
+
+fn queue_fee_recipients(authority: &Keypair, entries: &[(Pubkey, u16)]) -> Instruction {
+    let fee_recipients = entries
+        .iter()
+        .map(|&(recipient, weight_bp)| FeeRecipient {
+            recipient,
+            weight_bp,
+        })
+        .collect();
+    instructions::queue_config_change(
+        &authority.pubkey(),
+        None,
+        None,
+        None,
+        None,
+        None,
+        Some(fee_recipients),
+    )
+}
+
+// Applies the split right away, the config has no timelock delay
+fn set_fee_recipients(env: &mut TestEnv, authority: &Keypair, entries: &[(Pubkey, u16)]) {
+    env.send(
+        &[
+            queue_fee_recipients(authority, entries),
+            instructions::execute_config_change(&authority.pubkey()),
+        ],
+        &[authority],
+    )
+    .expect("fee split change failed");
+}
+
+// Pool with a 1% swap fee, 20% of which goes to the protocol, and fees accrued
+// in both tokens
+fn setup_pool(env: &mut TestEnv) -> (PoolKeys, Keypair) {
+    let (FundedPool { keys, .. }, admin) = env.setup_pool(2_000, 100, (RESERVE, RESERVE));
+
+    let trader = env.fund_user(&keys, AMOUNT_IN, AMOUNT_IN);
+    for direction in [SwapDirection::AToB, SwapDirection::BToA] {
+        env.swap(&keys, &trader, direction, AMOUNT_IN, 1, None)
+            .expect("swap failed");
+    }
+    (keys, admin)
+}
+
+#[test]
+fn initialize_sends_all_fees_to_the_fee_recipient() {
+    let mut env = TestEnv::new();
+    env.initialize(0);
+    let config = env.fetch_config();
+    assert_eq!(
+        config.active_fee_recipients(),
+        &[FeeRecipient {
+            recipient: config.protocol_fee_recipient,
+            weight_bp: 10_000,
+        }]
+    );
+}
+
+#[test]
+fn claim_fees_splits_each_token_by_weight() {
+    let mut env = TestEnv::new();
+    let (keys, admin) = setup_pool(&mut env);
+    let recipients = [
+        (Pubkey::new_unique(), 5_000),
+        (Pubkey::new_unique(), 3_333),
+        (Pubkey::new_unique(), 1_667),
+    ];
+    set_fee_recipients(&mut env, &admin, &recipients);
+
+    let atas: Vec<_> = recipients
+        .iter()
+        .map(|(recipient, _)| {
+            (
+                env.create_ata(&keys.token_mint_a, recipient),
+                env.create_ata(&keys.token_mint_b, recipient),
+            )
+        })
+        .collect();
+    let pool = env.fetch_pool(&keys.pool);
+    let (fees_a, fees_b) = (pool.protocol_fees_a, pool.protocol_fees_b);
+    assert!(fees_a > 0 && fees_b > 0);
+
+    let config = env.fetch_config();
+    env.send(
+        &[instructions::claim_fees(
+            &keys,
+            &instructions::fee_recipients(&config),
+        )],
+        &[&admin],
+    )
+    .expect("claim_fees failed");
+
+    // Every recipient but the last gets its floored share, the last one the rest
+    let (first_a, first_b) = (fees_a / 2, fees_b / 2);
+    let (second_a, second_b) = (fees_a * 3_333 / 10_000, fees_b * 3_333 / 10_000);
+    let balances: Vec<_> = atas
+        .iter()
+        .map(|(ata_a, ata_b)| (env.token_balance(ata_a), env.token_balance(ata_b)))
+        .collect();
+    assert_eq!(
+        balances,
+        [
+            (first_a, first_b),
+            (second_a, second_b),
+            (fees_a - first_a - second_a, fees_b - first_b - second_b),
+        ]
+    );
+    let pool = env.fetch_pool(&keys.pool);
+    assert_eq!((pool.protocol_fees_a, pool.protocol_fees_b), (0, 0));
+}
+
+#[test]
+fn claim_fees_needs_every_recipient_in_order() {
+    let mut env = TestEnv::new();
+    let (keys, admin) = setup_pool(&mut env);
+    let (first, second) = (Pubkey::new_unique(), Pubkey::new_unique());
+    set_fee_recipients(&mut env, &admin, &[(first, 6_000), (second, 4_000)]);
+    for recipient in [first, second] {
+        env.create_ata(&keys.token_mint_a, &recipient);
+        env.create_ata(&keys.token_mint_b, &recipient);
+    }
+
+    for recipients in [vec![first], vec![second, first]] {
+        let result = env.send(&[instructions::claim_fees(&keys, &recipients)], &[&admin]);
+        assert_eq!(
+            error_code(&result.unwrap_err()),
+            Some(6000 + ErrorCode::InvalidFeeRecipient as u32)
+        );
+    }
+}
+
+#[test]
+fn queue_config_change_rejects_invalid_splits() {
+    let mut env = TestEnv::new();
+    let admin = env.initialize(0);
+    let (first, second) = (Pubkey::new_unique(), Pubkey::new_unique());
+
+    for entries in [
+        vec![],
+        vec![(first, 6_000), (second, 3_000)],
+        vec![(first, 10_000), (second, 0)],
+        vec![(first, 5_000), (first, 5_000)],
+        (0..5).map(|_| (Pubkey::new_unique(), 2_000)).collect(),
+    ] {
+        let result = env.send(&[queue_fee_recipients(&admin, &entries)], &[&admin]);
+        assert_eq!(
+            error_code(&result.unwrap_err()),
+            Some(6000 + ErrorCode::InvalidFeeRecipients as u32)
+        );
+    }
+
+    // Only the fee manager changes the split
+    let user = env.create_user();
+    let result = env.send(
+        &[queue_fee_recipients(&user, &[(user.pubkey(), 10_000)])],
+        &[&user],
+    );
+    assert_eq!(
+        error_code(&result.unwrap_err()),
+        Some(6000 + ErrorCode::NotFeeManager as u32)
+    );
+}
+
+#[test]
+fn fee_split_and_fee_recipient_stay_consistent() {
+    let mut env = TestEnv::new();
+    let admin = env.initialize(0);
+    let (first, second) = (Pubkey::new_unique(), Pubkey::new_unique());
+
+    // The first entry of a new split becomes the protocol fee recipient
+    set_fee_recipients(&mut env, &admin, &[(first, 6_000), (second, 4_000)]);
+    assert_eq!(env.fetch_config().protocol_fee_recipient, first);
+
+    // A new recipient takes all of the claimed fees
+    env.send(
+        &[
+            instructions::queue_config_change(
+                &admin.pubkey(),
+                Some(second),
+                None,
+                None,
+                None,
+                None,
+                None,
+            ),
+            instructions::execute_config_change(&admin.pubkey()),
+        ],
+        &[&admin],
+    )
+    .expect("recipient change failed");
+    let config = env.fetch_config();
+    assert_eq!(config.protocol_fee_recipient, second);
+    assert_eq!(
+        config.active_fee_recipients(),
+        &[FeeRecipient {
+            recipient: second,
+            weight_bp: 10_000,
+        }]
+    );
+
+    // Both can not be changed at once
+    let result = env.send(
+        &[instructions::queue_config_change(
+            &admin.pubkey(),
+            Some(first),
+            None,
+            None,
+            None,
+            None,
+            Some(vec![FeeRecipient {
+                recipient: second,
+                weight_bp: 10_000,
+            }]),
+        )],
+        &[&admin],
+    );
+    assert_eq!(
+        error_code(&result.unwrap_err()),
+        Some(6000 + ErrorCode::InvalidFeeRecipients as u32)
+    );
+}
@@ -0,0 +1,137 @@
+use liquidity_pools::error::ErrorCode;
+use liquidity_pools_client::instructions;
+use liquidity_pools_tests::{cpi_proxy, error_code, TestEnv};
+use solana_sdk::signature::Signer;
+
+const RESERVE: u64 = 1_000_000 * 1_000_000_000;
+const LOAN: u64 = 1_000 * 1_000_000_000;
+// 0.3% of LOAN, the pool swap fee
+const FEE: u64 = LOAN * 30 / 10_000;
+
+#[test]
+fn flash_loan_is_repaid_with_the_fee() {
+    let mut env = TestEnv::new();
+    let (pool, _) = env.setup_pool(0, 30, (RESERVE, RESERVE));
+    let keys = &pool.keys;
+    let borrower = env.fund_user(keys, FEE, 0);
+
+    env.send(
+        &[
+            instructions::flash_loan(keys, &borrower.pubkey(), LOAN, 0),
+            instructions::flash_repay(keys, &borrower.pubkey(), LOAN + FEE, 0),
+        ],
+        &[&borrower],
+    )
+    .expect("flash loan failed");
+
+    assert_eq!(env.token_balance(&keys.user_ata_a(&borrower.pubkey())), 0);
+    assert_eq!(env.reserves(keys), (RESERVE + FEE, RESERVE));
+    let state = env.fetch_pool(&keys.pool);
+    assert!(!state.flash_loan_active);
+    assert_eq!(
+        (state.flash_loan_amount_a, state.flash_loan_amount_b),
+        (0, 0)
+    );
+}
+
+#[test]
+fn flash_loan_needs_a_top_level_flash_repay() {
+    let mut env = TestEnv::new();
+    let (pool, _) = env.setup_pool(0, 30, (RESERVE, RESERVE));
+    let keys = &pool.keys;
+    let borrower = env.fund_user(keys, FEE, 0);
+    let loan = instructions::flash_loan(keys, &borrower.pubkey(), LOAN, 0);
+    let repay = instructions::flash_repay(keys, &borrower.pubkey(), LOAN + FEE, 0);
+
+    let result = env.send(std::slice::from_ref(&loan), &[&borrower]);
+    assert_eq!(
+        error_code(&result.unwrap_err()),
+        Some(6000 + ErrorCode::FlashLoanNotRepaid as u32)
+    );
+
+    // A repayment made by another program is not seen by the loan
+    let result = env.send(
+        &[loan.clone(), cpi_proxy::wrap(repay.clone())],
+        &[&borrower],
+    );
+    assert_eq!(
+        error_code(&result.unwrap_err()),
+        Some(6000 + ErrorCode::FlashLoanNotRepaid as u32)
+    );
+
+    // Neither is the loan when it is taken by another program
+    let result = env.send(&[cpi_proxy::wrap(loan), repay], &[&borrower]);
+    assert_eq!(
+        error_code(&result.unwrap_err()),
+        Some(6000 + ErrorCode::FlashLoanCpiNotAllowed as u32)
+    );
+    assert_eq!(env.reserves(keys), (RESERVE, RESERVE));
+}
+
+#[test]
+fn flash_repay_short_of_the_fee_fails() {
+    let mut env = TestEnv::new();
+    let (pool, _) = env.setup_pool(0, 30, (RESERVE, RESERVE));
+    let keys = &pool.keys;
+    let borrower = env.fund_user(keys, FEE, 0);
+
+    let result = env.send(
+        &[
+            instructions::flash_loan(keys, &borrower.pubkey(), LOAN, 0),
+            instructions::flash_repay(keys, &borrower.pubkey(), LOAN + FEE - 1, 0),
+        ],
+        &[&borrower],
+    );
+    assert_eq!(
+        error_code(&result.unwrap_err()),
+        Some(6000 + ErrorCode::InvariantViolated as u32)
+    );
+    assert_eq!(env.reserves(keys), (RESERVE, RESERVE));
+}
+
+#[test]
+fn flash_loan_is_rejected_while_paused() {
+    let mut env = TestEnv::new();
+    let (pool, admin) = env.setup_pool(0, 30, (RESERVE, RESERVE));
+    let keys = &pool.keys;
+    let borrower = env.fund_user(keys, FEE, 0);
+    env.send(
+        &[instructions::set_paused(&admin.pubkey(), true)],
+        &[&admin],
+    )
+    .expect("set_paused failed");
+
+    let result = env.send(
+        &[
+            instructions::flash_loan(keys, &borrower.pubkey(), LOAN, 0),
+            instructions::flash_repay(keys, &borrower.pubkey(), LOAN + FEE, 0),
+        ],
+        &[&borrower],
+    );
+    assert_eq!(
+        error_code(&result.unwrap_err()),
+        Some(6000 + ErrorCode::Paused as u32)
+    );
+}
+
+#[test]
+fn pool_cannot_be_updated_during_a_flash_loan() {
+    let mut env = TestEnv::new();
+    let (pool, admin) = env.setup_pool(0, 30, (RESERVE, RESERVE));
+    let keys = &pool.keys;
+    let borrower = env.fund_user(keys, FEE, 0);
+
+    let result = env.send(
+        &[
+            instructions::flash_loan(keys, &borrower.pubkey(), LOAN, 0),
+            instructions::update_pool(&admin.pubkey(), &keys.pool, None, None, None, Some(0)),
+            instructions::flash_repay(keys, &borrower.pubkey(), LOAN + FEE, 0),
+        ],
+        &[&borrower, &admin],
+    );
+    assert_eq!(
+        error_code(&result.unwrap_err()),
+        Some(6000 + ErrorCode::FlashLoanActive as u32)
+    );
+    assert_eq!(env.fetch_pool(&keys.pool).fee_bp, 30);
+}
@@ -0,0 +1,290 @@
+use anchor_lang::prelude::Clock;
+use anchor_lang::AccountDeserialize;
+use liquidity_pools::error::ErrorCode;
+use liquidity_pools::state::{FeeRecipient, LockDuration, LockPosition};
+use liquidity_pools_client::instructions;
+use liquidity_pools_client::pda::{find_lock_position_address, PoolKeys};
+use liquidity_pools_tests::{error_code, FundedPool, TestEnv};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+
+const RESERVE: u64 = 1_000_000 * 1_000_000_000;
+
+fn fetch_lock_position(env: &TestEnv, keys: &PoolKeys, owner: &Keypair, id: u64) -> LockPosition {
+    let (address, _) = find_lock_position_address(&keys.pool, &owner.pubkey(), id);
+    let account = env
+        .svm
+        .get_account(&address)
+        .expect("lock position not found");
+    LockPosition::try_deserialize(&mut account.data.as_slice()).unwrap()
+}
+
+// Pool with a provider holding all of its LP tokens
+fn lock(
+    env: &mut TestEnv,
+    keys: &PoolKeys,
+    owner: &Keypair,
+    id: u64,
+    amount: u64,
+    duration: LockDuration,
+) {
+    env.send(
+        &[instructions::lock_lp(
+            keys,
+            &owner.pubkey(),
+            id,
+            amount,
+            duration,
+        )],
+        &[owner],
+    )
+    .expect("lock_lp failed");
+}
+
+fn set_early_exit_penalty(env: &mut TestEnv, admin: &Keypair, early_exit_penalty_bp: u16) {
+    env.send(
+        &[instructions::set_early_exit_penalty(
+            &admin.pubkey(),
+            early_exit_penalty_bp,
+        )],
+        &[admin],
+    )
+    .expect("set_early_exit_penalty failed");
+    assert_eq!(
+        env.fetch_config().early_exit_penalty_bp,
+        early_exit_penalty_bp
+    );
+}
+
+#[test]
+fn locked_positions_are_boosted_by_their_duration() {
+    let mut env = TestEnv::new();
+    env.initialize(0);
+    let FundedPool { keys, provider, .. } = env.create_test_pool(30, (RESERVE, RESERVE));
+    let provider_ata_lp = keys.user_ata_lp(&provider.pubkey());
+    let lp_balance = env.token_balance(&provider_ata_lp);
+
+    lock(&mut env, &keys, &provider, 0, 1_000, LockDuration::OneMonth);
+    lock(
+        &mut env,
+        &keys,
+        &provider,
+        1,
+        1_000,
+        LockDuration::TwelveMonths,
+    );
+
+    let now = env.svm.get_sysvar::<Clock>().unix_timestamp;
+    let position = fetch_lock_position(&env, &keys, &provider, 1);
+    assert_eq!(position.owner, provider.pubkey());
+    assert_eq!(position.pool, keys.pool);
+    assert_eq!(position.amount, 1_000);
+    assert_eq!(position.boosted_amount, 2_000);
+    assert_eq!(position.early_exit_penalty_bp, 0);
+    assert_eq!(
+        position.unlock_at,
+        now + LockDuration::TwelveMonths.seconds()
+    );
+    let position = fetch_lock_position(&env, &keys, &provider, 0);
+    assert_eq!(position.boosted_amount, 1_100);
+    assert_eq!(position.unlock_at, now + LockDuration::OneMonth.seconds());
+
+    let pool = env.fetch_pool(&keys.pool);
+    assert_eq!(
+        (pool.total_locked_lp, pool.total_boosted_lp),
+        (2_000, 3_100)
+    );
+    assert_eq!(env.token_balance(&keys.lock_vault()), 2_000);
+    assert_eq!(env.token_balance(&provider_ata_lp), lp_balance - 2_000);
+}
+
+#[test]
+fn positions_unlock_once_their_duration_has_passed() {
+    let mut env = TestEnv::new();
+    env.initialize(0);
+    let FundedPool { keys, provider, .. } = env.create_test_pool(30, (RESERVE, RESERVE));
+    let provider_ata_lp = keys.user_ata_lp(&provider.pubkey());
+    let lp_balance = env.token_balance(&provider_ata_lp);
+    lock(
+        &mut env,
+        &keys,
+        &provider,
+        0,
+        1_000,
+        LockDuration::ThreeMonths,
+    );
+
+    let result = env.send(
+        &[instructions::unlock_lp(&keys, &provider.pubkey(), 0, &[])],
+        &[&provider],
+    );
+    assert_eq!(
+        error_code(&result.unwrap_err()),
+        Some(6000 + ErrorCode::LockNotExpired as u32)
+    );
+
+    env.warp(LockDuration::ThreeMonths.seconds());
+    env.send(
+        &[instructions::unlock_lp(&keys, &provider.pubkey(), 0, &[])],
+        &[&provider],
+    )
+    .expect("unlock_lp failed");
+
+    assert_eq!(env.token_balance(&provider_ata_lp), lp_balance);
+    let pool = env.fetch_pool(&keys.pool);
+    assert_eq!((pool.total_locked_lp, pool.total_boosted_lp), (0, 0));
+    let (address, _) = find_lock_position_address(&keys.pool, &provider.pubkey(), 0);
+    assert_eq!(env.lamports(&address), 0);
+}
+
+#[test]
+fn early_exits_split_the_penalty_between_the_fee_recipients() {
+    let mut env = TestEnv::new();
+    let admin = env.initialize(0);
+    set_early_exit_penalty(&mut env, &admin, 1_000);
+    let (first, second) = (Pubkey::new_unique(), Pubkey::new_unique());
+    env.send(
+        &[
+            instructions::queue_config_change(
+                &admin.pubkey(),
+                None,
+                None,
+                None,
+                None,
+                None,
+                Some(vec![
+                    FeeRecipient {
+                        recipient: first,
+                        weight_bp: 7_000,
+                    },
+                    FeeRecipient {
+                        recipient: second,
+                        weight_bp: 3_000,
+                    },
+                ]),
+            ),
+            instructions::execute_config_change(&admin.pubkey()),
+        ],
+        &[&admin],
+    )
+    .expect("fee split change failed");
+
+    let FundedPool { keys, provider, .. } = env.create_test_pool(30, (RESERVE, RESERVE));
+    let provider_ata_lp = keys.user_ata_lp(&provider.pubkey());
+    let lp_balance = env.token_balance(&provider_ata_lp);
+    lock(
+        &mut env,
+        &keys,
+        &provider,
+        0,
+        1_000,
+        LockDuration::SixMonths,
+    );
+
+    // The penalty needs the LP token accounts of every fee recipient
+    let first_ata_lp = env.create_ata(&keys.lp_mint, &first);
+    let result = env.send(
+        &[instructions::unlock_lp(
+            &keys,
+            &provider.pubkey(),
+            0,
+            &[first],
+        )],
+        &[&provider],
+    );
+    assert_eq!(
+        error_code(&result.unwrap_err()),
+        Some(6000 + ErrorCode::InvalidFeeRecipient as u32)
+    );
+
+    let second_ata_lp = env.create_ata(&keys.lp_mint, &second);
+    env.send(
+        &[instructions::unlock_lp(
+            &keys,
+            &provider.pubkey(),
+            0,
+            &[first, second],
+        )],
+        &[&provider],
+    )
+    .expect("unlock_lp failed");
+
+    assert_eq!(env.token_balance(&first_ata_lp), 70);
+    assert_eq!(env.token_balance(&second_ata_lp), 30);
+    assert_eq!(env.token_balance(&provider_ata_lp), lp_balance - 100);
+    assert_eq!(env.fetch_pool(&keys.pool).total_locked_lp, 0);
+}
+
+#[test]
+fn positions_keep_the_penalty_they_were_opened_with() {
+    let mut env = TestEnv::new();
+    let admin = env.initialize(0);
+    let recipient = env.fetch_config().protocol_fee_recipient;
+    let FundedPool { keys, provider, .. } = env.create_test_pool(30, (RESERVE, RESERVE));
+    let recipient_ata_lp = env.create_ata(&keys.lp_mint, &recipient);
+
+    // Opened without a penalty, a later penalty does not allow an early exit
+    lock(&mut env, &keys, &provider, 0, 1_000, LockDuration::OneMonth);
+    set_early_exit_penalty(&mut env, &admin, 1_000);
+    assert_eq!(
+        fetch_lock_position(&env, &keys, &provider, 0).early_exit_penalty_bp,
+        0
+    );
+    let result = env.send(
+        &[instructions::unlock_lp(
+            &keys,
+            &provider.pubkey(),
+            0,
+            &[recipient],
+        )],
+        &[&provider],
+    );
+    assert_eq!(
+        error_code(&result.unwrap_err()),
+        Some(6000 + ErrorCode::LockNotExpired as u32)
+    );
+
+    // Raising the penalty afterwards leaves the position at 10%
+    lock(&mut env, &keys, &provider, 1, 1_000, LockDuration::OneMonth);
+    set_early_exit_penalty(&mut env, &admin, 5_000);
+    env.send(
+        &[instructions::unlock_lp(
+            &keys,
+            &provider.pubkey(),
+            1,
+            &[recipient],
+        )],
+        &[&provider],
+    )
+    .expect("unlock_lp failed");
+    assert_eq!(env.token_balance(&recipient_ata_lp), 100);
+}
+
+#[test]
+fn only_the_rewards_admin_sets_the_early_exit_penalty() {
+    let mut env = TestEnv::new();
+    let admin = env.initialize(0);
+    let user = env.create_user();
+
+    let result = env.send(
+        &[instructions::set_early_exit_penalty(&user.pubkey(), 1_000)],
+        &[&user],
+    );
+    assert_eq!(
+        error_code(&result.unwrap_err()),
+        Some(6000 + ErrorCode::NotRewardsAdmin as u32)
+    );
+
+    let result = env.send(
+        &[instructions::set_early_exit_penalty(
+            &admin.pubkey(),
+            10_001,
+        )],
+        &[&admin],
+    );
+    assert_eq!(
+        error_code(&result.unwrap_err()),
+        Some(6000 + ErrorCode::InvalidEarlyExitPenalty as u32)
+    );
+    assert_eq!(env.fetch_config().early_exit_penalty_bp, 0);
+}
@@ -0,0 +1,445 @@
+use anchor_lang::prelude::{borsh, Pubkey};
+use anchor_lang::{AccountDeserialize, AnchorSerialize, Discriminator, InitSpace, Space};
+use liquidity_pools::error::ErrorCode;
+use liquidity_pools::state::{Config, FeeRecipient, Pool};
+use liquidity_pools_client::instructions::{self, SwapDirection};
+use liquidity_pools_client::pda::{find_config_address, PoolKeys};
+use liquidity_pools_tests::{error_code, TestEnv, TOKEN_2022_PROGRAM_ID, TOKEN_PROGRAM_ID};
+use solana_sdk::signature::{Keypair, Signer};
+
+const RESERVE_A: u64 = 1_000_000 * 1_000_000_000;
+const RESERVE_B: u64 = 1_000_000 * 1_000_000_000;
+
+// Pool as written before the version field
+#[derive(AnchorSerialize, InitSpace)]
+struct UnversionedPool {
+    token_mint_a: Pubkey,
+    token_mint_b: Pubkey,
+    token_vault_a: Pubkey,
+    token_vault_b: Pubkey,
+    index: u16,
+    bump: u8,
+    creator: Pubkey,
+    flash_loan_active: bool,
+    flash_loan_amount_a: u64,
+    flash_loan_amount_b: u64,
+    flash_loan_reserve_a: u64,
+    flash_loan_reserve_b: u64,
+    max_price_impact_bp: u16,
+    max_trade_size_bp: u16,
+    protocol_fee_bp_override: Option<u16>,
+    protocol_fees_a: u64,
+    protocol_fees_b: u64,
+    fee_bp: u16,
+}
+
+impl UnversionedPool {
+    fn from_pool(pool: &Pool) -> Self {
+        Self {
+            token_mint_a: pool.token_mint_a,
+            token_mint_b: pool.token_mint_b,
+            token_vault_a: pool.token_vault_a,
+            token_vault_b: pool.token_vault_b,
+            index: pool.index,
+            bump: pool.bump,
+            creator: pool.creator,
+            flash_loan_active: pool.flash_loan_active,
+            flash_loan_amount_a: pool.flash_loan_amount_a,
+            flash_loan_amount_b: pool.flash_loan_amount_b,
+            flash_loan_reserve_a: pool.flash_loan_reserve_a,
+            flash_loan_reserve_b: pool.flash_loan_reserve_b,
+            max_price_impact_bp: pool.max_price_impact_bp,
+            max_trade_size_bp: pool.max_trade_size_bp,
+            protocol_fee_bp_override: pool.protocol_fee_bp_override,
+            protocol_fees_a: pool.protocol_fees_a,
+            protocol_fees_b: pool.protocol_fees_b,
+            fee_bp: pool.fee_bp,
+        }
+    }
+
+    // Account data the way Anchor allocated and wrote it
+    fn account_data(&self) -> Vec<u8> {
+        let mut data = Pool::DISCRIMINATOR.to_vec();
+        self.serialize(&mut data).unwrap();
+        data.resize(8 + Self::INIT_SPACE, 0);
+        data
+    }
+}
+
+// Config as written before the version field
+#[derive(AnchorSerialize, InitSpace)]
+struct UnversionedConfig {
+    authority: Pubkey,
+    protocol_fee_recipient: Pubkey,
+    protocol_fee_bp: u16,
+    bump: u8,
+    max_referral_fee_bp: u16,
+    fee_manager: Pubkey,
+    pause_guardian: Pubkey,
+    pool_admin: Pubkey,
+    rewards_admin: Pubkey,
+    paused: bool,
+    timelock_delay: i64,
+    min_pool_fee_bp: u16,
+    max_pool_fee_bp: u16,
+}
+
+impl UnversionedConfig {
+    fn from_config(config: &Config) -> Self {
+        Self {
+            authority: config.authority,
+            protocol_fee_recipient: config.protocol_fee_recipient,
+            protocol_fee_bp: config.protocol_fee_bp,
+            bump: config.bump,
+            max_referral_fee_bp: config.max_referral_fee_bp,
+            fee_manager: config.fee_manager,
+            pause_guardian: config.pause_guardian,
+            pool_admin: config.pool_admin,
+            rewards_admin: config.rewards_admin,
+            paused: config.paused,
+            timelock_delay: config.timelock_delay,
+            min_pool_fee_bp: config.min_pool_fee_bp,
+            max_pool_fee_bp: config.max_pool_fee_bp,
+        }
+    }
+
+    fn account_data(&self) -> Vec<u8> {
+        let mut data = Config::DISCRIMINATOR.to_vec();
+        self.serialize(&mut data).unwrap();
+        data.resize(8 + Self::INIT_SPACE, 0);
+        data
+    }
+}
+
+// Pool as created before the pool index, seeded with its fee
+fn legacy_pool_data(pool: &Pool) -> Vec<u8> {
+    [
+        Pool::DISCRIMINATOR,
+        pool.token_mint_a.as_ref(),
+        pool.token_mint_b.as_ref(),
+        pool.token_vault_a.as_ref(),
+        pool.token_vault_b.as_ref(),
+        &pool.index.to_le_bytes(),
+        &[pool.bump],
+    ]
+    .concat()
+}
+
+// Config as created before the operational roles
+fn legacy_config_data(config: &Config) -> Vec<u8> {
+    [
+        Config::DISCRIMINATOR,
+        config.authority.as_ref(),
+        config.protocol_fee_recipient.as_ref(),
+        &config.protocol_fee_bp.to_le_bytes(),
+        &[config.bump],
+    ]
+    .concat()
+}
+
+// Old accounts are zero extended by the realloc before being read
+fn read_grown<T: AccountDeserialize>(mut data: Vec<u8>, space: usize) -> T {
+    data.resize(space, 0);
+    T::try_deserialize(&mut data.as_slice()).unwrap()
+}
+
+fn sample_pool() -> Pool {
+    Pool {
+        token_mint_a: Pubkey::new_unique(),
+        token_mint_b: Pubkey::new_unique(),
+        token_vault_a: Pubkey::new_unique(),
+        token_vault_b: Pubkey::new_unique(),
+        index: 3,
+        bump: 254,
+        creator: Pubkey::new_unique(),
+        max_price_impact_bp: 500,
+        max_trade_size_bp: 1_000,
+        protocol_fee_bp_override: Some(2_000),
+        protocol_fees_a: 7,
+        protocol_fees_b: 11,
+        fee_bp: 30,
+        // Every other field zeroed, as in a freshly allocated account
+        ..read_grown(Pool::DISCRIMINATOR.to_vec(), 8 + Pool::INIT_SPACE)
+    }
+}
+
+fn sample_config() -> Config {
+    Config {
+        authority: Pubkey::new_unique(),
+        protocol_fee_recipient: Pubkey::new_unique(),
+        protocol_fee_bp: 1_000,
+        bump: 255,
+        max_referral_fee_bp: 3_000,
+        fee_manager: Pubkey::new_unique(),
+        pause_guardian: Pubkey::new_unique(),
+        pool_admin: Pubkey::new_unique(),
+        rewards_admin: Pubkey::new_unique(),
+        paused: true,
+        timelock_delay: 3_600,
+        min_pool_fee_bp: 1,
+        max_pool_fee_bp: 100,
+        ..read_grown(Config::DISCRIMINATOR.to_vec(), 8 + Config::INIT_SPACE)
+    }
+}
+
+// New fields take over padding, the sizes only change with a VERSION bump that
+// grows the accounts through the migrations
+#[test]
+fn account_sizes_are_fixed() {
+    assert_eq!(8 + Config::INIT_SPACE, 484);
+    assert_eq!(8 + Pool::INIT_SPACE, 358);
+}
+
+#[test]
+fn unversioned_pool_reads_as_version_0() {
+    let mut pool = sample_pool();
+    let data = UnversionedPool::from_pool(&pool).account_data();
+
+    let read: Pool = read_grown(data, 8 + Pool::INIT_SPACE);
+    assert_eq!(read.version, 0);
+    assert_eq!(format!("{read:?}"), format!("{pool:?}"));
+
+    // The override is variable length, following fields must not move
+    pool.protocol_fee_bp_override = None;
+    let data = UnversionedPool::from_pool(&pool).account_data();
+    let read: Pool = read_grown(data, 8 + Pool::INIT_SPACE);
+    assert_eq!(format!("{read:?}"), format!("{pool:?}"));
+}
+
+#[test]
+fn legacy_pool_reads_as_version_0() {
+    let pool = sample_pool();
+    let data = legacy_pool_data(&pool);
+    assert_eq!(data.len(), Pool::LEGACY_LEN);
+
+    let read: Pool = read_grown(data, 8 + Pool::INIT_SPACE);
+    assert_eq!(read.version, 0);
+    assert_eq!(read.token_vault_b, pool.token_vault_b);
+    assert_eq!((read.index, read.bump), (pool.index, pool.bump));
+    assert_eq!(read.creator, Pubkey::default());
+    assert_eq!(read.protocol_fee_bp_override, None);
+}
+
+#[test]
+fn unversioned_config_reads_as_version_0() {
+    let config = sample_config();
+    let data = UnversionedConfig::from_config(&config).account_data();
+
+    let read: Config = read_grown(data, 8 + Config::INIT_SPACE);
+    assert_eq!(read.version, 0);
+    assert_eq!(format!("{read:?}"), format!("{config:?}"));
+}
+
+#[test]
+fn legacy_config_reads_as_version_0() {
+    let config = sample_config();
+    let data = legacy_config_data(&config);
+    assert_eq!(data.len(), Config::LEGACY_LEN);
+
+    let read: Config = read_grown(data, 8 + Config::INIT_SPACE);
+    assert_eq!(read.version, 0);
+    assert_eq!(read.protocol_fee_recipient, config.protocol_fee_recipient);
+    assert_eq!((read.protocol_fee_bp, read.bump), (1_000, 255));
+    assert_eq!(read.fee_manager, Pubkey::default());
+}
+
+fn setup_pool(env: &mut TestEnv, index: u16, fee_bp: u16) -> PoolKeys {
+    let creator = env.create_user();
+    let (token_mint_a, token_mint_b) =
+        env.create_mint_pair((9, 9), (TOKEN_PROGRAM_ID, TOKEN_2022_PROGRAM_ID));
+    let keys = env.pool_keys(token_mint_a, token_mint_b, index);
+    env.send(
+        &[instructions::create_pool(
+            &creator.pubkey(),
+            &keys,
+            fee_bp,
+            env.fetch_config().registry_page(),
+            env.pair_index_page(&keys),
+        )],
+        &[&creator],
+    )
+    .expect("create_pool failed");
+    let provider = env.fund_user(&keys, RESERVE_A, RESERVE_B);
+    env.add_liquidity(&keys, &provider, RESERVE_A, RESERVE_B)
+        .expect("add_liquidity failed");
+    keys
+}
+
+fn write_data(env: &mut TestEnv, address: &Pubkey, data: Vec<u8>) {
+    let mut account = env.svm.get_account(address).unwrap();
+    account.data = data;
+    env.svm.set_account(*address, account).unwrap();
+}
+
+fn migrate_pool(env: &mut TestEnv, admin: &Keypair, pool: &Pubkey) {
+    env.send(
+        &[instructions::migrate_pool(&admin.pubkey(), pool)],
+        &[admin],
+    )
+    .expect("migrate_pool failed");
+}
+
+fn assert_swaps(env: &mut TestEnv, keys: &PoolKeys) {
+    let user = env.fund_user(keys, 1_000_000_000, 0);
+    env.swap(keys, &user, SwapDirection::AToB, 1_000_000_000, 1, None)
+        .expect("swap failed");
+}
+
+#[test]
+fn new_accounts_are_created_at_the_current_version() {
+    let mut env = TestEnv::new();
+    env.initialize(0);
+    let keys = setup_pool(&mut env, 0, 30);
+
+    assert_eq!(env.fetch_config().version, Config::VERSION);
+    assert_eq!(env.fetch_pool(&keys.pool).version, Pool::VERSION);
+}
+
+#[test]
+fn unversioned_pool_is_migrated_in_place() {
+    let mut env = TestEnv::new();
+    let admin = env.initialize(0);
+    let keys = setup_pool(&mut env, 0, 30);
+    env.update_pool(&keys, &admin, Some(500), None)
+        .expect("update_pool failed");
+
+    // Pools written before the version predate the registry
+    let pool = Pool {
+        registered: false,
+        ..env.fetch_pool(&keys.pool)
+    };
+    write_data(
+        &mut env,
+        &keys.pool,
+        UnversionedPool::from_pool(&pool).account_data(),
+    );
+    migrate_pool(&mut env, &admin, &keys.pool);
+
+    let migrated = env.fetch_pool(&keys.pool);
+    assert_eq!(migrated.version, Pool::VERSION);
+    assert_eq!(
+        env.svm.get_account(&keys.pool).unwrap().data.len(),
+        8 + Pool::INIT_SPACE
+    );
+    assert_eq!(format!("{migrated:?}"), format!("{pool:?}"));
+    assert_swaps(&mut env, &keys);
+
+    // Migrating twice fails
+    env.svm.expire_blockhash();
+    let result = env.send(
+        &[instructions::migrate_pool(&admin.pubkey(), &keys.pool)],
+        &[&admin],
+    );
+    assert_eq!(
+        error_code(&result.unwrap_err()),
+        Some(6000 + ErrorCode::AlreadyMigrated as u32)
+    );
+}
+
+#[test]
+fn legacy_pool_is_migrated_in_place() {
+    let mut env = TestEnv::new();
+    let admin = env.initialize(0);
+    // Legacy pools are derived from their fee, which becomes their index
+    let keys = setup_pool(&mut env, 30, 30);
+
+    let pool = env.fetch_pool(&keys.pool);
+    write_data(&mut env, &keys.pool, legacy_pool_data(&pool));
+    migrate_pool(&mut env, &admin, &keys.pool);
+
+    let migrated = env.fetch_pool(&keys.pool);
+    assert_eq!(migrated.version, Pool::VERSION);
+    assert_eq!((migrated.index, migrated.fee_bp), (30, 30));
+    assert_eq!(migrated.token_vault_a, keys.token_vault_a);
+    assert_eq!(migrated.bump, pool.bump);
+    assert_swaps(&mut env, &keys);
+}
+
+#[test]
+fn only_the_pool_admin_migrates_pools() {
+    let mut env = TestEnv::new();
+    env.initialize(0);
+    let keys = setup_pool(&mut env, 0, 30);
+    let pool = env.fetch_pool(&keys.pool);
+    write_data(
+        &mut env,
+        &keys.pool,
+        UnversionedPool::from_pool(&pool).account_data(),
+    );
+
+    let user = env.create_user();
+    let result = env.send(
+        &[instructions::migrate_pool(&user.pubkey(), &keys.pool)],
+        &[&user],
+    );
+    assert_eq!(
+        error_code(&result.unwrap_err()),
+        Some(6000 + ErrorCode::NotPoolAdmin as u32)
+    );
+}
+
+#[test]
+fn unversioned_config_is_migrated_in_place() {
+    let mut env = TestEnv::new();
+    let admin = env.initialize(500);
+    let config_address = find_config_address().0;
+
+    let config = env.fetch_config();
+    write_data(
+        &mut env,
+        &config_address,
+        UnversionedConfig::from_config(&config).account_data(),
+    );
+
+    // Only the authority migrates the config
+    let user = env.create_user();
+    let result = env.send(&[instructions::migrate_config(&user.pubkey())], &[&user]);
+    assert_eq!(
+        error_code(&result.unwrap_err()),
+        Some(6000 + ErrorCode::InvalidAuthority as u32)
+    );
+
+    env.send(&[instructions::migrate_config(&admin.pubkey())], &[&admin])
+        .expect("migrate_config failed");
+    let migrated = env.fetch_config();
+    assert_eq!(migrated.version, Config::VERSION);
+    assert_eq!(format!("{migrated:?}"), format!("{config:?}"));
+
+    env.svm.expire_blockhash();
+    let result = env.send(&[instructions::migrate_config(&admin.pubkey())], &[&admin]);
+    assert_eq!(
+        error_code(&result.unwrap_err()),
+        Some(6000 + ErrorCode::AlreadyMigrated as u32)
+    );
+}
+
+#[test]
+fn legacy_config_gets_the_initialize_defaults() {
+    let mut env = TestEnv::new();
+    let admin = env.initialize(500);
+    let config_address = find_config_address().0;
+
+    let config = env.fetch_config();
+    write_data(&mut env, &config_address, legacy_config_data(&config));
+    env.send(&[instructions::migrate_config(&admin.pubkey())], &[&admin])
+        .expect("migrate_config failed");
+
+    let migrated = env.fetch_config();
+    assert_eq!(migrated.version, Config::VERSION);
+    assert_eq!(migrated.protocol_fee_bp, 500);
+    assert_eq!(migrated.fee_manager, admin.pubkey());
+    assert_eq!(migrated.pause_guardian, admin.pubkey());
+    assert_eq!(migrated.pool_admin, admin.pubkey());
+    assert_eq!(migrated.rewards_admin, admin.pubkey());
+    assert_eq!(
+        (migrated.min_pool_fee_bp, migrated.max_pool_fee_bp),
+        (0, 10_000)
+    );
+    assert_eq!(
+        migrated.active_fee_recipients(),
+        &[FeeRecipient {
+            recipient: config.protocol_fee_recipient,
+            weight_bp: 10_000,
+        }]
+    );
+}
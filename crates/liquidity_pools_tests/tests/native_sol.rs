@@ -0,0 +1,331 @@
+use liquidity_pools::error::ErrorCode;
+use liquidity_pools::helpers::native_sol::is_native_mint;
+use liquidity_pools_client::instructions::{self, SwapDirection};
+use liquidity_pools_client::pda::{find_wsol_address, sort_mints, PoolKeys};
+use liquidity_pools_client::quote::{quote_remove_liquidity, quote_swap};
+use liquidity_pools_tests::{
+    error_code, FundedPool, TestEnv, TOKEN_2022_PROGRAM_ID, TOKEN_PROGRAM_ID,
+};
+use solana_sdk::instruction::Instruction;
+use solana_sdk::native_token::LAMPORTS_PER_SOL;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+
+const RESERVE: u64 = 100 * LAMPORTS_PER_SOL;
+const AMOUNT_IN: u64 = LAMPORTS_PER_SOL;
+
+// Pool of the native mint of `token_program` and a mint of the other token program
+fn setup_pool(env: &mut TestEnv, token_program: Pubkey) -> PoolKeys {
+    env.initialize(0);
+    let native_mint = env.native_mint(token_program);
+    let other_program = if token_program == TOKEN_PROGRAM_ID {
+        TOKEN_2022_PROGRAM_ID
+    } else {
+        TOKEN_PROGRAM_ID
+    };
+    let other_mint = env.create_mint(9, other_program);
+    let (token_mint_a, token_mint_b) = sort_mints(native_mint, other_mint);
+    let creator = env.create_user();
+    env.create_pool_for_mints(&creator, token_mint_a, token_mint_b, 30)
+}
+
+fn is_native_a(keys: &PoolKeys) -> bool {
+    is_native_mint(&keys.token_mint_a)
+}
+
+// Temporary wrapped SOL account of `owner`, gone once each instruction returns
+fn wsol_account(keys: &PoolKeys, owner: &Pubkey) -> Pubkey {
+    let native_mint = if is_native_a(keys) {
+        keys.token_mint_a
+    } else {
+        keys.token_mint_b
+    };
+    find_wsol_address(owner, &native_mint).0
+}
+
+// Token account of `owner` for the native side, and for the other side
+fn user_atas(keys: &PoolKeys, owner: &Pubkey) -> (Pubkey, Pubkey) {
+    if is_native_a(keys) {
+        (keys.user_ata_a(owner), keys.user_ata_b(owner))
+    } else {
+        (keys.user_ata_b(owner), keys.user_ata_a(owner))
+    }
+}
+
+// Token account of `owner` for the other side holding `amount`
+fn fund_other_side(env: &mut TestEnv, keys: &PoolKeys, owner: &Pubkey, amount: u64) -> Pubkey {
+    let other_mint = if is_native_a(keys) {
+        keys.token_mint_b
+    } else {
+        keys.token_mint_a
+    };
+    let ata = env.create_ata(&other_mint, owner);
+    env.mint_to(&ata, amount);
+    ata
+}
+
+// Native side and other side reserves
+fn native_reserves(env: &TestEnv, keys: &PoolKeys) -> (u64, u64) {
+    let (reserve_a, reserve_b) = env.reserves(keys);
+    if is_native_a(keys) {
+        (reserve_a, reserve_b)
+    } else {
+        (reserve_b, reserve_a)
+    }
+}
+
+// Native SOL swap of `trader` into or out of the native side
+fn swap(keys: &PoolKeys, trader: &Keypair, native_input: bool) -> Instruction {
+    let direction = if native_input == is_native_a(keys) {
+        SwapDirection::AToB
+    } else {
+        SwapDirection::BToA
+    };
+    instructions::swap_to(
+        keys,
+        &trader.pubkey(),
+        &trader.pubkey(),
+        direction,
+        AMOUNT_IN,
+        1,
+        None,
+        None,
+        true,
+    )
+}
+
+fn native_sol_round_trip(token_program: Pubkey) {
+    let mut env = TestEnv::new();
+    let keys = setup_pool(&mut env, token_program);
+    // Transaction fees are paid by a separate account so lamport deltas are exact
+    let fee_payer = env.create_user();
+
+    // Deposit the native side from lamports
+    let provider = env.create_user();
+    let (provider_wsol, _) = user_atas(&keys, &provider.pubkey());
+    fund_other_side(&mut env, &keys, &provider.pubkey(), RESERVE);
+    let before = env.lamports(&provider.pubkey());
+    env.send(
+        &[instructions::add_liquidity_to(
+            &keys,
+            &provider.pubkey(),
+            &provider.pubkey(),
+            RESERVE,
+            RESERVE,
+            0,
+            0,
+            None,
+            &[],
+            true,
+        )],
+        &[&fee_payer, &provider],
+    )
+    .expect("add_liquidity failed");
+    let lp_rent = env.lamports(&keys.user_ata_lp(&provider.pubkey()));
+    assert_eq!(env.lamports(&provider.pubkey()), before - RESERVE - lp_rent);
+    assert_eq!(native_reserves(&env, &keys), (RESERVE, RESERVE));
+    assert_eq!(env.lamports(&provider_wsol), 0);
+    assert_eq!(env.lamports(&wsol_account(&keys, &provider.pubkey())), 0);
+
+    // Swap lamports in, the output account is created for the trader
+    let trader = env.create_user();
+    let (trader_wsol, trader_ata_other) = user_atas(&keys, &trader.pubkey());
+    let pool = env.fetch_pool(&keys.pool);
+    let quote = quote_swap(&pool, AMOUNT_IN, RESERVE, RESERVE).unwrap();
+    let before = env.lamports(&trader.pubkey());
+    env.send(&[swap(&keys, &trader, true)], &[&fee_payer, &trader])
+        .expect("swap in failed");
+    let other_rent = env.lamports(&trader_ata_other);
+    assert_eq!(env.token_balance(&trader_ata_other), quote.amount_out);
+    assert_eq!(
+        env.lamports(&trader.pubkey()),
+        before - AMOUNT_IN - other_rent
+    );
+    assert_eq!(env.lamports(&trader_wsol), 0);
+
+    // Swap back out to lamports
+    let (reserve_native, reserve_other) = native_reserves(&env, &keys);
+    let quote = quote_swap(&pool, AMOUNT_IN, reserve_other, reserve_native).unwrap();
+    env.mint_to(&trader_ata_other, AMOUNT_IN);
+    let before = env.lamports(&trader.pubkey());
+    env.send(&[swap(&keys, &trader, false)], &[&fee_payer, &trader])
+        .expect("swap out failed");
+    assert_eq!(env.lamports(&trader.pubkey()), before + quote.amount_out);
+    assert_eq!(env.lamports(&trader_wsol), 0);
+    assert_eq!(env.lamports(&wsol_account(&keys, &trader.pubkey())), 0);
+
+    // Withdraw the native side to lamports
+    let lp_amount = env.token_balance(&keys.user_ata_lp(&provider.pubkey()));
+    let (reserve_a, reserve_b) = env.reserves(&keys);
+    let withdrawal = quote_remove_liquidity(
+        lp_amount,
+        reserve_a,
+        reserve_b,
+        env.mint_supply(&keys.lp_mint),
+    )
+    .unwrap();
+    let native_amount = if is_native_a(&keys) {
+        withdrawal.amount_a
+    } else {
+        withdrawal.amount_b
+    };
+    let before = env.lamports(&provider.pubkey());
+    env.send(
+        &[instructions::remove_liquidity(
+            &keys,
+            &provider.pubkey(),
+            lp_amount,
+            1,
+            1,
+            None,
+            &[],
+            true,
+        )],
+        &[&fee_payer, &provider],
+    )
+    .expect("remove_liquidity failed");
+    assert_eq!(env.lamports(&provider.pubkey()), before + native_amount);
+    assert!(env.svm.get_account(&provider_wsol).is_none());
+}
+
+#[test]
+fn native_sol_with_the_token_program_native_mint() {
+    native_sol_round_trip(TOKEN_PROGRAM_ID);
+}
+
+#[test]
+fn native_sol_with_the_token_2022_native_mint() {
+    native_sol_round_trip(TOKEN_2022_PROGRAM_ID);
+}
+
+#[test]
+fn native_sol_needs_a_native_mint_pool() {
+    let mut env = TestEnv::new();
+    env.initialize(0);
+    let FundedPool { keys, provider, .. } = env.create_funded_pool(
+        (TOKEN_PROGRAM_ID, TOKEN_2022_PROGRAM_ID),
+        30,
+        (RESERVE, RESERVE),
+    );
+
+    let result = env.send(
+        &[instructions::swap_to(
+            &keys,
+            &provider.pubkey(),
+            &provider.pubkey(),
+            SwapDirection::AToB,
+            AMOUNT_IN,
+            1,
+            None,
+            None,
+            true,
+        )],
+        &[&provider],
+    );
+    assert_eq!(
+        error_code(&result.unwrap_err()),
+        Some(6000 + ErrorCode::NotNativeMintPool as u32)
+    );
+}
+
+#[test]
+fn native_sol_output_goes_to_the_payer_only() {
+    let mut env = TestEnv::new();
+    let keys = setup_pool(&mut env, TOKEN_PROGRAM_ID);
+    let provider = env.create_user();
+    fund_other_side(&mut env, &keys, &provider.pubkey(), RESERVE + AMOUNT_IN);
+    env.send(
+        &[instructions::add_liquidity_to(
+            &keys,
+            &provider.pubkey(),
+            &provider.pubkey(),
+            RESERVE,
+            RESERVE,
+            0,
+            0,
+            None,
+            &[],
+            true,
+        )],
+        &[&provider],
+    )
+    .expect("add_liquidity failed");
+
+    let direction = if is_native_a(&keys) {
+        SwapDirection::BToA
+    } else {
+        SwapDirection::AToB
+    };
+    let result = env.send(
+        &[instructions::swap_to(
+            &keys,
+            &provider.pubkey(),
+            &Pubkey::new_unique(),
+            direction,
+            AMOUNT_IN,
+            1,
+            None,
+            None,
+            true,
+        )],
+        &[&provider],
+    );
+    assert_eq!(
+        error_code(&result.unwrap_err()),
+        Some(6000 + ErrorCode::NativeSolRecipient as u32)
+    );
+}
+
+#[test]
+fn native_sol_leaves_the_wrapped_sol_account_of_the_payer_alone() {
+    let mut env = TestEnv::new();
+    let keys = setup_pool(&mut env, TOKEN_PROGRAM_ID);
+    let provider = env.create_user();
+    fund_other_side(&mut env, &keys, &provider.pubkey(), RESERVE);
+    env.send(
+        &[instructions::add_liquidity_to(
+            &keys,
+            &provider.pubkey(),
+            &provider.pubkey(),
+            RESERVE,
+            RESERVE,
+            0,
+            0,
+            None,
+            &[],
+            true,
+        )],
+        &[&provider],
+    )
+    .expect("add_liquidity failed");
+
+    // Wrapped SOL the trader already holds stays where it is
+    let trader = env.create_user();
+    let native_mint = env.native_mint(TOKEN_PROGRAM_ID);
+    let trader_wsol = env.create_ata(&native_mint, &trader.pubkey());
+    env.mint_to(&trader_wsol, AMOUNT_IN);
+    fund_other_side(&mut env, &keys, &trader.pubkey(), AMOUNT_IN);
+    for native_input in [true, false] {
+        env.send(&[swap(&keys, &trader, native_input)], &[&trader])
+            .expect("swap failed");
+        assert_eq!(env.token_balance(&trader_wsol), AMOUNT_IN);
+    }
+}
+
+#[test]
+fn native_sol_needs_the_wrapped_sol_account_for_the_native_side_only() {
+    let mut env = TestEnv::new();
+    let keys = setup_pool(&mut env, TOKEN_PROGRAM_ID);
+    let trader = env.create_user();
+    fund_other_side(&mut env, &keys, &trader.pubkey(), AMOUNT_IN);
+
+    // The native_sol flag is the last byte, without it the temporary account
+    // would be left open
+    let mut instruction = swap(&keys, &trader, false);
+    *instruction.data.last_mut().unwrap() = 0;
+    let result = env.send(&[instruction], &[&trader]);
+    assert_eq!(
+        error_code(&result.unwrap_err()),
+        Some(6000 + ErrorCode::NativeSolAccounts as u32)
+    );
+}
@@ -0,0 +1,173 @@
+use liquidity_pools::error::ErrorCode;
+use liquidity_pools::state::PoolCreationMode;
+use liquidity_pools_client::instructions::{self, CreatePoolOptions};
+use liquidity_pools_client::pda::{find_pool_creator_address, PoolKeys};
+use liquidity_pools_tests::{error_code, TestEnv, TOKEN_PROGRAM_ID};
+use solana_sdk::instruction::Instruction;
+use solana_sdk::signature::{Keypair, Signer};
+
+fn new_pool_keys(env: &mut TestEnv) -> PoolKeys {
+    let (token_mint_a, token_mint_b) =
+        env.create_mint_pair((9, 9), (TOKEN_PROGRAM_ID, TOKEN_PROGRAM_ID));
+    env.pool_keys(token_mint_a, token_mint_b, 0)
+}
+
+fn create_pool(
+    env: &TestEnv,
+    creator: &Keypair,
+    keys: &PoolKeys,
+    allowlisted: bool,
+) -> Instruction {
+    let options = CreatePoolOptions {
+        allowlisted,
+        ..env.create_pool_options(&creator.pubkey(), keys)
+    };
+    instructions::create_pool_with_options(&creator.pubkey(), keys, 30, &options)
+}
+
+fn set_mode(env: &mut TestEnv, admin: &Keypair, mode: PoolCreationMode) {
+    env.send(
+        &[instructions::set_pool_creation_mode(&admin.pubkey(), mode)],
+        &[admin],
+    )
+    .expect("set_pool_creation_mode failed");
+    assert_eq!(env.fetch_config().pool_creation_mode, mode);
+}
+
+#[test]
+fn pool_creation_is_permissionless_by_default() {
+    let mut env = TestEnv::new();
+    env.initialize(0);
+    assert_eq!(
+        env.fetch_config().pool_creation_mode,
+        PoolCreationMode::Permissionless
+    );
+
+    let creator = env.create_user();
+    let keys = new_pool_keys(&mut env);
+    let instruction = create_pool(&env, &creator, &keys, false);
+    env.send(&[instruction], &[&creator])
+        .expect("create_pool failed");
+}
+
+#[test]
+fn authority_only_mode_rejects_other_creators() {
+    let mut env = TestEnv::new();
+    let admin = env.initialize(0);
+    set_mode(&mut env, &admin, PoolCreationMode::AuthorityOnly);
+
+    let creator = env.create_user();
+    let keys = new_pool_keys(&mut env);
+    let instruction = create_pool(&env, &creator, &keys, false);
+    let result = env.send(&[instruction], &[&creator]);
+    assert_eq!(
+        error_code(&result.unwrap_err()),
+        Some(6000 + ErrorCode::PoolCreationNotAllowed as u32)
+    );
+
+    let instruction = create_pool(&env, &admin, &keys, false);
+    env.send(&[instruction], &[&admin])
+        .expect("create_pool failed");
+    assert_eq!(env.fetch_pool(&keys.pool).creator, admin.pubkey());
+}
+
+#[test]
+fn allowlisted_creators_create_pools() {
+    let mut env = TestEnv::new();
+    let admin = env.initialize(0);
+    set_mode(&mut env, &admin, PoolCreationMode::Allowlist);
+    let creator = env.create_user();
+
+    let keys = new_pool_keys(&mut env);
+    let instruction = create_pool(&env, &creator, &keys, false);
+    let result = env.send(&[instruction], &[&creator]);
+    assert_eq!(
+        error_code(&result.unwrap_err()),
+        Some(6000 + ErrorCode::PoolCreationNotAllowed as u32)
+    );
+
+    env.send(
+        &[instructions::add_pool_creator(
+            &admin.pubkey(),
+            &creator.pubkey(),
+        )],
+        &[&admin],
+    )
+    .expect("add_pool_creator failed");
+    let instruction = create_pool(&env, &creator, &keys, true);
+    env.send(&[instruction], &[&creator])
+        .expect("create_pool failed");
+
+    // Removing the creator closes its entry and blocks its next pools
+    env.send(
+        &[instructions::remove_pool_creator(
+            &admin.pubkey(),
+            &creator.pubkey(),
+        )],
+        &[&admin],
+    )
+    .expect("remove_pool_creator failed");
+    assert_eq!(
+        env.lamports(&find_pool_creator_address(&creator.pubkey()).0),
+        0
+    );
+
+    let keys = new_pool_keys(&mut env);
+    let instruction = create_pool(&env, &creator, &keys, false);
+    let result = env.send(&[instruction], &[&creator]);
+    assert_eq!(
+        error_code(&result.unwrap_err()),
+        Some(6000 + ErrorCode::PoolCreationNotAllowed as u32)
+    );
+}
+
+#[test]
+fn pool_creation_admin_creates_pools_in_restricted_modes() {
+    let mut env = TestEnv::new();
+    let admin = env.initialize(0);
+    let pool_creation_admin = env.create_user();
+    env.send(
+        &[instructions::update_roles(
+            &admin.pubkey(),
+            None,
+            None,
+            None,
+            None,
+            Some(pool_creation_admin.pubkey()),
+        )],
+        &[&admin],
+    )
+    .expect("update_roles failed");
+
+    for mode in [PoolCreationMode::AuthorityOnly, PoolCreationMode::Allowlist] {
+        set_mode(&mut env, &pool_creation_admin, mode);
+
+        // Not allowlisted, the role alone lets it create pools
+        let keys = new_pool_keys(&mut env);
+        let instruction = create_pool(&env, &pool_creation_admin, &keys, false);
+        env.send(&[instruction], &[&pool_creation_admin])
+            .expect("create_pool failed");
+        assert_eq!(
+            env.fetch_pool(&keys.pool).creator,
+            pool_creation_admin.pubkey()
+        );
+    }
+}
+
+#[test]
+fn only_the_pool_creation_admin_manages_pool_creation() {
+    let mut env = TestEnv::new();
+    env.initialize(0);
+    let user = env.create_user();
+
+    for instruction in [
+        instructions::set_pool_creation_mode(&user.pubkey(), PoolCreationMode::Permissionless),
+        instructions::add_pool_creator(&user.pubkey(), &user.pubkey()),
+    ] {
+        let result = env.send(&[instruction], &[&user]);
+        assert_eq!(
+            error_code(&result.unwrap_err()),
+            Some(6000 + ErrorCode::NotPoolCreationAdmin as u32)
+        );
+    }
+}
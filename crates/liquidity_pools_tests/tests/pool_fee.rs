@@ -0,0 +1,145 @@
+use liquidity_pools::error::ErrorCode;
+use liquidity_pools_client::instructions::{self, SwapDirection};
+use liquidity_pools_client::pda::PoolKeys;
+use liquidity_pools_client::quote::quote_swap;
+use liquidity_pools_tests::{error_code, FundedPool, TestEnv, TOKEN_PROGRAM_ID};
+use solana_sdk::instruction::Instruction;
+use solana_sdk::signature::{Keypair, Signer};
+
+const RESERVE_A: u64 = 1_000_000 * 1_000_000_000;
+const RESERVE_B: u64 = 1_000_000 * 1_000_000_000;
+const AMOUNT_IN: u64 = 1_000 * 1_000_000_000;
+
+fn set_fee(keys: &PoolKeys, authority: &Keypair, fee_bp: u16) -> Instruction {
+    instructions::update_pool(
+        &authority.pubkey(),
+        &keys.pool,
+        None,
+        None,
+        None,
+        Some(fee_bp),
+    )
+}
+
+fn set_bounds(
+    authority: &Keypair,
+    min_pool_fee_bp: Option<u16>,
+    max_pool_fee_bp: Option<u16>,
+) -> Instruction {
+    instructions::update_config(
+        &authority.pubkey(),
+        None,
+        min_pool_fee_bp,
+        max_pool_fee_bp,
+        None,
+        None,
+    )
+}
+
+// Swaps AMOUNT_IN of token A and checks the output against the pool fee
+fn swap_at_fee(env: &mut TestEnv, keys: &PoolKeys, fee_bp: u16) {
+    let pool = env.fetch_pool(&keys.pool);
+    assert_eq!(pool.fee_bp, fee_bp);
+    let (reserve_a, reserve_b) = env.reserves(keys);
+    let quote = quote_swap(&pool, AMOUNT_IN, reserve_a, reserve_b).unwrap();
+
+    let user = env.fund_user(keys, AMOUNT_IN, 0);
+    env.swap(keys, &user, SwapDirection::AToB, AMOUNT_IN, 1, None)
+        .expect("swap failed");
+    assert_eq!(
+        env.token_balance(&keys.user_ata_b(&user.pubkey())),
+        quote.amount_out
+    );
+}
+
+#[test]
+fn pool_admin_changes_the_fee_in_place() {
+    let mut env = TestEnv::new();
+    let (FundedPool { keys, .. }, admin) = env.setup_pool(0, 30, (RESERVE_A, RESERVE_B));
+    swap_at_fee(&mut env, &keys, 30);
+
+    env.send(&[set_fee(&keys, &admin, 100)], &[&admin])
+        .expect("update_pool failed");
+
+    // Same pool, same liquidity, new fee
+    let pool = env.fetch_pool(&keys.pool);
+    assert_eq!((pool.index, pool.fee_bp), (0, 100));
+    assert!(env.mint_supply(&keys.lp_mint) > 0);
+    swap_at_fee(&mut env, &keys, 100);
+}
+
+#[test]
+fn random_user_cannot_change_the_fee() {
+    let mut env = TestEnv::new();
+    let (FundedPool { keys, .. }, _) = env.setup_pool(0, 30, (RESERVE_A, RESERVE_B));
+    let user = env.create_user();
+
+    let result = env.send(&[set_fee(&keys, &user, 100)], &[&user]);
+    assert_eq!(
+        error_code(&result.unwrap_err()),
+        Some(6000 + ErrorCode::NotPoolAdmin as u32)
+    );
+}
+
+#[test]
+fn fee_must_be_within_the_config_bounds() {
+    let mut env = TestEnv::new();
+    let (FundedPool { keys, .. }, admin) = env.setup_pool(0, 30, (RESERVE_A, RESERVE_B));
+    env.send(&[set_bounds(&admin, Some(10), Some(100))], &[&admin])
+        .expect("update_config failed");
+
+    let config = env.fetch_config();
+    assert_eq!((config.min_pool_fee_bp, config.max_pool_fee_bp), (10, 100));
+
+    for fee_bp in [9, 101] {
+        let result = env.send(&[set_fee(&keys, &admin, fee_bp)], &[&admin]);
+        assert_eq!(
+            error_code(&result.unwrap_err()),
+            Some(6000 + ErrorCode::InvalidPoolFee as u32)
+        );
+    }
+    env.send(&[set_fee(&keys, &admin, 100)], &[&admin])
+        .expect("update_pool failed");
+
+    // create_pool checks the same bounds
+    let creator = env.create_user();
+    let (token_mint_a, token_mint_b) =
+        env.create_mint_pair((9, 9), (TOKEN_PROGRAM_ID, TOKEN_PROGRAM_ID));
+    let keys = env.pool_keys(token_mint_a, token_mint_b, 0);
+    let result = env.send(
+        &[instructions::create_pool(
+            &creator.pubkey(),
+            &keys,
+            5,
+            env.fetch_config().registry_page(),
+            env.pair_index_page(&keys),
+        )],
+        &[&creator],
+    );
+    assert_eq!(
+        error_code(&result.unwrap_err()),
+        Some(6000 + ErrorCode::InvalidPoolFee as u32)
+    );
+}
+
+#[test]
+fn invalid_fee_bounds_are_rejected() {
+    let mut env = TestEnv::new();
+    let admin = env.initialize(0);
+
+    for (min, max) in [(Some(200), Some(100)), (None, Some(10_001))] {
+        let result = env.send(&[set_bounds(&admin, min, max)], &[&admin]);
+        assert_eq!(
+            error_code(&result.unwrap_err()),
+            Some(6000 + ErrorCode::InvalidPoolFeeBounds as u32)
+        );
+    }
+
+    // Only the fee manager sets the bounds
+    let user = env.create_user();
+    let result = env.send(&[set_bounds(&user, Some(0), Some(100))], &[&user]);
+    assert_eq!(
+        error_code(&result.unwrap_err()),
+        Some(6000 + ErrorCode::NotFeeManager as u32)
+    );
+}
@@ -0,0 +1,238 @@
+use liquidity_pools::error::ErrorCode;
+use liquidity_pools::math;
+use liquidity_pools_client::instructions::{self, SwapDirection};
+use liquidity_pools_client::pda::PoolKeys;
+use liquidity_pools_client::quote::{quote_swap, PoolState};
+use liquidity_pools_tests::{error_code, FundedPool, TestEnv};
+use solana_sdk::signature::{Keypair, Signer};
+
+const RESERVE_A: u64 = 1_000_000 * 1_000_000_000;
+const RESERVE_B: u64 = 1_000_000 * 1_000_000_000;
+const AMOUNT_IN: u64 = 1_000 * 1_000_000_000;
+
+// Pool with a 1% swap fee, 20% of which goes to the protocol by default
+fn set_override(env: &mut TestEnv, keys: &PoolKeys, admin: &Keypair, fee_bp: Option<u16>) {
+    env.send(
+        &[instructions::update_pool(
+            &admin.pubkey(),
+            &keys.pool,
+            None,
+            None,
+            Some(fee_bp),
+            None,
+        )],
+        &[admin],
+    )
+    .expect("update_pool failed");
+}
+
+// Lets pool overrides go up to `max_fee_bp_override`, the config has no timelock delay
+fn set_override_cap(env: &mut TestEnv, admin: &Keypair, max_fee_bp_override: u16) {
+    env.send(
+        &[
+            instructions::queue_config_change(
+                &admin.pubkey(),
+                None,
+                None,
+                None,
+                None,
+                Some(max_fee_bp_override),
+                None,
+            ),
+            instructions::execute_config_change(&admin.pubkey()),
+        ],
+        &[admin],
+    )
+    .expect("override cap change failed");
+}
+
+// Swaps AMOUNT_IN of token A and returns the protocol fee expected for `protocol_fee_bp`
+fn swap_a_to_b(env: &mut TestEnv, keys: &PoolKeys, protocol_fee_bp: u16) -> u64 {
+    let pool = env.fetch_pool(&keys.pool);
+    let (vault_a, vault_b) = env.reserves(keys);
+    let quote = quote_swap(
+        &pool,
+        AMOUNT_IN,
+        vault_a - pool.protocol_fees_a,
+        vault_b - pool.protocol_fees_b,
+    )
+    .unwrap();
+
+    let user = env.fund_user(keys, AMOUNT_IN, 0);
+    env.swap(keys, &user, SwapDirection::AToB, AMOUNT_IN, 1, None)
+        .expect("swap failed");
+    assert_eq!(
+        env.token_balance(&keys.user_ata_b(&user.pubkey())),
+        quote.amount_out
+    );
+
+    math::split_fee(quote.fee_amount, protocol_fee_bp)
+        .unwrap()
+        .share
+}
+
+#[test]
+fn swap_accrues_the_config_protocol_fee() {
+    let mut env = TestEnv::new();
+    let (FundedPool { keys, .. }, admin) = env.setup_pool(2_000, 100, (RESERVE_A, RESERVE_B));
+
+    let protocol_fee = swap_a_to_b(&mut env, &keys, 2_000);
+    assert!(protocol_fee > 0);
+
+    let pool = env.fetch_pool(&keys.pool);
+    assert_eq!(pool.protocol_fees_a, protocol_fee);
+    assert_eq!(pool.protocol_fees_b, 0);
+
+    // Accrued fees stay in the vault but are not part of the reserves
+    let state: PoolState = env
+        .view(instructions::get_pool_state(&keys), &admin)
+        .expect("get_pool_state failed");
+    assert_eq!(
+        state.reserve_a,
+        env.token_balance(&keys.token_vault_a) - protocol_fee
+    );
+}
+
+#[test]
+fn pool_override_replaces_the_config_protocol_fee() {
+    let mut env = TestEnv::new();
+    let (FundedPool { keys, .. }, admin) = env.setup_pool(2_000, 100, (RESERVE_A, RESERVE_B));
+
+    // Promotional pool without protocol fee
+    set_override(&mut env, &keys, &admin, Some(0));
+    assert_eq!(env.fetch_pool(&keys.pool).protocol_fee_bp_override, Some(0));
+    swap_a_to_b(&mut env, &keys, 0);
+    assert_eq!(env.fetch_pool(&keys.pool).protocol_fees_a, 0);
+
+    // Higher protocol fee
+    set_override_cap(&mut env, &admin, 5_000);
+    set_override(&mut env, &keys, &admin, Some(5_000));
+    let protocol_fee = swap_a_to_b(&mut env, &keys, 5_000);
+    assert_eq!(env.fetch_pool(&keys.pool).protocol_fees_a, protocol_fee);
+
+    // Back to the config protocol fee
+    set_override(&mut env, &keys, &admin, None);
+    assert_eq!(env.fetch_pool(&keys.pool).protocol_fee_bp_override, None);
+    let config_protocol_fee = swap_a_to_b(&mut env, &keys, 2_000);
+    assert_eq!(
+        env.fetch_pool(&keys.pool).protocol_fees_a,
+        protocol_fee + config_protocol_fee
+    );
+}
+
+#[test]
+fn protocol_fee_override_above_the_timelocked_cap_is_rejected() {
+    let mut env = TestEnv::new();
+    let (FundedPool { keys, .. }, admin) = env.setup_pool(2_000, 100, (RESERVE_A, RESERVE_B));
+    let raise = |protocol_fee_bp: u16| {
+        instructions::update_pool(
+            &admin.pubkey(),
+            &keys.pool,
+            None,
+            None,
+            Some(Some(protocol_fee_bp)),
+            None,
+        )
+    };
+
+    // Without a cap the override can not exceed the config protocol fee
+    let result = env.send(&[raise(2_001)], &[&admin]);
+    assert_eq!(
+        error_code(&result.unwrap_err()),
+        Some(6000 + ErrorCode::InvalidProtocolFee as u32)
+    );
+
+    set_override_cap(&mut env, &admin, 3_000);
+    env.send(&[raise(3_000)], &[&admin])
+        .expect("update_pool failed");
+    let result = env.send(&[raise(3_001)], &[&admin]);
+    assert_eq!(
+        error_code(&result.unwrap_err()),
+        Some(6000 + ErrorCode::InvalidProtocolFee as u32)
+    );
+}
+
+#[test]
+fn protocol_fee_override_above_100_percent_is_rejected() {
+    let mut env = TestEnv::new();
+    let (FundedPool { keys, .. }, admin) = env.setup_pool(2_000, 100, (RESERVE_A, RESERVE_B));
+    set_override_cap(&mut env, &admin, 10_000);
+
+    let result = env.send(
+        &[instructions::update_pool(
+            &admin.pubkey(),
+            &keys.pool,
+            None,
+            None,
+            Some(Some(10_001)),
+            None,
+        )],
+        &[&admin],
+    );
+    assert_eq!(
+        error_code(&result.unwrap_err()),
+        Some(6000 + ErrorCode::InvalidProtocolFee as u32)
+    );
+}
+
+#[test]
+fn random_user_cannot_override_the_protocol_fee() {
+    let mut env = TestEnv::new();
+    let (FundedPool { keys, .. }, _) = env.setup_pool(2_000, 100, (RESERVE_A, RESERVE_B));
+    let user = env.create_user();
+
+    let result = env.send(
+        &[instructions::update_pool(
+            &user.pubkey(),
+            &keys.pool,
+            None,
+            None,
+            Some(Some(0)),
+            None,
+        )],
+        &[&user],
+    );
+    assert_eq!(
+        error_code(&result.unwrap_err()),
+        Some(6000 + ErrorCode::NotPoolAdmin as u32)
+    );
+}
+
+#[test]
+fn claim_fees_pays_the_fee_recipient() {
+    let mut env = TestEnv::new();
+    let (FundedPool { keys, .. }, admin) = env.setup_pool(2_000, 100, (RESERVE_A, RESERVE_B));
+    let protocol_fee = swap_a_to_b(&mut env, &keys, 2_000);
+
+    let recipient = env.fetch_config().protocol_fee_recipient;
+    let recipient_ata_a = env.create_ata(&keys.token_mint_a, &recipient);
+    let recipient_ata_b = env.create_ata(&keys.token_mint_b, &recipient);
+
+    env.send(&[instructions::claim_fees(&keys, &[recipient])], &[&admin])
+        .expect("claim_fees failed");
+
+    assert_eq!(env.token_balance(&recipient_ata_a), protocol_fee);
+    assert_eq!(env.token_balance(&recipient_ata_b), 0);
+    let pool = env.fetch_pool(&keys.pool);
+    assert_eq!((pool.protocol_fees_a, pool.protocol_fees_b), (0, 0));
+}
+
+#[test]
+fn claim_fees_to_another_owner_fails() {
+    let mut env = TestEnv::new();
+    let (FundedPool { keys, .. }, admin) = env.setup_pool(2_000, 100, (RESERVE_A, RESERVE_B));
+    swap_a_to_b(&mut env, &keys, 2_000);
+
+    let attacker = env.create_user();
+    env.create_ata(&keys.token_mint_a, &attacker.pubkey());
+    env.create_ata(&keys.token_mint_b, &attacker.pubkey());
+
+    let result = env.send(
+        &[instructions::claim_fees(&keys, &[attacker.pubkey()])],
+        &[&admin],
+    );
+    assert_eq!(
+        error_code(&result.unwrap_err()),
+        Some(6000 + ErrorCode::InvalidFeeRecipient as u32)
+    );
+}
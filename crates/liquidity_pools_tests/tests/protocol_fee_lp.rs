@@ -0,0 +1,381 @@
+use liquidity_pools::error::ErrorCode;
+use liquidity_pools::math;
+use liquidity_pools::state::{FeeRecipient, ProtocolFeeMode};
+use liquidity_pools_client::instructions::{self, SwapDirection};
+use liquidity_pools_client::pda::PoolKeys;
+use liquidity_pools_client::quote::{self, AddLiquidityQuote, PoolState, RemoveLiquidityQuote};
+use liquidity_pools_tests::{error_code, FundedPool, TestEnv};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+
+const RESERVE: u64 = 1_000_000_000;
+const AMOUNT_IN: u64 = 50_000_000;
+
+// Config with a 1/6 protocol share minted as LP tokens. There is no timelock
+// delay yet, so the change applies right away
+fn setup_lp_fee(env: &mut TestEnv) -> Keypair {
+    let admin = env.initialize(0);
+    env.send(
+        &[
+            instructions::queue_config_change(
+                &admin.pubkey(),
+                None,
+                Some(1_667),
+                None,
+                Some(ProtocolFeeMode::LpTokens),
+                None,
+                None,
+            ),
+            instructions::execute_config_change(&admin.pubkey()),
+        ],
+        &[&admin],
+    )
+    .expect("protocol fee mode change failed");
+    assert!(env.fetch_config().mints_protocol_fee());
+    admin
+}
+
+// The provider keeps as much again for later deposits
+fn setup_pool(env: &mut TestEnv) -> (PoolKeys, Keypair) {
+    let FundedPool { keys, provider, .. } = env.create_test_pool(30, (RESERVE, RESERVE));
+    env.mint_to(&keys.user_ata_a(&provider.pubkey()), RESERVE);
+    env.mint_to(&keys.user_ata_b(&provider.pubkey()), RESERVE);
+    (keys, provider)
+}
+
+fn swap_back_and_forth(env: &mut TestEnv, keys: &PoolKeys) {
+    let trader = env.fund_user(keys, RESERVE, RESERVE);
+    for direction in [SwapDirection::AToB, SwapDirection::BToA] {
+        env.swap(keys, &trader, direction, AMOUNT_IN, 1, None)
+            .expect("swap failed");
+    }
+}
+
+#[test]
+fn swaps_leave_the_protocol_fee_in_the_reserves() {
+    let mut env = TestEnv::new();
+    setup_lp_fee(&mut env);
+    let (keys, _) = setup_pool(&mut env);
+    assert_eq!(
+        env.fetch_pool(&keys.pool).k_last,
+        RESERVE as u128 * RESERVE as u128
+    );
+
+    swap_back_and_forth(&mut env, &keys);
+
+    let pool = env.fetch_pool(&keys.pool);
+    assert_eq!((pool.protocol_fees_a, pool.protocol_fees_b), (0, 0));
+    let (reserve_a, reserve_b) = env.reserves(&keys);
+    assert!(reserve_a as u128 * reserve_b as u128 > pool.k_last);
+}
+
+#[test]
+fn liquidity_changes_mint_the_protocol_share_of_the_fee_growth() {
+    let mut env = TestEnv::new();
+    setup_lp_fee(&mut env);
+    let (keys, provider) = setup_pool(&mut env);
+    let recipient = env.fetch_config().protocol_fee_recipient;
+    let recipient_ata_lp = env.create_ata(&keys.lp_mint, &recipient);
+
+    swap_back_and_forth(&mut env, &keys);
+    let k_last = env.fetch_pool(&keys.pool).k_last;
+    let (reserve_a, reserve_b) = env.reserves(&keys);
+    let lp_supply = env.mint_supply(&keys.lp_mint);
+    let expected = math::protocol_lp_fee(reserve_a, reserve_b, k_last, lp_supply, 1_667).unwrap();
+    assert!(expected > 0);
+
+    // Any liquidity change collects the fee, here a deposit
+    env.add_liquidity(&keys, &provider, RESERVE / 10, RESERVE / 10)
+        .expect("add_liquidity failed");
+    assert_eq!(env.token_balance(&recipient_ata_lp), expected);
+    let (reserve_a, reserve_b) = env.reserves(&keys);
+    assert_eq!(
+        env.fetch_pool(&keys.pool).k_last,
+        reserve_a as u128 * reserve_b as u128
+    );
+
+    // Nothing more is owed until the next swaps
+    let lp_amount = env.token_balance(&keys.user_ata_lp(&provider.pubkey()));
+    env.send(
+        &[instructions::remove_liquidity(
+            &keys,
+            &provider.pubkey(),
+            lp_amount,
+            1,
+            1,
+            None,
+            &[recipient],
+            false,
+        )],
+        &[&provider],
+    )
+    .expect("remove_liquidity failed");
+    assert_eq!(env.token_balance(&recipient_ata_lp), expected);
+    assert_eq!(env.mint_supply(&keys.lp_mint), expected);
+}
+
+fn add_liquidity(
+    env: &mut TestEnv,
+    keys: &PoolKeys,
+    provider: &Keypair,
+    fee_recipients: &[Pubkey],
+) -> Result<(), Option<u32>> {
+    env.send(
+        &[instructions::add_liquidity(
+            keys,
+            &provider.pubkey(),
+            RESERVE / 10,
+            RESERVE / 10,
+            0,
+            0,
+            None,
+            fee_recipients,
+        )],
+        &[provider],
+    )
+    .map(|_| ())
+    .map_err(|failed| error_code(&failed))
+}
+
+#[test]
+fn pending_protocol_lp_fee_needs_the_recipient_lp_accounts() {
+    let mut env = TestEnv::new();
+    setup_lp_fee(&mut env);
+    let (keys, provider) = setup_pool(&mut env);
+    let recipient = env.fetch_config().protocol_fee_recipient;
+
+    // Nothing is pending before the first swap, the accounts can be left out
+    add_liquidity(&mut env, &keys, &provider, &[]).expect("add_liquidity failed");
+
+    swap_back_and_forth(&mut env, &keys);
+    let k_last = env.fetch_pool(&keys.pool).k_last;
+    let (reserve_a, reserve_b) = env.reserves(&keys);
+    let lp_supply = env.mint_supply(&keys.lp_mint);
+    let expected = math::protocol_lp_fee(reserve_a, reserve_b, k_last, lp_supply, 1_667).unwrap();
+    assert!(expected > 0);
+
+    // Once a fee is pending, leaving the accounts out fails for deposits and withdrawals
+    assert_eq!(
+        add_liquidity(&mut env, &keys, &provider, &[]),
+        Err(Some(6000 + ErrorCode::InvalidFeeRecipient as u32))
+    );
+    let provider_ata_lp = keys.user_ata_lp(&provider.pubkey());
+    let result = env.send(
+        &[instructions::remove_liquidity(
+            &keys,
+            &provider.pubkey(),
+            env.token_balance(&provider_ata_lp) / 2,
+            1,
+            1,
+            None,
+            &[],
+            false,
+        )],
+        &[&provider],
+    );
+    assert_eq!(
+        error_code(&result.unwrap_err()),
+        Some(6000 + ErrorCode::InvalidFeeRecipient as u32)
+    );
+
+    // So do accounts of someone else
+    assert_eq!(
+        add_liquidity(&mut env, &keys, &provider, &[provider.pubkey()]),
+        Err(Some(6000 + ErrorCode::InvalidFeeRecipient as u32))
+    );
+
+    // The client creates the missing account in the same transaction
+    let fee_recipients =
+        instructions::protocol_fee_lp_recipients(&env.fetch_config(), &env.fetch_pool(&keys.pool));
+    assert_eq!(fee_recipients, vec![recipient]);
+    let mut liquidity_instructions =
+        instructions::create_recipient_lp_accounts(&provider.pubkey(), &keys, &fee_recipients);
+    liquidity_instructions.push(instructions::add_liquidity(
+        &keys,
+        &provider.pubkey(),
+        RESERVE / 10,
+        RESERVE / 10,
+        0,
+        0,
+        None,
+        &fee_recipients,
+    ));
+    env.send(&liquidity_instructions, &[&provider])
+        .expect("add_liquidity failed");
+    assert_eq!(env.token_balance(&keys.user_ata_lp(&recipient)), expected);
+}
+
+#[test]
+fn switching_to_swap_mode_mints_the_accrued_fee_first() {
+    let mut env = TestEnv::new();
+    let admin = setup_lp_fee(&mut env);
+    let (keys, provider) = setup_pool(&mut env);
+    let recipient = env.fetch_config().protocol_fee_recipient;
+    let recipient_ata_lp = env.create_ata(&keys.lp_mint, &recipient);
+    swap_back_and_forth(&mut env, &keys);
+
+    env.send(
+        &[
+            instructions::queue_config_change(
+                &admin.pubkey(),
+                None,
+                None,
+                None,
+                Some(ProtocolFeeMode::Swap),
+                None,
+                None,
+            ),
+            instructions::execute_config_change(&admin.pubkey()),
+        ],
+        &[&admin],
+    )
+    .expect("protocol fee mode change failed");
+
+    // Until its next liquidity change the pool keeps the fee in the reserves
+    swap_back_and_forth(&mut env, &keys);
+    let pool = env.fetch_pool(&keys.pool);
+    assert_eq!((pool.protocol_fees_a, pool.protocol_fees_b), (0, 0));
+
+    let (reserve_a, reserve_b) = env.reserves(&keys);
+    let lp_supply = env.mint_supply(&keys.lp_mint);
+    let expected =
+        math::protocol_lp_fee(reserve_a, reserve_b, pool.k_last, lp_supply, 1_667).unwrap();
+    assert!(expected > 0);
+    env.add_liquidity(&keys, &provider, RESERVE / 10, RESERVE / 10)
+        .expect("add_liquidity failed");
+    assert_eq!(env.token_balance(&recipient_ata_lp), expected);
+    assert_eq!(env.fetch_pool(&keys.pool).k_last, 0);
+
+    // From there swaps skim the protocol fee
+    swap_back_and_forth(&mut env, &keys);
+    let pool = env.fetch_pool(&keys.pool);
+    assert!(pool.protocol_fees_a > 0 && pool.protocol_fees_b > 0);
+}
+
+#[test]
+fn protocol_lp_fee_follows_the_fee_split() {
+    let mut env = TestEnv::new();
+    let admin = setup_lp_fee(&mut env);
+    let (first, second) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let fee_recipients = vec![
+        FeeRecipient {
+            recipient: first,
+            weight_bp: 7_000,
+        },
+        FeeRecipient {
+            recipient: second,
+            weight_bp: 3_000,
+        },
+    ];
+    env.send(
+        &[
+            instructions::queue_config_change(
+                &admin.pubkey(),
+                None,
+                None,
+                None,
+                None,
+                None,
+                Some(fee_recipients),
+            ),
+            instructions::execute_config_change(&admin.pubkey()),
+        ],
+        &[&admin],
+    )
+    .expect("fee split change failed");
+    let (keys, provider) = setup_pool(&mut env);
+    let first_ata_lp = env.create_ata(&keys.lp_mint, &first);
+    let second_ata_lp = env.create_ata(&keys.lp_mint, &second);
+
+    swap_back_and_forth(&mut env, &keys);
+    let k_last = env.fetch_pool(&keys.pool).k_last;
+    let (reserve_a, reserve_b) = env.reserves(&keys);
+    let lp_supply = env.mint_supply(&keys.lp_mint);
+    let expected = math::protocol_lp_fee(reserve_a, reserve_b, k_last, lp_supply, 1_667).unwrap();
+
+    env.add_liquidity(&keys, &provider, RESERVE / 10, RESERVE / 10)
+        .expect("add_liquidity failed");
+    // The last recipient takes the rounding dust
+    let first_share = math::split_fee(expected, 7_000).unwrap().share;
+    assert_eq!(env.token_balance(&first_ata_lp), first_share);
+    assert_eq!(env.token_balance(&second_ata_lp), expected - first_share);
+}
+
+#[test]
+fn quotes_include_the_pending_protocol_lp_fee() {
+    let mut env = TestEnv::new();
+    setup_lp_fee(&mut env);
+    let (keys, provider) = setup_pool(&mut env);
+    let recipient = env.fetch_config().protocol_fee_recipient;
+    env.create_ata(&keys.lp_mint, &recipient);
+    swap_back_and_forth(&mut env, &keys);
+
+    let provider_ata_lp = keys.user_ata_lp(&provider.pubkey());
+    let lp_before = env.token_balance(&provider_ata_lp);
+    let quote: AddLiquidityQuote = env
+        .view(
+            instructions::quote_add_liquidity(&keys, RESERVE / 10, RESERVE / 10),
+            &provider,
+        )
+        .expect("quote_add_liquidity failed");
+    env.add_liquidity(&keys, &provider, RESERVE / 10, RESERVE / 10)
+        .expect("add_liquidity failed");
+    assert_eq!(
+        env.token_balance(&provider_ata_lp) - lp_before,
+        quote.lp_amount
+    );
+
+    // Fees accrued again, so the withdrawal also mints the protocol share first
+    swap_back_and_forth(&mut env, &keys);
+    let lp_amount = env.token_balance(&provider_ata_lp) / 2;
+    let quote: RemoveLiquidityQuote = env
+        .view(
+            instructions::quote_remove_liquidity(&keys, lp_amount),
+            &provider,
+        )
+        .expect("quote_remove_liquidity failed");
+    // The pool state prices the withdrawal the same way
+    let state: PoolState = env
+        .view(instructions::get_pool_state(&keys), &provider)
+        .expect("get_pool_state failed");
+    assert!(state.lp_supply > env.mint_supply(&keys.lp_mint));
+    assert_eq!(
+        quote::quote_remove_liquidity(lp_amount, state.reserve_a, state.reserve_b, state.lp_supply)
+            .unwrap(),
+        quote
+    );
+    let provider_ata_a = keys.user_ata_a(&provider.pubkey());
+    let a_before = env.token_balance(&provider_ata_a);
+    env.send(
+        &[instructions::remove_liquidity(
+            &keys,
+            &provider.pubkey(),
+            lp_amount,
+            1,
+            1,
+            None,
+            &[recipient],
+            false,
+        )],
+        &[&provider],
+    )
+    .expect("remove_liquidity failed");
+    assert_eq!(
+        env.token_balance(&provider_ata_a) - a_before,
+        quote.amount_a
+    );
+}
+
+#[test]
+fn swap_mode_keeps_k_last_cleared() {
+    let mut env = TestEnv::new();
+    env.initialize(1_667);
+    let (keys, provider) = setup_pool(&mut env);
+    swap_back_and_forth(&mut env, &keys);
+
+    env.add_liquidity(&keys, &provider, RESERVE / 10, RESERVE / 10)
+        .expect("add_liquidity failed");
+    let pool = env.fetch_pool(&keys.pool);
+    assert_eq!(pool.k_last, 0);
+    assert!(pool.protocol_fees_a > 0 && pool.protocol_fees_b > 0);
+}
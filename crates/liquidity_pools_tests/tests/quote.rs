@@ -0,0 +1,164 @@
+use liquidity_pools::error::ErrorCode;
+use liquidity_pools_client::instructions::{self, SwapDirection};
+use liquidity_pools_client::quote::{
+    self, AddLiquidityQuote, PoolState, RemoveLiquidityQuote, SwapQuote,
+};
+use liquidity_pools_tests::{error_code, FundedPool, TestEnv, TOKEN_PROGRAM_ID};
+use solana_sdk::signature::Signer;
+
+const RESERVE_A: u64 = 92_495 * 1_000_000_000;
+const RESERVE_B: u64 = 33_053_283 * 1_000_000_000;
+
+#[test]
+fn quote_swap_matches_executed_swap() {
+    let mut env = TestEnv::new();
+    let (FundedPool { keys, .. }, _) = env.setup_pool(0, 100, (RESERVE_A, RESERVE_B));
+    let amount_in = 150 * 10u64.pow(9);
+    let user = env.fund_user(&keys, 0, amount_in);
+
+    let quote: SwapQuote = env
+        .view(
+            instructions::quote_swap(&keys, amount_in, SwapDirection::BToA),
+            &user,
+        )
+        .expect("quote_swap failed");
+
+    // on-chain quote and off-chain quote use the same math
+    let (reserve_a, reserve_b) = env.reserves(&keys);
+    let pool = env.fetch_pool(&keys.pool);
+    assert_eq!(
+        quote,
+        quote::quote_swap(&pool, amount_in, reserve_b, reserve_a).unwrap()
+    );
+
+    env.swap(
+        &keys,
+        &user,
+        SwapDirection::BToA,
+        amount_in,
+        quote.amount_out,
+        None,
+    )
+    .expect("swap failed");
+    assert_eq!(
+        env.token_balance(&keys.user_ata_a(&user.pubkey())),
+        quote.amount_out
+    );
+}
+
+#[test]
+fn quote_swap_exact_out_returns_enough_input() {
+    let mut env = TestEnv::new();
+    let (FundedPool { keys, provider, .. }, _) = env.setup_pool(0, 100, (RESERVE_A, RESERVE_B));
+    let amount_out = 1_000 * 10u64.pow(9);
+
+    let quote: SwapQuote = env
+        .view(
+            instructions::quote_swap_exact_out(&keys, amount_out, SwapDirection::AToB),
+            &provider,
+        )
+        .expect("quote_swap_exact_out failed");
+    assert!(quote.amount_out >= amount_out);
+
+    let user = env.fund_user(&keys, quote.amount_in, 0);
+    env.swap(
+        &keys,
+        &user,
+        SwapDirection::AToB,
+        quote.amount_in,
+        amount_out,
+        None,
+    )
+    .expect("swap failed");
+    assert_eq!(
+        env.token_balance(&keys.user_ata_b(&user.pubkey())),
+        quote.amount_out
+    );
+}
+
+#[test]
+fn quote_liquidity_matches_executed_deposit_and_withdrawal() {
+    let mut env = TestEnv::new();
+    let (FundedPool { keys, .. }, _) = env.setup_pool(0, 100, (RESERVE_A, RESERVE_B));
+    let provider = env.fund_user(&keys, RESERVE_A, RESERVE_B);
+
+    let deposit: AddLiquidityQuote = env
+        .view(
+            instructions::quote_add_liquidity(&keys, RESERVE_A / 3, RESERVE_B),
+            &provider,
+        )
+        .expect("quote_add_liquidity failed");
+    env.add_liquidity(&keys, &provider, RESERVE_A / 3, RESERVE_B)
+        .expect("add_liquidity failed");
+
+    let lp_balance = env.token_balance(&keys.user_ata_lp(&provider.pubkey()));
+    assert_eq!(lp_balance, deposit.lp_amount);
+    assert_eq!(
+        env.token_balance(&keys.user_ata_a(&provider.pubkey())),
+        RESERVE_A - deposit.amount_a
+    );
+
+    let withdrawal: RemoveLiquidityQuote = env
+        .view(
+            instructions::quote_remove_liquidity(&keys, lp_balance),
+            &provider,
+        )
+        .expect("quote_remove_liquidity failed");
+    env.send(
+        &[instructions::remove_liquidity(
+            &keys,
+            &provider.pubkey(),
+            lp_balance,
+            withdrawal.amount_a,
+            withdrawal.amount_b,
+            None,
+            &[],
+            false,
+        )],
+        &[&provider],
+    )
+    .expect("remove_liquidity failed");
+    assert_eq!(
+        env.token_balance(&keys.user_ata_a(&provider.pubkey())),
+        RESERVE_A - deposit.amount_a + withdrawal.amount_a
+    );
+}
+
+#[test]
+fn get_pool_state_returns_reserves_and_supply() {
+    let mut env = TestEnv::new();
+    let (FundedPool { keys, provider, .. }, _) = env.setup_pool(0, 100, (RESERVE_A, RESERVE_B));
+
+    let state: PoolState = env
+        .view(instructions::get_pool_state(&keys), &provider)
+        .expect("get_pool_state failed");
+
+    assert_eq!(
+        state,
+        PoolState {
+            token_mint_a: keys.token_mint_a,
+            token_mint_b: keys.token_mint_b,
+            lp_mint: keys.lp_mint,
+            reserve_a: RESERVE_A,
+            reserve_b: RESERVE_B,
+            lp_supply: env.mint_supply(&keys.lp_mint),
+            fee_bp: env.fetch_pool(&keys.pool).fee_bp,
+        }
+    );
+}
+
+#[test]
+fn quote_swap_fails_on_empty_pool() {
+    let mut env = TestEnv::new();
+    let creator = env.create_user();
+    let keys = env.create_pool(&creator, (9, 9), (TOKEN_PROGRAM_ID, TOKEN_PROGRAM_ID), 100);
+
+    let result = env.view::<SwapQuote>(
+        instructions::quote_swap(&keys, 1_000, SwapDirection::AToB),
+        &creator,
+    );
+    assert_eq!(
+        error_code(&result.unwrap_err()),
+        Some(6000 + ErrorCode::EmptyPool as u32)
+    );
+}
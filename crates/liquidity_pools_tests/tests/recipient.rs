@@ -0,0 +1,78 @@
+use liquidity_pools_client::instructions::{self, SwapDirection};
+use liquidity_pools_client::quote::quote_swap;
+use liquidity_pools_tests::{FundedPool, TestEnv};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signer;
+
+const RESERVE: u64 = 1_000_000 * 1_000_000_000;
+const AMOUNT_IN: u64 = 1_000 * 1_000_000_000;
+
+#[test]
+fn swap_pays_another_recipient_and_creates_its_account() {
+    let mut env = TestEnv::new();
+    let (FundedPool { keys, .. }, _) = env.setup_pool(0, 30, (RESERVE, RESERVE));
+    let payer = env.fund_user(&keys, 2 * AMOUNT_IN, 0);
+    let merchant = Pubkey::new_unique();
+    let merchant_ata_b = keys.user_ata_b(&merchant);
+    assert!(env.svm.get_account(&merchant_ata_b).is_none());
+
+    let mut received = 0;
+    // The second swap finds the account created by the first one
+    for _ in 0..2 {
+        let (reserve_a, reserve_b) = env.reserves(&keys);
+        let pool = env.fetch_pool(&keys.pool);
+        let quote = quote_swap(&pool, AMOUNT_IN, reserve_a, reserve_b).unwrap();
+        env.send(
+            &[instructions::swap_to(
+                &keys,
+                &payer.pubkey(),
+                &merchant,
+                SwapDirection::AToB,
+                AMOUNT_IN,
+                1,
+                None,
+                None,
+                false,
+            )],
+            &[&payer],
+        )
+        .expect("swap_to failed");
+        received += quote.amount_out;
+        assert_eq!(env.token_balance(&merchant_ata_b), received);
+        env.svm.expire_blockhash();
+    }
+    assert_eq!(env.token_balance(&keys.user_ata_a(&payer.pubkey())), 0);
+    assert_eq!(env.token_balance(&keys.user_ata_b(&payer.pubkey())), 0);
+}
+
+#[test]
+fn deposit_mints_lp_tokens_to_another_recipient() {
+    let mut env = TestEnv::new();
+    let (FundedPool { keys, .. }, _) = env.setup_pool(0, 30, (RESERVE, RESERVE));
+    let provider = env.fund_user(&keys, RESERVE / 10, RESERVE / 10);
+    let vault = Pubkey::new_unique();
+    let lp_supply = env.mint_supply(&keys.lp_mint);
+
+    env.send(
+        &[instructions::add_liquidity_to(
+            &keys,
+            &provider.pubkey(),
+            &vault,
+            RESERVE / 10,
+            RESERVE / 10,
+            0,
+            0,
+            None,
+            &[],
+            false,
+        )],
+        &[&provider],
+    )
+    .expect("add_liquidity_to failed");
+
+    assert_eq!(env.token_balance(&keys.user_ata_lp(&vault)), lp_supply / 10);
+    assert!(env
+        .svm
+        .get_account(&keys.user_ata_lp(&provider.pubkey()))
+        .is_none());
+}
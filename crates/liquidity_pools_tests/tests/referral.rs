@@ -0,0 +1,155 @@
+use anchor_lang::prelude::Pubkey;
+use liquidity_pools::error::ErrorCode;
+use liquidity_pools::events::SwapEvent;
+use liquidity_pools::math;
+use liquidity_pools_client::instructions::{self, SwapDirection};
+use liquidity_pools_client::pda::PoolKeys;
+use liquidity_pools_client::quote::quote_swap;
+use liquidity_pools_tests::{error_code, events, TestEnv};
+use solana_sdk::signature::{Keypair, Signer};
+
+const RESERVE_A: u64 = 1_000_000 * 1_000_000_000;
+const RESERVE_B: u64 = 1_000_000 * 1_000_000_000;
+const AMOUNT_IN: u64 = 1_000 * 1_000_000_000;
+
+// Pool with a 1% fee and referrers capped at 30% of the fee
+fn setup_pool(env: &mut TestEnv) -> (PoolKeys, Keypair) {
+    let admin = env.initialize(0);
+    env.send(
+        &[instructions::update_config(
+            &admin.pubkey(),
+            Some(3_000),
+            None,
+            None,
+            None,
+            None,
+        )],
+        &[&admin],
+    )
+    .expect("update_config failed");
+
+    let keys = env.create_test_pool(100, (RESERVE_A, RESERVE_B)).keys;
+    (keys, admin)
+}
+
+fn swap_with_referrer(
+    env: &mut TestEnv,
+    keys: &PoolKeys,
+    user: &Keypair,
+    referrer_ata: Pubkey,
+    referral_fee_bp: u16,
+) -> Result<Vec<SwapEvent>, Option<u32>> {
+    env.send(
+        &[instructions::swap_with_referrer(
+            keys,
+            &user.pubkey(),
+            SwapDirection::AToB,
+            AMOUNT_IN,
+            1,
+            None,
+            Some((referrer_ata, referral_fee_bp)),
+        )],
+        &[user],
+    )
+    .map(|meta| events(&meta))
+    .map_err(|failed| error_code(&failed))
+}
+
+#[test]
+fn referrer_receives_share_of_the_fee() {
+    let mut env = TestEnv::new();
+    let (keys, _) = setup_pool(&mut env);
+    let referrer = Pubkey::new_unique();
+    let referrer_ata = env.create_ata(&keys.token_mint_a, &referrer);
+    let user = env.fund_user(&keys, AMOUNT_IN, 0);
+    let pool = env.fetch_pool(&keys.pool);
+    let quote = quote_swap(&pool, AMOUNT_IN, RESERVE_A, RESERVE_B).unwrap();
+
+    let swap_events =
+        swap_with_referrer(&mut env, &keys, &user, referrer_ata, 2_000).expect("swap failed");
+
+    let referral_fee = math::split_fee(quote.fee_amount, 2_000).unwrap().share;
+    assert!(referral_fee > 0);
+    assert_eq!(env.token_balance(&referrer_ata), referral_fee);
+    // The pool keeps the rest of the input, output is unchanged
+    assert_eq!(
+        env.reserves(&keys),
+        (
+            RESERVE_A + AMOUNT_IN - referral_fee,
+            RESERVE_B - quote.amount_out
+        )
+    );
+    assert_eq!(
+        env.token_balance(&keys.user_ata_b(&user.pubkey())),
+        quote.amount_out
+    );
+
+    assert_eq!(swap_events.len(), 1);
+    assert_eq!(swap_events[0].referrer, Some(referrer_ata));
+    assert_eq!(swap_events[0].referral_fee, referral_fee);
+    assert_eq!(swap_events[0].fee_amount, quote.fee_amount);
+}
+
+#[test]
+fn swap_without_referrer_keeps_the_whole_fee() {
+    let mut env = TestEnv::new();
+    let (keys, _) = setup_pool(&mut env);
+    let user = env.fund_user(&keys, AMOUNT_IN, 0);
+
+    let meta = env
+        .swap(&keys, &user, SwapDirection::AToB, AMOUNT_IN, 1, None)
+        .expect("swap failed");
+
+    assert_eq!(env.reserves(&keys).0, RESERVE_A + AMOUNT_IN);
+    let swap_events = events::<SwapEvent>(&meta);
+    assert_eq!(swap_events[0].referrer, None);
+    assert_eq!(swap_events[0].referral_fee, 0);
+}
+
+#[test]
+fn referral_fee_above_the_config_cap_fails() {
+    let mut env = TestEnv::new();
+    let (keys, _) = setup_pool(&mut env);
+    let referrer_ata = env.create_ata(&keys.token_mint_a, &Pubkey::new_unique());
+    let user = env.fund_user(&keys, AMOUNT_IN, 0);
+
+    assert_eq!(
+        swap_with_referrer(&mut env, &keys, &user, referrer_ata, 3_001).unwrap_err(),
+        Some(6000 + ErrorCode::ReferralFeeTooHigh as u32)
+    );
+}
+
+#[test]
+fn referrer_account_must_hold_the_input_token() {
+    let mut env = TestEnv::new();
+    let (keys, _) = setup_pool(&mut env);
+    let referrer_ata = env.create_ata(&keys.token_mint_b, &Pubkey::new_unique());
+    let user = env.fund_user(&keys, AMOUNT_IN, 0);
+
+    assert_eq!(
+        swap_with_referrer(&mut env, &keys, &user, referrer_ata, 1_000).unwrap_err(),
+        Some(6000 + ErrorCode::InvalidReferrer as u32)
+    );
+}
+
+#[test]
+fn max_referral_fee_cannot_exceed_the_whole_fee() {
+    let mut env = TestEnv::new();
+    let (_, admin) = setup_pool(&mut env);
+
+    let result = env.send(
+        &[instructions::update_config(
+            &admin.pubkey(),
+            Some(10_001),
+            None,
+            None,
+            None,
+            None,
+        )],
+        &[&admin],
+    );
+    assert_eq!(
+        error_code(&result.unwrap_err()),
+        Some(6000 + ErrorCode::ReferralFeeTooHigh as u32)
+    );
+}
@@ -0,0 +1,189 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::{AccountDeserialize, AccountSerialize};
+use liquidity_pools::state::{PairIndex, PairIndexHeader, PoolRegistry};
+use liquidity_pools_client::instructions;
+use liquidity_pools_client::pda::{find_config_address, find_registry_address, PoolKeys};
+use liquidity_pools_tests::{TestEnv, TOKEN_2022_PROGRAM_ID, TOKEN_PROGRAM_ID};
+use solana_sdk::account::Account;
+use solana_sdk::signature::{Keypair, Signer};
+
+fn fetch_registry(env: &TestEnv, page: u32) -> PoolRegistry {
+    let account = env
+        .svm
+        .get_account(&find_registry_address(page).0)
+        .expect("registry page not found");
+    PoolRegistry::try_deserialize(&mut account.data.as_slice()).unwrap()
+}
+
+// Pair index page listing the pool
+fn fetch_pair_index(env: &TestEnv, keys: &PoolKeys) -> PairIndex {
+    let page = env.fetch_pool(&keys.pool).pair_index_page;
+    let account = env
+        .svm
+        .get_account(&keys.pair_index(page))
+        .expect("pair index not found");
+    PairIndex::try_deserialize(&mut account.data.as_slice()).unwrap()
+}
+
+fn write_account<T: AccountSerialize>(env: &mut TestEnv, address: Pubkey, value: &T) {
+    let mut account = env.svm.get_account(&address).unwrap();
+    let len = account.data.len();
+    account.data.clear();
+    value.try_serialize(&mut account.data).unwrap();
+    account.data.resize(len, 0);
+    env.svm.set_account(address, account).unwrap();
+}
+
+fn create_pool(env: &mut TestEnv, creator: &Keypair, keys: &PoolKeys, fee_bp: u16) {
+    env.send(
+        &[instructions::create_pool(
+            &creator.pubkey(),
+            keys,
+            fee_bp,
+            env.fetch_config().registry_page(),
+            env.pair_index_page(keys),
+        )],
+        &[creator],
+    )
+    .expect("create_pool failed");
+}
+
+#[test]
+fn create_pool_registers_every_fee_tier_of_a_pair() {
+    let mut env = TestEnv::new();
+    env.initialize(0);
+    let creator = env.create_user();
+    let (token_mint_a, token_mint_b) =
+        env.create_mint_pair((9, 9), (TOKEN_PROGRAM_ID, TOKEN_2022_PROGRAM_ID));
+
+    let tiers: Vec<PoolKeys> = [(0, 5), (1, 30), (2, 100)]
+        .into_iter()
+        .map(|(index, fee_bp)| {
+            let keys = env.pool_keys(token_mint_a, token_mint_b, index);
+            create_pool(&mut env, &creator, &keys, fee_bp);
+            keys
+        })
+        .collect();
+    let other = env.create_pool(&creator, (9, 9), (TOKEN_PROGRAM_ID, TOKEN_PROGRAM_ID), 30);
+
+    let pair_index = fetch_pair_index(&env, &tiers[0]);
+    assert_eq!(
+        (pair_index.token_mint_a, pair_index.token_mint_b),
+        (token_mint_a, token_mint_b)
+    );
+    let pools: Vec<Pubkey> = tiers.iter().map(|keys| keys.pool).collect();
+    assert_eq!(pair_index.pools, pools);
+    assert_eq!(fetch_pair_index(&env, &other).pools, vec![other.pool]);
+
+    // The registry lists every pool in creation order
+    let registry = fetch_registry(&env, 0);
+    assert_eq!(registry.page, 0);
+    assert_eq!(registry.pools, [pools, vec![other.pool]].concat());
+    assert_eq!(env.fetch_config().pool_count, 4);
+}
+
+#[test]
+fn full_registry_page_rolls_over_to_the_next_one() {
+    let mut env = TestEnv::new();
+    env.initialize(0);
+    let creator = env.create_user();
+    let first = env.create_pool(&creator, (9, 9), (TOKEN_PROGRAM_ID, TOKEN_PROGRAM_ID), 30);
+
+    // Pretend the rest of page 0 was filled
+    let mut config = env.fetch_config();
+    config.pool_count = PoolRegistry::PAGE_SIZE as u64;
+    write_account(&mut env, find_config_address().0, &config);
+
+    let second = env.create_pool(&creator, (9, 9), (TOKEN_PROGRAM_ID, TOKEN_PROGRAM_ID), 30);
+    assert_eq!(fetch_registry(&env, 0).pools, vec![first.pool]);
+    let registry = fetch_registry(&env, 1);
+    assert_eq!((registry.page, registry.pools), (1, vec![second.pool]));
+    assert_eq!(
+        env.fetch_config().pool_count,
+        PoolRegistry::PAGE_SIZE as u64 + 1
+    );
+}
+
+#[test]
+fn pair_index_pages_fill_in_registration_order() {
+    let mut env = TestEnv::new();
+    env.initialize(0);
+    let creator = env.create_user();
+    let (token_mint_a, token_mint_b) =
+        env.create_mint_pair((9, 9), (TOKEN_PROGRAM_ID, TOKEN_PROGRAM_ID));
+
+    // The pool index does not pick the page, the pools share the first one
+    let first_pools: Vec<PoolKeys> = [7, 2000, 0]
+        .into_iter()
+        .map(|index| {
+            let keys = env.pool_keys(token_mint_a, token_mint_b, index);
+            create_pool(&mut env, &creator, &keys, 30);
+            keys
+        })
+        .collect();
+    let header = env.fetch_pair_index_header(&first_pools[0]).unwrap();
+    assert_eq!((header.pool_count, header.page_count()), (3, 1));
+    let first_page = fetch_pair_index(&env, &first_pools[0]);
+    assert_eq!(first_page.page, 0);
+    assert_eq!(
+        first_page.pools,
+        first_pools.iter().map(|keys| keys.pool).collect::<Vec<_>>()
+    );
+
+    // Pretend the rest of page 0 was filled
+    let header = PairIndexHeader {
+        pool_count: PairIndex::MAX_POOLS as u32,
+        ..header
+    };
+    write_account(&mut env, first_pools[0].pair_index_header(), &header);
+
+    let keys = env.pool_keys(token_mint_a, token_mint_b, 1);
+    create_pool(&mut env, &creator, &keys, 30);
+    assert_eq!(env.fetch_pool(&keys.pool).pair_index_page, 1);
+    let second_page = fetch_pair_index(&env, &keys);
+    assert_eq!((second_page.page, second_page.pools), (1, vec![keys.pool]));
+    assert_eq!(fetch_pair_index(&env, &first_pools[0]).pools.len(), 3);
+    let header = env.fetch_pair_index_header(&keys).unwrap();
+    assert_eq!(
+        (header.pool_count, header.page_count()),
+        (PairIndex::MAX_POOLS as u32 + 1, 2)
+    );
+}
+
+#[test]
+fn anyone_registers_pools_created_before_the_registry() {
+    let mut env = TestEnv::new();
+    env.initialize(0);
+    let creator = env.create_user();
+    let keys = env.create_pool(&creator, (9, 9), (TOKEN_PROGRAM_ID, TOKEN_PROGRAM_ID), 30);
+
+    // Remove every trace of the registration
+    let mut config = env.fetch_config();
+    config.pool_count = 0;
+    write_account(&mut env, find_config_address().0, &config);
+    let mut pool = env.fetch_pool(&keys.pool);
+    pool.registered = false;
+    write_account(&mut env, keys.pool, &pool);
+    for address in [
+        find_registry_address(0).0,
+        keys.pair_index_header(),
+        keys.pair_index(0),
+    ] {
+        env.svm.set_account(address, Account::default()).unwrap();
+    }
+
+    let payer = env.create_user();
+    for _ in 0..2 {
+        env.send(
+            &[instructions::register_pool(&payer.pubkey(), &keys, 0, 0)],
+            &[&payer],
+        )
+        .expect("register_pool failed");
+    }
+
+    // Registering twice changes nothing
+    assert_eq!(fetch_registry(&env, 0).pools, vec![keys.pool]);
+    assert_eq!(fetch_pair_index(&env, &keys).pools, vec![keys.pool]);
+    assert_eq!(env.fetch_config().pool_count, 1);
+    assert_eq!(env.fetch_pair_index_header(&keys).unwrap().pool_count, 1);
+}
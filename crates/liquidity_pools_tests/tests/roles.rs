@@ -0,0 +1,284 @@
+use liquidity_pools::error::ErrorCode;
+use liquidity_pools::state::PoolCreationMode;
+use liquidity_pools_client::instructions::{self, SwapDirection};
+use liquidity_pools_tests::{error_code, TestEnv};
+use solana_sdk::signature::{Keypair, Signer};
+
+const RESERVE_A: u64 = 1_000_000 * 1_000_000_000;
+const RESERVE_B: u64 = 1_000_000 * 1_000_000_000;
+
+struct Roles {
+    admin: Keypair,
+    fee_manager: Keypair,
+    pause_guardian: Keypair,
+    pool_admin: Keypair,
+    rewards_admin: Keypair,
+    pool_creation_admin: Keypair,
+}
+
+// Initializes the config and hands every role to a different key
+fn setup_roles(env: &mut TestEnv) -> Roles {
+    let admin = env.initialize(0);
+    let roles = Roles {
+        admin,
+        fee_manager: env.create_user(),
+        pause_guardian: env.create_user(),
+        pool_admin: env.create_user(),
+        rewards_admin: env.create_user(),
+        pool_creation_admin: env.create_user(),
+    };
+    env.send(
+        &[instructions::update_roles(
+            &roles.admin.pubkey(),
+            Some(roles.fee_manager.pubkey()),
+            Some(roles.pause_guardian.pubkey()),
+            Some(roles.pool_admin.pubkey()),
+            Some(roles.rewards_admin.pubkey()),
+            Some(roles.pool_creation_admin.pubkey()),
+        )],
+        &[&roles.admin],
+    )
+    .expect("update_roles failed");
+    roles
+}
+
+#[test]
+fn roles_start_with_the_authority() {
+    let mut env = TestEnv::new();
+    let admin = env.initialize(0);
+
+    let config = env.fetch_config();
+    assert_eq!(config.fee_manager, admin.pubkey());
+    assert_eq!(config.pause_guardian, admin.pubkey());
+    assert_eq!(config.pool_admin, admin.pubkey());
+    assert_eq!(config.rewards_admin, admin.pubkey());
+    assert_eq!(config.pool_creation_admin, admin.pubkey());
+    assert!(!config.paused);
+}
+
+#[test]
+fn authority_assigns_roles() {
+    let mut env = TestEnv::new();
+    let roles = setup_roles(&mut env);
+
+    let config = env.fetch_config();
+    assert_eq!(config.authority, roles.admin.pubkey());
+    assert_eq!(config.fee_manager, roles.fee_manager.pubkey());
+    assert_eq!(config.pause_guardian, roles.pause_guardian.pubkey());
+    assert_eq!(config.pool_admin, roles.pool_admin.pubkey());
+    assert_eq!(config.rewards_admin, roles.rewards_admin.pubkey());
+    assert_eq!(
+        config.pool_creation_admin,
+        roles.pool_creation_admin.pubkey()
+    );
+
+    // Roles can not hand out roles
+    let result = env.send(
+        &[instructions::update_roles(
+            &roles.fee_manager.pubkey(),
+            None,
+            Some(roles.fee_manager.pubkey()),
+            None,
+            None,
+            None,
+        )],
+        &[&roles.fee_manager],
+    );
+    assert_eq!(
+        error_code(&result.unwrap_err()),
+        Some(6000 + ErrorCode::InvalidAuthority as u32)
+    );
+}
+
+#[test]
+fn fee_manager_updates_fees() {
+    let mut env = TestEnv::new();
+    let roles = setup_roles(&mut env);
+
+    env.send(
+        &[
+            instructions::update_config(
+                &roles.fee_manager.pubkey(),
+                Some(1_000),
+                None,
+                None,
+                None,
+                None,
+            ),
+            instructions::queue_config_change(
+                &roles.fee_manager.pubkey(),
+                None,
+                Some(500),
+                None,
+                None,
+                None,
+                None,
+            ),
+            instructions::execute_config_change(&roles.fee_manager.pubkey()),
+        ],
+        &[&roles.fee_manager],
+    )
+    .expect("config update failed");
+    let config = env.fetch_config();
+    assert_eq!(config.protocol_fee_bp, 500);
+    assert_eq!(config.max_referral_fee_bp, 1_000);
+
+    let result = env.send(
+        &[instructions::update_config(
+            &roles.pool_admin.pubkey(),
+            Some(0),
+            None,
+            None,
+            None,
+            None,
+        )],
+        &[&roles.pool_admin],
+    );
+    assert_eq!(
+        error_code(&result.unwrap_err()),
+        Some(6000 + ErrorCode::NotFeeManager as u32)
+    );
+}
+
+#[test]
+fn pause_guardian_halts_swaps() {
+    let mut env = TestEnv::new();
+    let roles = setup_roles(&mut env);
+    let keys = env.create_test_pool(30, (RESERVE_A, RESERVE_B)).keys;
+    let amount_in = 1_000_000_000;
+    let user = env.fund_user(&keys, 2 * amount_in, 0);
+
+    let result = env.send(
+        &[instructions::set_paused(&roles.fee_manager.pubkey(), true)],
+        &[&roles.fee_manager],
+    );
+    assert_eq!(
+        error_code(&result.unwrap_err()),
+        Some(6000 + ErrorCode::NotPauseGuardian as u32)
+    );
+
+    env.send(
+        &[instructions::set_paused(
+            &roles.pause_guardian.pubkey(),
+            true,
+        )],
+        &[&roles.pause_guardian],
+    )
+    .expect("set_paused failed");
+    assert!(env.fetch_config().paused);
+
+    let result = env.swap(&keys, &user, SwapDirection::AToB, amount_in, 1, None);
+    assert_eq!(
+        error_code(&result.unwrap_err()),
+        Some(6000 + ErrorCode::Paused as u32)
+    );
+
+    env.send(
+        &[instructions::set_paused(
+            &roles.pause_guardian.pubkey(),
+            false,
+        )],
+        &[&roles.pause_guardian],
+    )
+    .expect("set_paused failed");
+    env.swap(&keys, &user, SwapDirection::AToB, amount_in, 1, None)
+        .expect("swap failed");
+}
+
+#[test]
+fn pause_guardian_halts_flash_loans() {
+    let mut env = TestEnv::new();
+    let roles = setup_roles(&mut env);
+    let keys = env.create_test_pool(30, (RESERVE_A, RESERVE_B)).keys;
+    let amount = 1_000_000_000;
+    let user = env.fund_user(&keys, 2 * amount, 0);
+    let flash_loan = [
+        instructions::flash_loan(&keys, &user.pubkey(), amount, 0),
+        instructions::flash_repay(&keys, &user.pubkey(), 2 * amount, 0),
+    ];
+
+    env.send(
+        &[instructions::set_paused(
+            &roles.pause_guardian.pubkey(),
+            true,
+        )],
+        &[&roles.pause_guardian],
+    )
+    .expect("set_paused failed");
+
+    let result = env.send(&flash_loan, &[&user]);
+    assert_eq!(
+        error_code(&result.unwrap_err()),
+        Some(6000 + ErrorCode::Paused as u32)
+    );
+
+    env.send(
+        &[instructions::set_paused(
+            &roles.pause_guardian.pubkey(),
+            false,
+        )],
+        &[&roles.pause_guardian],
+    )
+    .expect("set_paused failed");
+    env.send(&flash_loan, &[&user]).expect("flash loan failed");
+}
+
+#[test]
+fn pool_admin_updates_pools() {
+    let mut env = TestEnv::new();
+    let roles = setup_roles(&mut env);
+    let keys = env.create_test_pool(30, (RESERVE_A, RESERVE_B)).keys;
+
+    env.update_pool(&keys, &roles.pool_admin, Some(500), None)
+        .expect("update_pool failed");
+    assert_eq!(env.fetch_pool(&keys.pool).max_price_impact_bp, 500);
+
+    // The authority keeps every role
+    env.update_pool(&keys, &roles.admin, Some(0), None)
+        .expect("update_pool failed");
+
+    let result = env.update_pool(&keys, &roles.fee_manager, Some(500), None);
+    assert_eq!(
+        error_code(&result.unwrap_err()),
+        Some(6000 + ErrorCode::NotPoolAdmin as u32)
+    );
+}
+
+#[test]
+fn pool_creation_admin_manages_pool_creation() {
+    let mut env = TestEnv::new();
+    let roles = setup_roles(&mut env);
+    let creator = env.create_user();
+
+    env.send(
+        &[
+            instructions::set_pool_creation_mode(
+                &roles.pool_creation_admin.pubkey(),
+                PoolCreationMode::Allowlist,
+            ),
+            instructions::add_pool_creator(&roles.pool_creation_admin.pubkey(), &creator.pubkey()),
+            instructions::remove_pool_creator(
+                &roles.pool_creation_admin.pubkey(),
+                &creator.pubkey(),
+            ),
+        ],
+        &[&roles.pool_creation_admin],
+    )
+    .expect("pool creation management failed");
+    assert_eq!(
+        env.fetch_config().pool_creation_mode,
+        PoolCreationMode::Allowlist
+    );
+
+    let result = env.send(
+        &[instructions::set_pool_creation_mode(
+            &roles.pool_admin.pubkey(),
+            PoolCreationMode::Permissionless,
+        )],
+        &[&roles.pool_admin],
+    );
+    assert_eq!(
+        error_code(&result.unwrap_err()),
+        Some(6000 + ErrorCode::NotPoolCreationAdmin as u32)
+    );
+}
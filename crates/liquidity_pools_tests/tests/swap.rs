@@ -0,0 +1,169 @@
+use anchor_lang::prelude::{Clock, Pubkey};
+use liquidity_pools::error::ErrorCode;
+use liquidity_pools_client::instructions::{self, SwapDirection};
+use liquidity_pools_client::quote::quote_swap;
+use liquidity_pools_tests::{error_code, TestEnv, TOKEN_2022_PROGRAM_ID, TOKEN_PROGRAM_ID};
+use solana_sdk::signature::Signer;
+
+const RESERVE_A: u64 = 92_495 * 1_000_000_000;
+const RESERVE_B: u64 = 33_053_283 * 1_000_000_000;
+
+#[test]
+fn random_user_can_swap_tokens() {
+    let mut env = TestEnv::new();
+    env.initialize(0);
+
+    for token_programs in [
+        (TOKEN_PROGRAM_ID, TOKEN_2022_PROGRAM_ID),
+        (TOKEN_2022_PROGRAM_ID, TOKEN_PROGRAM_ID),
+    ] {
+        let keys = env
+            .create_funded_pool(token_programs, 100, (RESERVE_A, RESERVE_B))
+            .keys;
+        let amount_in = 150 * 10u64.pow(9);
+        let user = env.fund_user(&keys, amount_in, 0);
+
+        let (reserve_a, reserve_b) = env.reserves(&keys);
+        let pool = env.fetch_pool(&keys.pool);
+        let quote = quote_swap(&pool, amount_in, reserve_a, reserve_b).unwrap();
+
+        env.swap(
+            &keys,
+            &user,
+            SwapDirection::AToB,
+            amount_in,
+            quote.amount_out,
+            None,
+        )
+        .expect("swap failed");
+
+        // user sent exactly amount_in and received exactly the quoted amount
+        assert_eq!(env.token_balance(&keys.user_ata_a(&user.pubkey())), 0);
+        assert_eq!(
+            env.token_balance(&keys.user_ata_b(&user.pubkey())),
+            quote.amount_out
+        );
+
+        let (reserve_a_after, reserve_b_after) = env.reserves(&keys);
+        assert_eq!(reserve_a_after, reserve_a + amount_in);
+        assert_eq!(reserve_b_after, reserve_b - quote.amount_out);
+        assert!(
+            reserve_a_after as u128 * reserve_b_after as u128
+                >= reserve_a as u128 * reserve_b as u128
+        );
+    }
+}
+
+#[test]
+fn user_can_swap_b_for_a() {
+    let mut env = TestEnv::new();
+    env.initialize(0);
+    let keys = env.create_test_pool(100, (RESERVE_A, RESERVE_B)).keys;
+    let amount_in = 10_000 * 10u64.pow(9);
+    let user = env.fund_user(&keys, 0, amount_in);
+
+    let (reserve_a, reserve_b) = env.reserves(&keys);
+    let pool = env.fetch_pool(&keys.pool);
+    let quote = quote_swap(&pool, amount_in, reserve_b, reserve_a).unwrap();
+
+    env.swap(
+        &keys,
+        &user,
+        SwapDirection::BToA,
+        amount_in,
+        quote.amount_out,
+        None,
+    )
+    .expect("swap failed");
+
+    assert_eq!(
+        env.token_balance(&keys.user_ata_a(&user.pubkey())),
+        quote.amount_out
+    );
+    assert_eq!(env.token_balance(&keys.user_ata_b(&user.pubkey())), 0);
+}
+
+#[test]
+fn fails_if_output_is_below_minimum() {
+    let mut env = TestEnv::new();
+    env.initialize(0);
+    let keys = env.create_test_pool(100, (RESERVE_A, RESERVE_B)).keys;
+    let amount_in = 150 * 10u64.pow(9);
+    let user = env.fund_user(&keys, amount_in, 0);
+
+    let (reserve_a, reserve_b) = env.reserves(&keys);
+    let pool = env.fetch_pool(&keys.pool);
+    let quote = quote_swap(&pool, amount_in, reserve_a, reserve_b).unwrap();
+
+    let result = env.swap(
+        &keys,
+        &user,
+        SwapDirection::AToB,
+        amount_in,
+        quote.amount_out + 1,
+        None,
+    );
+
+    assert_eq!(
+        error_code(&result.unwrap_err()),
+        Some(ErrorCode::InsufficientOutputAmount.into())
+    );
+}
+
+#[test]
+fn fails_if_the_deadline_has_passed() {
+    let mut env = TestEnv::new();
+    env.initialize(0);
+    let keys = env.create_test_pool(100, (RESERVE_A, RESERVE_B)).keys;
+    let user = env.fund_user(&keys, 1_000_000, 0);
+
+    let mut clock = env.svm.get_sysvar::<Clock>();
+    clock.unix_timestamp = 1_700_000_000;
+    env.svm.set_sysvar(&clock);
+
+    let result = env.swap(
+        &keys,
+        &user,
+        SwapDirection::AToB,
+        1_000_000,
+        1,
+        Some(1_699_999_999),
+    );
+
+    assert_eq!(
+        error_code(&result.unwrap_err()),
+        Some(ErrorCode::Expired.into())
+    );
+}
+
+#[test]
+fn input_can_come_from_any_token_account_of_the_payer() {
+    let mut env = TestEnv::new();
+    env.initialize(0);
+    let keys = env.create_test_pool(100, (RESERVE_A, RESERVE_B)).keys;
+    let amount_in = 150 * 10u64.pow(9);
+    // No associated token account for the input mint, the output one is created
+    let user = env.create_user();
+    let input_account = Pubkey::new_unique();
+    env.create_token_account(input_account, &keys.token_mint_a, &user.pubkey());
+    env.mint_to(&input_account, amount_in);
+
+    let mut instruction = instructions::swap(
+        &keys,
+        &user.pubkey(),
+        SwapDirection::AToB,
+        amount_in,
+        1,
+        None,
+    );
+    let payer_ata_input = keys.user_ata_a(&user.pubkey());
+    for meta in &mut instruction.accounts {
+        if meta.pubkey == payer_ata_input {
+            meta.pubkey = input_account;
+        }
+    }
+    env.send(&[instruction], &[&user]).expect("swap failed");
+
+    assert_eq!(env.token_balance(&input_account), 0);
+    assert!(env.token_balance(&keys.user_ata_b(&user.pubkey())) > 0);
+}
@@ -0,0 +1,207 @@
+use anchor_lang::prelude::{Clock, Pubkey};
+use liquidity_pools::error::ErrorCode;
+use liquidity_pools_client::instructions;
+use liquidity_pools_client::pda::find_config_change_address;
+use liquidity_pools_tests::{error_code, TestEnv};
+use solana_sdk::signature::{Keypair, Signer};
+
+const DELAY: i64 = 2 * 24 * 60 * 60;
+
+// Initializes the config with a 1% protocol fee and a two days timelock
+fn setup_timelock(env: &mut TestEnv) -> Keypair {
+    let admin = env.initialize(100);
+    // The first delay applies immediately, there is no delay yet
+    env.send(
+        &[
+            instructions::queue_config_change(
+                &admin.pubkey(),
+                None,
+                None,
+                Some(DELAY),
+                None,
+                None,
+                None,
+            ),
+            instructions::execute_config_change(&admin.pubkey()),
+        ],
+        &[&admin],
+    )
+    .expect("timelock setup failed");
+    assert_eq!(env.fetch_config().timelock_delay, DELAY);
+    admin
+}
+
+#[test]
+fn queued_change_applies_after_the_delay() {
+    let mut env = TestEnv::new();
+    let admin = setup_timelock(&mut env);
+    let recipient = Pubkey::new_unique();
+
+    env.send(
+        &[instructions::queue_config_change(
+            &admin.pubkey(),
+            Some(recipient),
+            Some(500),
+            None,
+            None,
+            None,
+            None,
+        )],
+        &[&admin],
+    )
+    .expect("queue_config_change failed");
+
+    let now = env.svm.get_sysvar::<Clock>().unix_timestamp;
+    let config_change = env.fetch_config_change();
+    assert_eq!(config_change.eta, now + DELAY);
+    assert_eq!(config_change.protocol_fee_bp, Some(500));
+
+    // Nothing changes before the eta
+    env.warp(DELAY - 1);
+    let result = env.send(
+        &[instructions::execute_config_change(&admin.pubkey())],
+        &[&admin],
+    );
+    assert_eq!(
+        error_code(&result.unwrap_err()),
+        Some(6000 + ErrorCode::TimelockNotExpired as u32)
+    );
+    assert_eq!(env.fetch_config().protocol_fee_bp, 100);
+
+    env.warp(1);
+    env.send(
+        &[instructions::execute_config_change(&admin.pubkey())],
+        &[&admin],
+    )
+    .expect("execute_config_change failed");
+
+    let config = env.fetch_config();
+    assert_eq!(config.protocol_fee_bp, 500);
+    assert_eq!(config.protocol_fee_recipient, recipient);
+    assert_eq!(config.timelock_delay, DELAY);
+    assert_eq!(env.lamports(&find_config_change_address().0), 0);
+}
+
+#[test]
+fn cancelled_change_is_discarded() {
+    let mut env = TestEnv::new();
+    let admin = setup_timelock(&mut env);
+
+    env.send(
+        &[instructions::queue_config_change(
+            &admin.pubkey(),
+            None,
+            Some(10_000),
+            None,
+            None,
+            None,
+            None,
+        )],
+        &[&admin],
+    )
+    .expect("queue_config_change failed");
+
+    // Only one change can be pending
+    let result = env.send(
+        &[instructions::queue_config_change(
+            &admin.pubkey(),
+            None,
+            Some(0),
+            None,
+            None,
+            None,
+            None,
+        )],
+        &[&admin],
+    );
+    assert!(result.is_err());
+
+    env.send(
+        &[instructions::cancel_config_change(&admin.pubkey())],
+        &[&admin],
+    )
+    .expect("cancel_config_change failed");
+
+    env.warp(DELAY);
+    let result = env.send(
+        &[instructions::execute_config_change(&admin.pubkey())],
+        &[&admin],
+    );
+    assert!(result.is_err());
+    assert_eq!(env.fetch_config().protocol_fee_bp, 100);
+
+    // A new change can be queued once the previous one is cancelled
+    env.send(
+        &[instructions::queue_config_change(
+            &admin.pubkey(),
+            None,
+            Some(0),
+            None,
+            None,
+            None,
+            None,
+        )],
+        &[&admin],
+    )
+    .expect("queue_config_change failed");
+}
+
+#[test]
+fn only_the_fee_manager_queues_changes() {
+    let mut env = TestEnv::new();
+    setup_timelock(&mut env);
+    let user = env.create_user();
+
+    let result = env.send(
+        &[instructions::queue_config_change(
+            &user.pubkey(),
+            Some(user.pubkey()),
+            None,
+            None,
+            None,
+            None,
+            None,
+        )],
+        &[&user],
+    );
+    assert_eq!(
+        error_code(&result.unwrap_err()),
+        Some(6000 + ErrorCode::NotFeeManager as u32)
+    );
+}
+
+#[test]
+fn negative_delay_is_rejected() {
+    let mut env = TestEnv::new();
+    let admin = setup_timelock(&mut env);
+
+    let result = env.send(
+        &[instructions::queue_config_change(
+            &admin.pubkey(),
+            None,
+            None,
+            Some(-1),
+            None,
+            None,
+            None,
+        )],
+        &[&admin],
+    );
+    assert_eq!(
+        error_code(&result.unwrap_err()),
+        Some(6000 + ErrorCode::InvalidTimelockDelay as u32)
+    );
+}
+
+#[test]
+fn pause_is_not_timelocked() {
+    let mut env = TestEnv::new();
+    let admin = setup_timelock(&mut env);
+
+    env.send(
+        &[instructions::set_paused(&admin.pubkey(), true)],
+        &[&admin],
+    )
+    .expect("set_paused failed");
+    assert!(env.fetch_config().paused);
+}
@@ -0,0 +1,180 @@
+use anchor_lang::error::ErrorCode as AnchorErrorCode;
+use liquidity_pools::error::ErrorCode;
+use liquidity_pools::math;
+use liquidity_pools_client::instructions::{self, SwapDirection};
+use liquidity_pools_client::quote::quote_swap;
+use liquidity_pools_tests::{error_code, FundedPool, TestEnv};
+use solana_sdk::signature::Signer;
+
+const RESERVE_A: u64 = 1_000_000 * 1_000_000_000;
+const RESERVE_B: u64 = 1_000_000 * 1_000_000_000;
+
+#[test]
+fn authority_can_set_pool_limits() {
+    let mut env = TestEnv::new();
+    let (FundedPool { keys, .. }, admin) = env.setup_pool(0, 30, (RESERVE_A, RESERVE_B));
+
+    env.update_pool(&keys, &admin, Some(500), Some(100))
+        .expect("update_pool failed");
+    let pool = env.fetch_pool(&keys.pool);
+    assert_eq!(pool.max_price_impact_bp, 500);
+    assert_eq!(pool.max_trade_size_bp, 100);
+
+    // None leaves a limit unchanged
+    env.update_pool(&keys, &admin, None, Some(0))
+        .expect("update_pool failed");
+    let pool = env.fetch_pool(&keys.pool);
+    assert_eq!(pool.max_price_impact_bp, 500);
+    assert_eq!(pool.max_trade_size_bp, 0);
+}
+
+#[test]
+fn random_user_cannot_set_pool_limits() {
+    let mut env = TestEnv::new();
+    let (FundedPool { keys, .. }, _) = env.setup_pool(0, 30, (RESERVE_A, RESERVE_B));
+    let user = env.create_user();
+
+    let result = env.update_pool(&keys, &user, Some(500), None);
+    assert_eq!(
+        error_code(&result.unwrap_err()),
+        Some(6000 + ErrorCode::NotPoolAdmin as u32)
+    );
+}
+
+#[test]
+fn limits_above_100_percent_are_rejected() {
+    let mut env = TestEnv::new();
+    let (FundedPool { keys, .. }, admin) = env.setup_pool(0, 30, (RESERVE_A, RESERVE_B));
+
+    let result = env.update_pool(&keys, &admin, Some(10_001), None);
+    assert_eq!(
+        error_code(&result.unwrap_err()),
+        Some(6000 + ErrorCode::InvalidTradeLimit as u32)
+    );
+}
+
+#[test]
+fn swap_above_max_trade_size_fails() {
+    let mut env = TestEnv::new();
+    let (FundedPool { keys, .. }, admin) = env.setup_pool(0, 30, (RESERVE_A, RESERVE_B));
+    // 1% of the input reserve
+    env.update_pool(&keys, &admin, None, Some(100))
+        .expect("update_pool failed");
+
+    let max_amount_in = RESERVE_A / 100;
+    let user = env.fund_user(&keys, max_amount_in + 1, 0);
+
+    let result = env.swap(
+        &keys,
+        &user,
+        SwapDirection::AToB,
+        max_amount_in + 1,
+        1,
+        None,
+    );
+    assert_eq!(
+        error_code(&result.unwrap_err()),
+        Some(6000 + ErrorCode::TradeTooLarge as u32)
+    );
+
+    env.swap(&keys, &user, SwapDirection::AToB, max_amount_in, 1, None)
+        .expect("swap at the limit failed");
+}
+
+#[test]
+fn swap_above_max_price_impact_fails() {
+    let mut env = TestEnv::new();
+    let (FundedPool { keys, .. }, admin) = env.setup_pool(0, 30, (RESERVE_A, RESERVE_B));
+    env.update_pool(&keys, &admin, Some(200), None)
+        .expect("update_pool failed");
+
+    // ~2% of the reserve moves the price by ~4%
+    let large_amount_in = RESERVE_A / 50;
+    let pool = env.fetch_pool(&keys.pool);
+    let large = math::get_amount_out(large_amount_in, RESERVE_A, RESERVE_B, pool.fee_bp).unwrap();
+    assert!(
+        math::price_impact_bp(large.amount_in, large.amount_out, RESERVE_A, RESERVE_B).unwrap()
+            > 200
+    );
+    // The client quote applies the pool limits as well
+    assert_eq!(
+        quote_swap(&pool, large_amount_in, RESERVE_A, RESERVE_B).unwrap_err(),
+        ErrorCode::PriceImpactTooHigh.into()
+    );
+
+    let user = env.fund_user(&keys, large_amount_in, 0);
+    let result = env.swap(&keys, &user, SwapDirection::AToB, large_amount_in, 1, None);
+    assert_eq!(
+        error_code(&result.unwrap_err()),
+        Some(6000 + ErrorCode::PriceImpactTooHigh as u32)
+    );
+
+    // ~0.5% of the reserve stays below 2%
+    env.swap(&keys, &user, SwapDirection::AToB, RESERVE_A / 200, 1, None)
+        .expect("small swap failed");
+}
+
+#[test]
+fn swap_rejects_a_vault_other_than_the_pool_vault() {
+    let mut env = TestEnv::new();
+    let (FundedPool { keys, .. }, admin) = env.setup_pool(0, 30, (RESERVE_A, RESERVE_B));
+    env.update_pool(&keys, &admin, Some(200), Some(100))
+        .expect("update_pool failed");
+
+    // A huge fake input reserve would hide the price impact and trade size
+    let user = env.fund_user(&keys, RESERVE_A, 0);
+    let fake_vault = env.create_ata(&keys.token_mint_a, &admin.pubkey());
+    env.mint_to(&fake_vault, 1_000 * RESERVE_A);
+    let mut instruction = instructions::swap(
+        &keys,
+        &user.pubkey(),
+        SwapDirection::AToB,
+        RESERVE_A / 10,
+        1,
+        None,
+    );
+    for account in &mut instruction.accounts {
+        if account.pubkey == keys.token_vault_a {
+            account.pubkey = fake_vault;
+        }
+    }
+
+    let result = env.send(&[instruction], &[&user]);
+    assert_eq!(
+        error_code(&result.unwrap_err()),
+        Some(AnchorErrorCode::ConstraintAddress as u32)
+    );
+}
+
+#[test]
+fn flash_swap_above_max_trade_size_fails() {
+    let mut env = TestEnv::new();
+    let (FundedPool { keys, .. }, admin) = env.setup_pool(0, 30, (RESERVE_A, RESERVE_B));
+    // 1% of the input reserve
+    env.update_pool(&keys, &admin, None, Some(100))
+        .expect("update_pool failed");
+    let user = env.fund_user(&keys, RESERVE_A, 0);
+
+    // Borrowing 10% of B and repaying in A keeps k but trades ~12% of the A reserve
+    let result = env.send(
+        &[
+            instructions::flash_loan(&keys, &user.pubkey(), 0, RESERVE_B / 10),
+            instructions::flash_repay(&keys, &user.pubkey(), RESERVE_A / 8, 0),
+        ],
+        &[&user],
+    );
+    assert_eq!(
+        error_code(&result.unwrap_err()),
+        Some(6000 + ErrorCode::TradeTooLarge as u32)
+    );
+
+    // Below the limit the flash swap goes through
+    env.send(
+        &[
+            instructions::flash_loan(&keys, &user.pubkey(), 0, RESERVE_B / 1_000),
+            instructions::flash_repay(&keys, &user.pubkey(), RESERVE_A / 500, 0),
+        ],
+        &[&user],
+    )
+    .expect("flash swap under the limit failed");
+}
@@ -0,0 +1,15 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use liquidity_pools_fuzz::{PoolModel, Scenario};
+
+fuzz_target!(|scenario: Scenario| {
+    let mut pool = PoolModel::new(scenario.fee_bp());
+
+    for action in &scenario.actions {
+        // Rejected actions leave the pool untouched
+        if let Ok(next) = pool.apply(action) {
+            pool = next;
+        }
+    }
+});
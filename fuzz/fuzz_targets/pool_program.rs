@@ -0,0 +1,89 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use liquidity_pools_client::instructions::{self, SwapDirection};
+use liquidity_pools_fuzz::{Action, PoolModel, Scenario, USERS};
+use liquidity_pools_tests::{error_code, TestEnv, TOKEN_2022_PROGRAM_ID, TOKEN_PROGRAM_ID};
+use solana_sdk::signature::{Keypair, Signer};
+
+// Balance of each token given to every user
+const FUNDING: u64 = u64::MAX / 16;
+
+fuzz_target!(|scenario: Scenario| {
+    // Runs the SBF build of the program when there is one, natively otherwise
+    // (see `TestEnv::program_path`)
+    let mut env = TestEnv::new();
+    // No protocol fee, the model only tracks the LP side of the fee
+    env.initialize(0);
+
+    let token_program = |token_2022: bool| {
+        if token_2022 {
+            TOKEN_2022_PROGRAM_ID
+        } else {
+            TOKEN_PROGRAM_ID
+        }
+    };
+
+    let creator = env.create_user();
+    let keys = env.create_pool(
+        &creator,
+        scenario.decimals(),
+        (
+            token_program(scenario.token_2022_a),
+            token_program(scenario.token_2022_b),
+        ),
+        scenario.fee_bp(),
+    );
+    let users: Vec<Keypair> = (0..USERS)
+        .map(|_| env.fund_user(&keys, FUNDING, FUNDING))
+        .collect();
+
+    let mut model = PoolModel::new(scenario.fee_bp());
+
+    for action in &scenario.actions {
+        let user = &users[action.user()];
+        let instruction = match *action {
+            Action::AddLiquidity {
+                amount_a, amount_b, ..
+            } => instructions::add_liquidity(&keys, &user.pubkey(), amount_a, amount_b, 0, 0, None, &[]),
+            Action::RemoveLiquidity { lp_amount, .. } => {
+                instructions::remove_liquidity(&keys, &user.pubkey(), lp_amount, 1, 1, None, &[], false)
+            }
+            Action::Swap {
+                a_to_b, amount_in, ..
+            } => {
+                let direction = if a_to_b {
+                    SwapDirection::AToB
+                } else {
+                    SwapDirection::BToA
+                };
+                instructions::swap(&keys, &user.pubkey(), direction, amount_in, 1, None)
+            }
+        };
+
+        match env.send(&[instruction], &[user]) {
+            Ok(_) => {
+                // Whatever the program accepts must match the model exactly
+                let next = model
+                    .apply(action)
+                    .expect("program accepted an action the model rejects");
+                let (reserve_a, reserve_b) = env.reserves(&keys);
+                assert_eq!(
+                    (reserve_a, reserve_b, env.mint_supply(&keys.lp_mint)),
+                    (next.reserve_a, next.reserve_b, next.lp_supply),
+                    "program diverged from the model after {action:?}"
+                );
+                model = next;
+            }
+            Err(failed) => {
+                // Rejections must be program or token errors, never panics
+                assert!(
+                    error_code(&failed).is_some(),
+                    "{action:?} failed with {:?}\n{:#?}",
+                    failed.err,
+                    failed.meta.logs
+                );
+            }
+        }
+    }
+});
@@ -0,0 +1,226 @@
+//! Stateful fuzzing of the liquidity_pools program
+//!
+//! A `Scenario` is a pool configuration plus a random sequence of liquidity
+//! and swap actions. `PoolModel` replays it with the shared pool math and
+//! checks the pool invariants after every step:
+//! - k never decreases on swaps
+//! - LP supply stays backed by reserves (lp_supply^2 <= reserve_a * reserve_b)
+//! - round trips (swap and swap back, deposit and withdraw) never return more than was put in
+//!
+//! `pool_math` fuzzes the model alone, `pool_program` runs the same scenario
+//! through the compiled program in LiteSVM and compares it with the model.
+
+use arbitrary::Arbitrary;
+use liquidity_pools::math::{self, MathError};
+
+// Number of distinct users acting on the pool
+pub const USERS: usize = 4;
+
+#[derive(Arbitrary, Clone, Copy, Debug)]
+pub enum Action {
+    AddLiquidity {
+        user: u8,
+        amount_a: u64,
+        amount_b: u64,
+    },
+    RemoveLiquidity {
+        user: u8,
+        lp_amount: u64,
+    },
+    Swap {
+        user: u8,
+        a_to_b: bool,
+        amount_in: u64,
+    },
+}
+
+impl Action {
+    pub fn user(&self) -> usize {
+        match self {
+            Action::AddLiquidity { user, .. }
+            | Action::RemoveLiquidity { user, .. }
+            | Action::Swap { user, .. } => *user as usize % USERS,
+        }
+    }
+}
+
+#[derive(Arbitrary, Clone, Debug)]
+pub struct Scenario {
+    fee_bp: u16,
+    decimals_a: u8,
+    decimals_b: u8,
+    pub token_2022_a: bool,
+    pub token_2022_b: bool,
+    pub actions: Vec<Action>,
+}
+
+impl Scenario {
+    // Fees above 10% only exercise the same paths
+    pub fn fee_bp(&self) -> u16 {
+        self.fee_bp % 1001
+    }
+
+    pub fn decimals(&self) -> (u8, u8) {
+        (self.decimals_a % 19, self.decimals_b % 19)
+    }
+}
+
+/// Why the model rejected an action, mirrors the handler checks
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Rejected {
+    ZeroAmount,
+    EmptyPool,
+    Math(MathError),
+}
+
+impl From<MathError> for Rejected {
+    fn from(error: MathError) -> Self {
+        Rejected::Math(error)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PoolModel {
+    pub reserve_a: u64,
+    pub reserve_b: u64,
+    pub lp_supply: u64,
+    pub fee_bp: u16,
+}
+
+impl PoolModel {
+    pub fn new(fee_bp: u16) -> Self {
+        Self {
+            fee_bp,
+            ..Default::default()
+        }
+    }
+
+    /// Applies `action` like the on-chain handler would, returns the next state
+    pub fn apply(&self, action: &Action) -> Result<PoolModel, Rejected> {
+        let mut next = *self;
+
+        match *action {
+            Action::AddLiquidity {
+                amount_a, amount_b, ..
+            } => {
+                if amount_a == 0 || amount_b == 0 {
+                    return Err(Rejected::ZeroAmount);
+                }
+
+                let (amount_a, amount_b) =
+                    math::quote_deposit(amount_a, amount_b, self.reserve_a, self.reserve_b)?;
+                let lp_amount = math::lp_to_mint(
+                    amount_a,
+                    amount_b,
+                    self.reserve_a,
+                    self.reserve_b,
+                    self.lp_supply,
+                )?;
+
+                next.reserve_a = add(self.reserve_a, amount_a)?;
+                next.reserve_b = add(self.reserve_b, amount_b)?;
+                next.lp_supply = add(self.lp_supply, lp_amount)?;
+
+                // Withdrawing the freshly minted LP never returns more than was deposited
+                if next.lp_supply > 0 {
+                    let (out_a, out_b) = math::lp_to_burn(
+                        lp_amount,
+                        next.reserve_a,
+                        next.reserve_b,
+                        next.lp_supply,
+                    )?;
+                    assert!(
+                        out_a <= amount_a && out_b <= amount_b,
+                        "deposit round trip profit"
+                    );
+                }
+            }
+            Action::RemoveLiquidity { lp_amount, .. } => {
+                if lp_amount == 0 {
+                    return Err(Rejected::ZeroAmount);
+                }
+                if self.reserve_a == 0 || self.reserve_b == 0 {
+                    return Err(Rejected::EmptyPool);
+                }
+
+                let (amount_a, amount_b) =
+                    math::lp_to_burn(lp_amount, self.reserve_a, self.reserve_b, self.lp_supply)?;
+                // The handler is always sent minimums of 1
+                if amount_a == 0 || amount_b == 0 {
+                    return Err(Rejected::ZeroAmount);
+                }
+
+                next.reserve_a -= amount_a;
+                next.reserve_b -= amount_b;
+                next.lp_supply -= lp_amount;
+            }
+            Action::Swap {
+                a_to_b, amount_in, ..
+            } => {
+                if amount_in == 0 {
+                    return Err(Rejected::ZeroAmount);
+                }
+
+                let (reserve_in, reserve_out) = self.oriented(a_to_b);
+                if reserve_in == 0 || reserve_out == 0 {
+                    return Err(Rejected::EmptyPool);
+                }
+
+                let swap = math::get_amount_out(amount_in, reserve_in, reserve_out, self.fee_bp)?;
+                // The handler is always sent a minimum of 1
+                if swap.amount_out == 0 {
+                    return Err(Rejected::ZeroAmount);
+                }
+                let reserve_in_after = add(reserve_in, amount_in)?;
+                let reserve_out_after = reserve_out - swap.amount_out;
+
+                assert!(
+                    reserve_in_after as u128 * reserve_out_after as u128
+                        >= reserve_in as u128 * reserve_out as u128,
+                    "k decreased"
+                );
+
+                // Swapping the output straight back never returns more than was swapped in
+                if let Ok(back) = math::get_amount_out(
+                    swap.amount_out,
+                    reserve_out_after,
+                    reserve_in_after,
+                    self.fee_bp,
+                ) {
+                    assert!(back.amount_out <= amount_in, "swap round trip profit");
+                }
+
+                if a_to_b {
+                    next.reserve_a = reserve_in_after;
+                    next.reserve_b = reserve_out_after;
+                } else {
+                    next.reserve_b = reserve_in_after;
+                    next.reserve_a = reserve_out_after;
+                }
+            }
+        }
+
+        next.check_invariants();
+        Ok(next)
+    }
+
+    /// LP supply is always backed by the reserves
+    pub fn check_invariants(&self) {
+        assert!(
+            (self.lp_supply as u128).pow(2) <= self.reserve_a as u128 * self.reserve_b as u128,
+            "LP supply exceeds reserves: {self:?}"
+        );
+    }
+
+    fn oriented(&self, a_to_b: bool) -> (u64, u64) {
+        if a_to_b {
+            (self.reserve_a, self.reserve_b)
+        } else {
+            (self.reserve_b, self.reserve_a)
+        }
+    }
+}
+
+fn add(a: u64, b: u64) -> Result<u64, Rejected> {
+    a.checked_add(b).ok_or(Rejected::Math(MathError::Overflow))
+}
@@ -1,3 +1,4 @@
+use crate::math::MathError;
 use anchor_lang::prelude::*;
 
 #[error_code]
@@ -28,4 +29,87 @@ pub enum ErrorCode {
     InsufficientOutputAmount = 12,
     #[msg("Pool has no liquidity")]
     EmptyPool = 13,
+    #[msg("Transaction expired - deadline has passed")]
+    Expired = 14,
+    #[msg("Flash loan in progress - pool is locked until it is repaid")]
+    FlashLoanActive = 15,
+    #[msg("No flash loan in progress for this pool")]
+    FlashLoanNotActive = 16,
+    #[msg("Flash loan must be repaid by a flash_repay instruction later in the same transaction")]
+    FlashLoanNotRepaid = 17,
+    #[msg("Flash loans can not be taken through CPI")]
+    FlashLoanCpiNotAllowed = 18,
+    #[msg("Constant product invariant violated - k decreased")]
+    InvariantViolated = 19,
+    #[msg("Price impact exceeds the pool maximum")]
+    PriceImpactTooHigh = 20,
+    #[msg("Trade size exceeds the pool maximum")]
+    TradeTooLarge = 21,
+    #[msg("Invalid trade limit - must be at most 10000 basis points")]
+    InvalidTradeLimit = 22,
+    #[msg("Pool still holds liquidity - LP supply and vaults must be empty")]
+    PoolNotEmpty = 23,
+    #[msg("Referral fee exceeds the maximum share of the swap fee")]
+    ReferralFeeTooHigh = 24,
+    #[msg("Invalid referrer - token account must hold the input token")]
+    InvalidReferrer = 25,
+    #[msg("Invalid protocol fee - must be at most 10000 basis points")]
+    InvalidProtocolFee = 26,
+    #[msg("Invalid fee recipient - token account must be owned by the protocol fee recipient")]
+    InvalidFeeRecipient = 27,
+    #[msg("Signer is not the fee manager")]
+    NotFeeManager = 28,
+    #[msg("Signer is not the pause guardian")]
+    NotPauseGuardian = 29,
+    #[msg("Signer is not the pool admin")]
+    NotPoolAdmin = 30,
+    #[msg("Swaps are paused")]
+    Paused = 31,
+    #[msg("Config change can not be executed before its eta")]
+    TimelockNotExpired = 32,
+    #[msg("Invalid timelock delay - must not be negative")]
+    InvalidTimelockDelay = 33,
+    #[msg("Invalid pool fee - must be within the config fee bounds")]
+    InvalidPoolFee = 34,
+    #[msg(
+        "Invalid pool fee bounds - min must not exceed max, max must be at most 10000 basis points"
+    )]
+    InvalidPoolFeeBounds = 35,
+    #[msg("Account is already at the current version")]
+    AlreadyMigrated = 36,
+    #[msg("Pair index is full - no more pools can be registered for this mint pair")]
+    PairIndexFull = 37,
+    #[msg("Signer is not allowed to create pools in the current pool creation mode")]
+    PoolCreationNotAllowed = 38,
+    #[msg("Accounts paying the pool creation fee are missing")]
+    MissingCreationFeeAccounts = 39,
+    #[msg("Signer is not the rewards admin")]
+    NotRewardsAdmin = 40,
+    #[msg("Lock position can not be withdrawn before it unlocks")]
+    LockNotExpired = 41,
+    #[msg("Invalid early exit penalty - must be at most 10000 basis points")]
+    InvalidEarlyExitPenalty = 42,
+    #[msg("Invalid fee recipients - up to 4 distinct recipients with weights adding up to 10000 basis points")]
+    InvalidFeeRecipients = 43,
+    #[msg("Invalid creator fee - must be at most the config max creator fee")]
+    InvalidCreatorFee = 44,
+    #[msg("Native SOL needs a pool with the native mint on one side")]
+    NotNativeMintPool = 45,
+    #[msg("Native SOL output is unwrapped to the payer, the recipient must be the payer")]
+    NativeSolRecipient = 46,
+    #[msg("Signer is not the pool creation admin")]
+    NotPoolCreationAdmin = 47,
+    #[msg("Native SOL sides need their temporary wrapped SOL account, the other sides a token account")]
+    NativeSolAccounts = 48,
+}
+
+impl From<MathError> for ErrorCode {
+    fn from(error: MathError) -> Self {
+        match error {
+            MathError::Overflow => ErrorCode::Overflow,
+            MathError::Underflow => ErrorCode::Underflow,
+            MathError::DivisionByZero | MathError::EmptyPool => ErrorCode::EmptyPool,
+            MathError::InsufficientLiquidity => ErrorCode::InsufficientLiquidity,
+        }
+    }
 }
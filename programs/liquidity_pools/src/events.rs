@@ -0,0 +1,22 @@
+use anchor_lang::prelude::*;
+
+#[event]
+#[derive(Debug)]
+pub struct SwapEvent {
+    pub pool: Pubkey,
+    pub payer: Pubkey,
+    // Owner of the output token account
+    pub recipient: Pubkey,
+    pub token_mint_input: Pubkey,
+    pub amount_in: u64,
+    pub amount_out: u64,
+    // Total swap fee, referral fee included
+    pub fee_amount: u64,
+    // Share of the fee accrued to the protocol
+    pub protocol_fee: u64,
+    // Share of the fee accrued to the pool creator
+    pub creator_fee: u64,
+    // Referrer token account paid in the input token, if any
+    pub referrer: Option<Pubkey>,
+    pub referral_fee: u64,
+}
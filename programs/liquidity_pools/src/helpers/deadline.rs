@@ -0,0 +1,13 @@
+use crate::error::ErrorCode;
+use anchor_lang::prelude::*;
+
+// Fails with `Expired` once the cluster clock has passed the deadline (unix timestamp)
+// No deadline means the instruction never expires
+pub fn check_deadline(deadline: Option<i64>) -> Result<()> {
+    if let Some(deadline) = deadline {
+        let now = Clock::get()?.unix_timestamp;
+        require!(now <= deadline, ErrorCode::Expired);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,33 @@
+use crate::error::ErrorCode;
+use crate::math::{self, FEE_DENOMINATOR};
+use crate::state::Pool;
+use anchor_lang::prelude::*;
+
+// Enforces the pool swap limits on a trade of `amount_in` for `amount_out`
+// Every instruction that swaps against the pool reserves must call it
+pub fn check_trade_limits(
+    pool: &Pool,
+    amount_in: u64,
+    amount_out: u64,
+    reserve_in: u64,
+    reserve_out: u64,
+) -> Result<()> {
+    if pool.max_trade_size_bp > 0 {
+        require!(
+            amount_in as u128 * FEE_DENOMINATOR
+                <= reserve_in as u128 * pool.max_trade_size_bp as u128,
+            ErrorCode::TradeTooLarge
+        );
+    }
+
+    if pool.max_price_impact_bp > 0 {
+        let price_impact_bp = math::price_impact_bp(amount_in, amount_out, reserve_in, reserve_out)
+            .map_err(ErrorCode::from)?;
+        require!(
+            price_impact_bp <= pool.max_price_impact_bp,
+            ErrorCode::PriceImpactTooHigh
+        );
+    }
+
+    Ok(())
+}
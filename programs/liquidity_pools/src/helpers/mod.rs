@@ -1,2 +1,8 @@
+pub mod deadline;
 pub mod fees;
+pub mod limits;
+pub mod migration;
+pub mod native_sol;
+pub mod protocol_fee;
+pub mod registry;
 pub mod transfer;
@@ -0,0 +1,83 @@
+use crate::error::ErrorCode;
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_spl::token::spl_token;
+use anchor_spl::token_2022::{self, spl_token_2022};
+use anchor_spl::token_interface::{TokenAccount, TokenInterface};
+
+// Seed of the temporary wrapped SOL account of an owner, [WSOL_SEED, owner, native
+// mint]. Native SOL moves through it so the owner's own wrapped SOL accounts are
+// never touched, it is created and closed within the same instruction
+pub const WSOL_SEED: &[u8] = b"wsol";
+
+// Wrapped SOL of the Token program or of Token-2022
+pub fn is_native_mint(mint: &Pubkey) -> bool {
+    *mint == spl_token::native_mint::ID || *mint == spl_token_2022::native_mint::ID
+}
+
+// Moves `amount` lamports of `owner` into its temporary wrapped SOL account and
+// syncs the token balance
+pub fn wrap_sol<'info>(
+    owner: &Signer<'info>,
+    wsol_account: &InterfaceAccount<'info, TokenAccount>,
+    token_program: &Interface<'info, TokenInterface>,
+    system_program: &Program<'info, System>,
+    amount: u64,
+) -> Result<()> {
+    system_program::transfer(
+        CpiContext::new(
+            system_program.to_account_info(),
+            system_program::Transfer {
+                from: owner.to_account_info(),
+                to: wsol_account.to_account_info(),
+            },
+        ),
+        amount,
+    )?;
+
+    token_2022::sync_native(CpiContext::new(
+        token_program.to_account_info(),
+        token_2022::SyncNative {
+            account: wsol_account.to_account_info(),
+        },
+    ))
+}
+
+// Closes the temporary wrapped SOL account of `owner`, its whole balance and rent
+// go back as lamports
+pub fn unwrap_sol<'info>(
+    owner: &Signer<'info>,
+    wsol_account: &InterfaceAccount<'info, TokenAccount>,
+    token_program: &Interface<'info, TokenInterface>,
+) -> Result<()> {
+    token_2022::close_account(CpiContext::new(
+        token_program.to_account_info(),
+        token_2022::CloseAccount {
+            account: wsol_account.to_account_info(),
+            destination: owner.to_account_info(),
+            authority: owner.to_account_info(),
+        },
+    ))
+}
+
+// Token account one side of the instruction moves through, the temporary wrapped
+// SOL account when the side is paid in native SOL and the user's own otherwise.
+// A temporary account passed for another side would be left open and block the
+// next native SOL instruction of the owner
+pub fn side_token_account<'a, 'info>(
+    native: bool,
+    wsol_account: &'a mut Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    token_account: &'a mut Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+) -> Result<&'a mut InterfaceAccount<'info, TokenAccount>> {
+    require!(
+        wsol_account.is_some() == native,
+        ErrorCode::NativeSolAccounts
+    );
+
+    let account = if native {
+        wsol_account.as_deref_mut()
+    } else {
+        token_account.as_deref_mut()
+    };
+    Ok(account.ok_or(ErrorCode::NativeSolAccounts)?)
+}
@@ -0,0 +1,121 @@
+use crate::error::ErrorCode;
+use crate::helpers::transfer::mint_lp_tokens;
+use crate::math;
+use crate::state::{Config, Pool};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+// Mints the protocol share of the fees earned since Pool::k_last when the protocol
+// fee is collected as LP tokens, split across Config::fee_recipients like claim_fees.
+// `recipient_atas_lp` are the LP token accounts of the fee recipients, in order, they
+// are required whenever a fee is pending. Liquidity changes call it before pricing LP
+// tokens and use the returned LP supply, which includes the minted fee
+pub fn mint_protocol_lp_fee<'info>(
+    config: &Config,
+    pool: &mut Account<'info, Pool>,
+    lp_mint: &mut InterfaceAccount<'info, Mint>,
+    recipient_atas_lp: &'info [AccountInfo<'info>],
+    token_program_lp: &mut Interface<'info, TokenInterface>,
+    reserve_a: u64,
+    reserve_b: u64,
+) -> Result<u64> {
+    let protocol_lp_fee =
+        pending_protocol_lp_fee(config, pool, lp_mint.supply, reserve_a, reserve_b)?;
+    let lp_supply = lp_mint
+        .supply
+        .checked_add(protocol_lp_fee)
+        .ok_or(ErrorCode::Overflow)?;
+    if protocol_lp_fee == 0 {
+        return Ok(lp_supply);
+    }
+
+    msg!("protocol_lp_fee: {}", protocol_lp_fee);
+
+    let recipients = config.active_fee_recipients();
+    require!(
+        recipient_atas_lp.len() == recipients.len(),
+        ErrorCode::InvalidFeeRecipient
+    );
+
+    let lp_mint_key = lp_mint.key();
+    let mut remaining = protocol_lp_fee;
+    for (i, (entry, info)) in recipients.iter().zip(recipient_atas_lp).enumerate() {
+        let mut recipient_ata_lp = fee_recipient_account(info, &lp_mint_key, &entry.recipient)?;
+
+        // The last recipient takes the rounding dust
+        let amount = if i + 1 == recipients.len() {
+            remaining
+        } else {
+            weighted_share(protocol_lp_fee, entry.weight_bp)?
+        };
+        remaining -= amount;
+
+        if amount > 0 {
+            mint_lp_tokens(
+                lp_mint,
+                &mut recipient_ata_lp,
+                pool,
+                amount,
+                token_program_lp,
+            )?;
+        }
+    }
+
+    Ok(lp_supply)
+}
+
+// LP tokens the next liquidity change mints to the fee recipients, the protocol share
+// of the growth since Pool::k_last
+pub fn pending_protocol_lp_fee(
+    config: &Config,
+    pool: &Pool,
+    lp_supply: u64,
+    reserve_a: u64,
+    reserve_b: u64,
+) -> Result<u64> {
+    if !pool.mints_protocol_fee(config) {
+        return Ok(0);
+    }
+
+    Ok(math::protocol_lp_fee(
+        reserve_a,
+        reserve_b,
+        pool.k_last,
+        lp_supply,
+        pool.protocol_fee_bp(config),
+    )
+    .map_err(ErrorCode::from)?)
+}
+
+// Token account of `owner` for `mint` passed as a remaining account
+pub fn fee_recipient_account<'info>(
+    info: &'info AccountInfo<'info>,
+    mint: &Pubkey,
+    owner: &Pubkey,
+) -> Result<InterfaceAccount<'info, TokenAccount>> {
+    let account = InterfaceAccount::<TokenAccount>::try_from(info)?;
+    require!(
+        info.is_writable && account.mint == *mint && account.owner == *owner,
+        ErrorCode::InvalidFeeRecipient
+    );
+
+    Ok(account)
+}
+
+// Share of `amount` for a fee recipient of weight `weight_bp`, rounded down
+pub fn weighted_share(amount: u64, weight_bp: u16) -> Result<u64> {
+    Ok(math::split_fee(amount, weight_bp)
+        .map_err(ErrorCode::from)?
+        .share)
+}
+
+// Records k once a liquidity change is done, after mint_protocol_lp_fee took the growth
+// since the previous k. It stays 0 while swaps skim the protocol fee, so the growth
+// earned in that mode is never minted again as LP tokens
+pub fn update_k_last(config: &Config, pool: &mut Pool, reserve_a: u64, reserve_b: u64) {
+    pool.k_last = if config.mints_protocol_fee() {
+        reserve_a as u128 * reserve_b as u128
+    } else {
+        0
+    };
+}
@@ -0,0 +1,42 @@
+use crate::error::ErrorCode;
+use crate::state::{Config, PairIndex, PairIndexHeader, Pool, PoolRegistry};
+use anchor_lang::prelude::*;
+
+// Appends `pool` to the current registry page and to the next page of its pair
+// index. Registered pools are left as they are
+pub fn add_to_registry(
+    config: &mut Config,
+    registry: &mut PoolRegistry,
+    pair_index_header: &mut PairIndexHeader,
+    pair_index: &mut PairIndex,
+    pool: &mut Pool,
+    pool_key: Pubkey,
+) -> Result<()> {
+    if pool.registered {
+        return Ok(());
+    }
+    require!(
+        pair_index.pools.len() < PairIndex::MAX_POOLS,
+        ErrorCode::PairIndexFull
+    );
+
+    pair_index.pools.push(pool_key);
+    registry.pools.push(pool_key);
+    pool.registered = true;
+    pool.pair_index_page = pair_index.page;
+    pair_index_header.pool_count = pair_index_header
+        .pool_count
+        .checked_add(1)
+        .ok_or(ErrorCode::Overflow)?;
+    config.pool_count = config
+        .pool_count
+        .checked_add(1)
+        .ok_or(ErrorCode::Overflow)?;
+
+    Ok(())
+}
+
+// Drops `pool` from its pair index, the registry keeps listing it
+pub fn remove_from_pair_index(pair_index: &mut PairIndex, pool: &Pubkey) {
+    pair_index.pools.retain(|listed| listed != pool);
+}
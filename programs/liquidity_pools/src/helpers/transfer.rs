@@ -1,4 +1,5 @@
 use anchor_lang::prelude::*;
+use anchor_lang::system_program;
 use anchor_spl::token_2022;
 use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 
@@ -26,7 +27,27 @@ pub fn transfer_token_to_pool<'info>(
         token_mint.decimals,
     )?;
 
-    return Ok(());
+    Ok(())
+}
+
+pub fn transfer_lamports<'info>(
+    from: &Signer<'info>,
+    to: &AccountInfo<'info>,
+    system_program: &Program<'info, System>,
+    amount: u64,
+) -> Result<()> {
+    system_program::transfer(
+        CpiContext::new(
+            system_program.to_account_info(),
+            system_program::Transfer {
+                from: from.to_account_info(),
+                to: to.clone(),
+            },
+        ),
+        amount,
+    )?;
+
+    Ok(())
 }
 
 pub fn transfer_token_from_pool<'info>(
@@ -50,7 +71,7 @@ pub fn transfer_token_from_pool<'info>(
                 Pool::SEED,
                 pool.token_mint_a.key().as_ref(),
                 pool.token_mint_b.key().as_ref(),
-                pool.fee_bp.to_le_bytes().as_ref(),
+                pool.index.to_le_bytes().as_ref(),
                 &[pool.bump],
             ]],
         ),
@@ -80,7 +101,7 @@ pub fn mint_lp_tokens<'info>(
                 Pool::SEED,
                 pool.token_mint_a.key().as_ref(),
                 pool.token_mint_b.key().as_ref(),
-                pool.fee_bp.to_le_bytes().as_ref(),
+                pool.index.to_le_bytes().as_ref(),
                 &[pool.bump],
             ]],
         ),
@@ -88,5 +109,51 @@ pub fn mint_lp_tokens<'info>(
         lp_mint.decimals,
     )?;
 
-    return Ok(());
+    Ok(())
+}
+
+pub fn burn_lp_tokens<'info>(
+    provider: &mut Signer<'info>,
+    lp_mint: &mut InterfaceAccount<'info, Mint>,
+    provider_ata_lp: &mut InterfaceAccount<'info, TokenAccount>,
+    amount: u64,
+    token_program: &mut Interface<'info, TokenInterface>,
+) -> Result<()> {
+    token_2022::burn_checked(
+        CpiContext::new(
+            token_program.to_account_info(),
+            token_2022::BurnChecked {
+                mint: lp_mint.to_account_info(),
+                from: provider_ata_lp.to_account_info(),
+                authority: provider.to_account_info(),
+            },
+        ),
+        amount,
+        lp_mint.decimals,
+    )?;
+
+    Ok(())
+}
+
+pub fn close_pool_token_account<'info>(
+    pool: &Account<'info, Pool>,
+    pool_ata: &InterfaceAccount<'info, TokenAccount>,
+    destination: &AccountInfo<'info>,
+    token_program: &Interface<'info, TokenInterface>,
+) -> Result<()> {
+    token_2022::close_account(CpiContext::new_with_signer(
+        token_program.to_account_info(),
+        token_2022::CloseAccount {
+            account: pool_ata.to_account_info(),
+            destination: destination.clone(),
+            authority: pool.to_account_info(),
+        },
+        &[&[
+            Pool::SEED,
+            pool.token_mint_a.key().as_ref(),
+            pool.token_mint_b.key().as_ref(),
+            pool.index.to_le_bytes().as_ref(),
+            &[pool.bump],
+        ]],
+    ))
 }
@@ -1,15 +1,19 @@
 use crate::error::ErrorCode as CustomErrorCode;
+use crate::helpers::deadline::check_deadline;
+use crate::helpers::native_sol::{
+    is_native_mint, side_token_account, unwrap_sol, wrap_sol, WSOL_SEED,
+};
+use crate::helpers::protocol_fee::{mint_protocol_lp_fee, update_k_last};
 use crate::helpers::transfer::{mint_lp_tokens, transfer_token_to_pool};
-use crate::state::Pool;
+use crate::math;
+use crate::state::{Config, Pool};
 use anchor_lang::prelude::*;
 use anchor_spl::associated_token::AssociatedToken;
 use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
-use spl_math::approximations::sqrt;
-use spl_math::precise_number::PreciseNumber;
 
 #[derive(Accounts)]
 pub struct AddLiquidity<'info> {
-    // Provider accounts
+    // Provider accounts, the token account of a side paid in native SOL is left out
     #[account(mut)]
     pub provider: Signer<'info>,
     #[account(
@@ -18,22 +22,49 @@ pub struct AddLiquidity<'info> {
         associated_token::authority = provider,
         associated_token::token_program = token_program_a,
     )]
-    pub provider_ata_a: InterfaceAccount<'info, TokenAccount>,
+    pub provider_ata_a: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
     #[account(
         mut,
         associated_token::mint = token_mint_b,
         associated_token::authority = provider,
         associated_token::token_program = token_program_b,
     )]
-    pub provider_ata_b: InterfaceAccount<'info, TokenAccount>,
+    pub provider_ata_b: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    // Temporary wrapped SOL accounts of the provider for the sides paid in native
+    // SOL, closed again before the instruction returns
+    #[account(
+        init,
+        payer = provider,
+        seeds = [WSOL_SEED, provider.key().as_ref(), token_mint_a.key().as_ref()],
+        bump,
+        token::mint = token_mint_a,
+        token::authority = provider,
+        token::token_program = token_program_a,
+    )]
+    pub wsol_a: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    #[account(
+        init,
+        payer = provider,
+        seeds = [WSOL_SEED, provider.key().as_ref(), token_mint_b.key().as_ref()],
+        bump,
+        token::mint = token_mint_b,
+        token::authority = provider,
+        token::token_program = token_program_b,
+    )]
+    pub wsol_b: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    /// CHECK: Any owner, the LP tokens go to its associated token account
+    pub lp_recipient: UncheckedAccount<'info>,
+    // Created by the provider when the recipient does not hold LP tokens yet
     #[account(
         init_if_needed,
         payer = provider,
         associated_token::mint = lp_mint,
-        associated_token::authority = provider,
+        associated_token::authority = lp_recipient,
         associated_token::token_program = token_program_lp,
     )]
-    pub provider_ata_lp: InterfaceAccount<'info, TokenAccount>,
+    pub lp_recipient_ata: InterfaceAccount<'info, TokenAccount>,
 
     // Pool accounts
     #[account(
@@ -42,9 +73,10 @@ pub struct AddLiquidity<'info> {
             Pool::SEED,
             pool.token_mint_a.key().as_ref(),
             pool.token_mint_b.key().as_ref(),
-            pool.fee_bp.to_le_bytes().as_ref(),
+            pool.index.to_le_bytes().as_ref(),
         ],
         bump = pool.bump,
+        constraint = !pool.flash_loan_active @ CustomErrorCode::FlashLoanActive,
     )]
     pub pool: Box<Account<'info, Pool>>,
 
@@ -77,7 +109,7 @@ pub struct AddLiquidity<'info> {
             Pool::MINT_SEED,
             pool.token_mint_a.key().as_ref(),
             pool.token_mint_b.key().as_ref(),
-            pool.fee_bp.to_le_bytes().as_ref(),
+            pool.index.to_le_bytes().as_ref(),
         ],
         bump,
     )]
@@ -90,19 +122,28 @@ pub struct AddLiquidity<'info> {
     pub token_program_lp: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
     pub associated_token_program: Program<'info, AssociatedToken>,
+
+    // Protocol fee mode and fee split. When the protocol fee is minted as LP
+    // tokens the LP token accounts of the fee recipients follow as remaining
+    // accounts, in fee split order
+    #[account(
+        seeds = [Config::SEED],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
 }
 
-pub fn add_liquidity(
-    ctx: Context<AddLiquidity>,
+pub fn add_liquidity<'info>(
+    ctx: Context<'_, '_, 'info, 'info, AddLiquidity<'info>>,
     amount_a_desired: u64,
     amount_b_desired: u64,
     amount_a_min: u64,
     amount_b_min: u64,
+    deadline: Option<i64>,
+    native_sol: bool,
 ) -> Result<()> {
     let provider = &mut ctx.accounts.provider;
-    let provider_ata_a = &mut ctx.accounts.provider_ata_a;
-    let provider_ata_b = &mut ctx.accounts.provider_ata_b;
-    let provider_ata_lp = &mut ctx.accounts.provider_ata_lp;
+    let lp_recipient_ata = &mut ctx.accounts.lp_recipient_ata;
 
     let token_mint_a = &mut ctx.accounts.token_mint_a;
     let token_mint_b = &mut ctx.accounts.token_mint_b;
@@ -115,183 +156,117 @@ pub fn add_liquidity(
     let token_program_a = &mut ctx.accounts.token_program_a;
     let token_program_b = &mut ctx.accounts.token_program_b;
     let token_program_lp = &mut ctx.accounts.token_program_lp;
+    let system_program = &ctx.accounts.system_program;
+
+    let config = &ctx.accounts.config;
 
     // Validate amounts
+    check_deadline(deadline)?;
     require!(amount_a_desired > 0, CustomErrorCode::MustBeGreaterThanZero);
     require!(amount_b_desired > 0, CustomErrorCode::MustBeGreaterThanZero);
 
-    // Initial liquidity provision
-    // Don't validate tokens deposited ratio
-    if pool_ata_a.amount == 0 || pool_ata_b.amount == 0 {
-        // Transfer token A to pool
-        transfer_token_to_pool(
-            provider,
-            token_mint_a,
-            provider_ata_a,
-            pool_ata_a,
-            token_program_a,
-            amount_a_desired,
-        )?;
-
-        // Transfer token B to pool
-        transfer_token_to_pool(
-            provider,
-            token_mint_b,
-            provider_ata_b,
-            pool_ata_b,
-            token_program_b,
-            amount_b_desired,
-        )?;
-
-        let lp_amount = sqrt(amount_a_desired as u128 * amount_b_desired as u128).unwrap() as u64;
-
-        // Mint LP tokens to the provider
-        mint_lp_tokens(lp_mint, provider_ata_lp, pool, lp_amount, token_program_lp)?;
+    // Native SOL is wrapped from the provider lamports into a temporary wrapped SOL
+    // account, closed again once deposited
+    let wraps_a = native_sol && is_native_mint(&token_mint_a.key());
+    let wraps_b = native_sol && is_native_mint(&token_mint_b.key());
+    require!(
+        !native_sol || wraps_a || wraps_b,
+        CustomErrorCode::NotNativeMintPool
+    );
+    let provider_ata_a = side_token_account(
+        wraps_a,
+        &mut ctx.accounts.wsol_a,
+        &mut ctx.accounts.provider_ata_a,
+    )?;
+    let provider_ata_b = side_token_account(
+        wraps_b,
+        &mut ctx.accounts.wsol_b,
+        &mut ctx.accounts.provider_ata_b,
+    )?;
 
-        return Ok(());
-    }
+    let (reserve_a, reserve_b) = pool.reserves(pool_ata_a.amount, pool_ata_b.amount)?;
 
     // Calculate optimal amounts
+    // Initial liquidity provision doesn't validate the tokens deposited ratio
     // Following math logic from https://github.com/Uniswap/v2-periphery/blob/master/contracts/UniswapV2Router01.sol#L46
-    // + lps to mint https://github.com/Uniswap/v2-core/blob/master/contracts/UniswapV2Pair.sol#L110
-    let amount_a_desired = PreciseNumber::new(amount_a_desired as u128).unwrap();
-    let amount_b_desired = PreciseNumber::new(amount_b_desired as u128).unwrap();
-    let reserve_a_pool = PreciseNumber::new(pool_ata_a.amount as u128).unwrap();
-    let reserve_b_pool = PreciseNumber::new(pool_ata_b.amount as u128).unwrap();
-    let lp_total_supply = PreciseNumber::new(lp_mint.supply as u128).unwrap();
-
-    // Calculate optimal amount of token B to transfer (a_desired * reserve_b / reserve_a)
-    let amount_b_optimal = amount_a_desired
-        .checked_mul(&reserve_b_pool)
-        .unwrap()
-        .checked_div(&reserve_a_pool)
-        .unwrap();
+    let (amount_a, amount_b) =
+        math::quote_deposit(amount_a_desired, amount_b_desired, reserve_a, reserve_b)
+            .map_err(CustomErrorCode::from)?;
 
-    // if amount_b_optimal is within desired and amount_b_min, then we can transfer that amount
-    if amount_b_optimal.less_than_or_equal(&amount_b_desired) {
-        let amount_b_min = PreciseNumber::new(amount_b_min as u128).unwrap();
-
-        // make sure the optimal amount is greater than the minimum amount
-        require!(
-            amount_b_optimal.greater_than_or_equal(&amount_b_min),
-            CustomErrorCode::InsufficientBAmount
-        );
+    // make sure the optimal amounts are greater than the minimum amounts
+    require!(
+        amount_a >= amount_a_min,
+        CustomErrorCode::InsufficientAAmount
+    );
+    require!(
+        amount_b >= amount_b_min,
+        CustomErrorCode::InsufficientBAmount
+    );
 
-        let amount_a_transferred = &amount_a_desired;
-        let amount_b_transferred = &amount_b_optimal;
-        // transfer token B to pool
-        transfer_token_to_pool(
-            provider,
-            token_mint_b,
-            provider_ata_b,
-            pool_ata_b,
-            token_program_b,
-            amount_b_transferred.to_imprecise().unwrap() as u64,
-        )?;
+    // Protocol share of the fees earned since the last liquidity change, minted
+    // before pricing the deposit so the provider does not pay for it
+    let lp_supply = mint_protocol_lp_fee(
+        config,
+        pool,
+        lp_mint,
+        ctx.remaining_accounts,
+        token_program_lp,
+        reserve_a,
+        reserve_b,
+    )?;
 
-        msg!("Transferred token B to pool");
+    // LPs to mint https://github.com/Uniswap/v2-core/blob/master/contracts/UniswapV2Pair.sol#L110
+    let lp_amount = math::lp_to_mint(amount_a, amount_b, reserve_a, reserve_b, lp_supply)
+        .map_err(CustomErrorCode::from)?;
 
-        // transfer token A to pool
-        transfer_token_to_pool(
+    // Transfer token A to pool
+    if wraps_a {
+        wrap_sol(
             provider,
-            token_mint_a,
             provider_ata_a,
-            pool_ata_a,
             token_program_a,
-            amount_a_transferred.to_imprecise().unwrap() as u64,
+            system_program,
+            amount_a,
         )?;
+    }
+    transfer_token_to_pool(
+        provider,
+        token_mint_a,
+        provider_ata_a,
+        pool_ata_a,
+        token_program_a,
+        amount_a,
+    )?;
+    if wraps_a {
+        unwrap_sol(provider, provider_ata_a, token_program_a)?;
+    }
 
-        msg!("Transferred token A to pool");
-
-        let lp_amount_by_a = amount_a_transferred
-            .checked_mul(&lp_total_supply)
-            .unwrap()
-            .checked_div(&reserve_a_pool)
-            .unwrap()
-            .to_imprecise()
-            .unwrap() as u64;
-
-        let lp_amount_by_b = amount_b_transferred
-            .checked_mul(&lp_total_supply)
-            .unwrap()
-            .checked_div(&reserve_b_pool)
-            .unwrap()
-            .to_imprecise()
-            .unwrap() as u64;
-
-        msg!("Minted LP tokens");
-
-        mint_lp_tokens(
-            lp_mint,
-            provider_ata_lp,
-            pool,
-            std::cmp::min(lp_amount_by_a, lp_amount_by_b),
-            token_program_lp,
+    // Transfer token B to pool
+    if wraps_b {
+        wrap_sol(
+            provider,
+            provider_ata_b,
+            token_program_b,
+            system_program,
+            amount_b,
         )?;
-
-        return Ok(());
     }
-
-    let amount_a_optimal = amount_b_desired
-        .checked_mul(&reserve_a_pool)
-        .unwrap()
-        .checked_div(&reserve_b_pool)
-        .unwrap();
-    let amount_a_min = PreciseNumber::new(amount_a_min as u128).unwrap();
-
-    assert!(&amount_a_optimal.less_than_or_equal(&amount_a_desired));
-    // make sure the optimal amount is greater than the minimum amount
-    require!(
-        amount_a_optimal.greater_than_or_equal(&amount_a_min),
-        CustomErrorCode::InsufficientAAmount
-    );
-
-    let amount_a_transferred = &amount_a_optimal;
-    let amount_b_transferred = &amount_b_desired;
-
-    // transfer token B to pool
     transfer_token_to_pool(
         provider,
         token_mint_b,
         provider_ata_b,
         pool_ata_b,
         token_program_b,
-        amount_b_transferred.to_imprecise().unwrap() as u64,
+        amount_b,
     )?;
+    if wraps_b {
+        unwrap_sol(provider, provider_ata_b, token_program_b)?;
+    }
 
-    // transfer token A to pool
-    transfer_token_to_pool(
-        provider,
-        token_mint_a,
-        provider_ata_a,
-        pool_ata_a,
-        token_program_a,
-        amount_a_transferred.to_imprecise().unwrap() as u64,
-    )?;
-
-    let lp_amount_by_a = amount_a_transferred
-        .checked_mul(&lp_total_supply)
-        .unwrap()
-        .checked_div(&reserve_a_pool)
-        .unwrap()
-        .to_imprecise()
-        .unwrap() as u64;
-
-    let lp_amount_by_b = amount_b_transferred
-        .checked_mul(&lp_total_supply)
-        .unwrap()
-        .checked_div(&reserve_b_pool)
-        .unwrap()
-        .to_imprecise()
-        .unwrap() as u64;
+    // Mint LP tokens to the recipient
+    mint_lp_tokens(lp_mint, lp_recipient_ata, pool, lp_amount, token_program_lp)?;
 
-    mint_lp_tokens(
-        lp_mint,
-        provider_ata_lp,
-        pool,
-        std::cmp::min(lp_amount_by_a, lp_amount_by_b),
-        token_program_lp,
-    )?;
+    update_k_last(config, pool, reserve_a + amount_a, reserve_b + amount_b);
 
     Ok(())
 }
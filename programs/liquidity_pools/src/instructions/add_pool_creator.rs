@@ -0,0 +1,39 @@
+use crate::error::ErrorCode;
+use crate::state::{Config, PoolCreator};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(creator: Pubkey)]
+pub struct AddPoolCreator<'info> {
+    // CHECK: The signer is the pool creation admin or the authority of the program
+    #[account(
+        mut,
+        constraint = config.is_pool_creation_admin(&authority.key()) @ ErrorCode::NotPoolCreationAdmin,
+    )]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [Config::SEED],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + PoolCreator::INIT_SPACE,
+        seeds = [PoolCreator::SEED, creator.as_ref()],
+        bump,
+    )]
+    pub pool_creator: Account<'info, PoolCreator>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn add_pool_creator(ctx: Context<AddPoolCreator>, creator: Pubkey) -> Result<()> {
+    let pool_creator = &mut ctx.accounts.pool_creator;
+    pool_creator.creator = creator;
+    pool_creator.bump = ctx.bumps.pool_creator;
+
+    Ok(())
+}
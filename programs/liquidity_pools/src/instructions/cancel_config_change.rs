@@ -0,0 +1,32 @@
+use crate::error::ErrorCode;
+use crate::state::{Config, ConfigChange};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct CancelConfigChange<'info> {
+    // CHECK: The signer is the fee manager or the authority of the program
+    #[account(
+        mut,
+        constraint = config.is_fee_manager(&authority.key()) @ ErrorCode::NotFeeManager,
+    )]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [Config::SEED],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        close = authority,
+        seeds = [ConfigChange::SEED],
+        bump = config_change.bump,
+    )]
+    pub config_change: Account<'info, ConfigChange>,
+}
+
+// Discards the queued change, closing the account is all there is to do
+pub fn cancel_config_change(_ctx: Context<CancelConfigChange>) -> Result<()> {
+    Ok(())
+}
@@ -0,0 +1,94 @@
+use crate::error::ErrorCode;
+use crate::helpers::transfer::transfer_token_from_pool;
+use crate::state::Pool;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+// The creator withdraws the creator fees of its pool to token accounts of its choice
+#[derive(Accounts)]
+pub struct ClaimCreatorFees<'info> {
+    #[account(address = pool.creator @ ErrorCode::InvalidAuthority)]
+    pub creator: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            Pool::SEED,
+            pool.token_mint_a.key().as_ref(),
+            pool.token_mint_b.key().as_ref(),
+            pool.index.to_le_bytes().as_ref(),
+        ],
+        bump = pool.bump,
+        constraint = !pool.flash_loan_active @ ErrorCode::FlashLoanActive,
+    )]
+    pub pool: Account<'info, Pool>,
+
+    // Pool Token A accounts
+    #[account(address = pool.token_mint_a)]
+    pub token_mint_a: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        address = pool.token_vault_a,
+    )]
+    pub pool_ata_a: InterfaceAccount<'info, TokenAccount>,
+
+    // Pool Token B accounts
+    #[account(address = pool.token_mint_b)]
+    pub token_mint_b: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        address = pool.token_vault_b,
+    )]
+    pub pool_ata_b: InterfaceAccount<'info, TokenAccount>,
+
+    // Creator accounts
+    #[account(
+        mut,
+        constraint = creator_ata_a.mint == pool.token_mint_a,
+    )]
+    pub creator_ata_a: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        constraint = creator_ata_b.mint == pool.token_mint_b,
+    )]
+    pub creator_ata_b: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program_a: Interface<'info, TokenInterface>,
+    pub token_program_b: Interface<'info, TokenInterface>,
+}
+
+pub fn claim_creator_fees(ctx: Context<ClaimCreatorFees>) -> Result<()> {
+    let pool = &mut ctx.accounts.pool;
+    let creator_fees_a = pool.creator_fees_a;
+    let creator_fees_b = pool.creator_fees_b;
+
+    msg!("creator_fees_a: {}", creator_fees_a);
+    msg!("creator_fees_b: {}", creator_fees_b);
+
+    pool.creator_fees_a = 0;
+    pool.creator_fees_b = 0;
+
+    if creator_fees_a > 0 {
+        transfer_token_from_pool(
+            pool,
+            &ctx.accounts.token_mint_a,
+            &mut ctx.accounts.pool_ata_a,
+            &mut ctx.accounts.creator_ata_a,
+            &ctx.accounts.token_program_a,
+            creator_fees_a,
+        )?;
+    }
+
+    if creator_fees_b > 0 {
+        transfer_token_from_pool(
+            pool,
+            &ctx.accounts.token_mint_b,
+            &mut ctx.accounts.pool_ata_b,
+            &mut ctx.accounts.creator_ata_b,
+            &ctx.accounts.token_program_b,
+            creator_fees_b,
+        )?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,119 @@
+use crate::error::ErrorCode;
+use crate::helpers::protocol_fee::{fee_recipient_account, weighted_share};
+use crate::helpers::transfer::transfer_token_from_pool;
+use crate::state::{Config, Pool};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+// Anyone can crank the protocol fees of a pool, they can only go to the fee recipients.
+// The remaining accounts are the token A and token B accounts of each entry of
+// Config::fee_recipients, in order
+#[derive(Accounts)]
+pub struct ClaimFees<'info> {
+    #[account(
+        seeds = [Config::SEED],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        seeds = [
+            Pool::SEED,
+            pool.token_mint_a.key().as_ref(),
+            pool.token_mint_b.key().as_ref(),
+            pool.index.to_le_bytes().as_ref(),
+        ],
+        bump = pool.bump,
+        constraint = !pool.flash_loan_active @ ErrorCode::FlashLoanActive,
+    )]
+    pub pool: Account<'info, Pool>,
+
+    // Pool Token A accounts
+    #[account(address = pool.token_mint_a)]
+    pub token_mint_a: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        address = pool.token_vault_a,
+    )]
+    pub pool_ata_a: InterfaceAccount<'info, TokenAccount>,
+
+    // Pool Token B accounts
+    #[account(address = pool.token_mint_b)]
+    pub token_mint_b: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        address = pool.token_vault_b,
+    )]
+    pub pool_ata_b: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program_a: Interface<'info, TokenInterface>,
+    pub token_program_b: Interface<'info, TokenInterface>,
+}
+
+pub fn claim_fees<'info>(ctx: Context<'_, '_, 'info, 'info, ClaimFees<'info>>) -> Result<()> {
+    let recipients = ctx.accounts.config.active_fee_recipients();
+    require!(
+        ctx.remaining_accounts.len() == 2 * recipients.len(),
+        ErrorCode::InvalidFeeRecipient
+    );
+
+    let pool = &mut ctx.accounts.pool;
+    let protocol_fees_a = pool.protocol_fees_a;
+    let protocol_fees_b = pool.protocol_fees_b;
+
+    msg!("protocol_fees_a: {}", protocol_fees_a);
+    msg!("protocol_fees_b: {}", protocol_fees_b);
+
+    pool.protocol_fees_a = 0;
+    pool.protocol_fees_b = 0;
+
+    let mut remaining_a = protocol_fees_a;
+    let mut remaining_b = protocol_fees_b;
+    for (i, (entry, accounts)) in recipients
+        .iter()
+        .zip(ctx.remaining_accounts.chunks(2))
+        .enumerate()
+    {
+        let mut recipient_ata_a =
+            fee_recipient_account(&accounts[0], &pool.token_mint_a, &entry.recipient)?;
+        let mut recipient_ata_b =
+            fee_recipient_account(&accounts[1], &pool.token_mint_b, &entry.recipient)?;
+
+        // The last recipient takes the rounding dust
+        let (amount_a, amount_b) = if i + 1 == recipients.len() {
+            (remaining_a, remaining_b)
+        } else {
+            (
+                weighted_share(protocol_fees_a, entry.weight_bp)?,
+                weighted_share(protocol_fees_b, entry.weight_bp)?,
+            )
+        };
+        remaining_a -= amount_a;
+        remaining_b -= amount_b;
+
+        if amount_a > 0 {
+            transfer_token_from_pool(
+                pool,
+                &ctx.accounts.token_mint_a,
+                &mut ctx.accounts.pool_ata_a,
+                &mut recipient_ata_a,
+                &ctx.accounts.token_program_a,
+                amount_a,
+            )?;
+        }
+
+        if amount_b > 0 {
+            transfer_token_from_pool(
+                pool,
+                &ctx.accounts.token_mint_b,
+                &mut ctx.accounts.pool_ata_b,
+                &mut recipient_ata_b,
+                &ctx.accounts.token_program_b,
+                amount_b,
+            )?;
+        }
+    }
+
+    Ok(())
+}
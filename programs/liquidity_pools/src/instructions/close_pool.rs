@@ -0,0 +1,130 @@
+use crate::error::ErrorCode;
+use crate::helpers::registry::remove_from_pair_index;
+use crate::helpers::transfer::close_pool_token_account;
+use crate::state::{Config, PairIndex, Pool};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+#[derive(Accounts)]
+pub struct ClosePool<'info> {
+    // Pool creator, pool admin or config authority
+    #[account(
+        constraint = authority.key() == pool.creator
+            || config.is_pool_admin(&authority.key()) @ ErrorCode::InvalidAuthority,
+    )]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [Config::SEED],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        close = recipient,
+        seeds = [
+            Pool::SEED,
+            pool.token_mint_a.key().as_ref(),
+            pool.token_mint_b.key().as_ref(),
+            pool.index.to_le_bytes().as_ref(),
+        ],
+        bump = pool.bump,
+        constraint = !pool.flash_loan_active @ ErrorCode::FlashLoanActive,
+    )]
+    pub pool: Account<'info, Pool>,
+
+    // Pool Token A accounts
+    pub token_mint_a: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        associated_token::mint = token_mint_a,
+        associated_token::authority = pool,
+        associated_token::token_program = token_program_a,
+        constraint = pool_ata_a.amount == 0 @ ErrorCode::PoolNotEmpty,
+    )]
+    pub pool_ata_a: InterfaceAccount<'info, TokenAccount>,
+
+    // Pool Token B accounts
+    pub token_mint_b: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        associated_token::mint = token_mint_b,
+        associated_token::authority = pool,
+        associated_token::token_program = token_program_b,
+        constraint = pool_ata_b.amount == 0 @ ErrorCode::PoolNotEmpty,
+    )]
+    pub pool_ata_b: InterfaceAccount<'info, TokenAccount>,
+
+    // LP mint can not be closed (no close authority), it only has to be unused
+    #[account(
+        seeds = [
+            Pool::MINT_SEED,
+            pool.token_mint_a.key().as_ref(),
+            pool.token_mint_b.key().as_ref(),
+            pool.index.to_le_bytes().as_ref(),
+        ],
+        bump,
+        constraint = lp_mint.supply == 0 @ ErrorCode::PoolNotEmpty,
+    )]
+    pub lp_mint: InterfaceAccount<'info, Mint>,
+
+    // Holds the LP tokens of the lock positions, it only exists once a position was
+    // opened and is empty with the LP supply
+    #[account(
+        mut,
+        associated_token::mint = lp_mint,
+        associated_token::authority = pool,
+        associated_token::token_program = token_program_lp,
+    )]
+    pub lock_vault: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    // Pair index page listing the pool, pools created before the registry go
+    // through register_pool first
+    #[account(
+        mut,
+        seeds = [
+            PairIndex::SEED,
+            pool.token_mint_a.key().as_ref(),
+            pool.token_mint_b.key().as_ref(),
+            pool.pair_index_page.to_le_bytes().as_ref(),
+        ],
+        bump = pair_index.bump,
+    )]
+    pub pair_index: Box<Account<'info, PairIndex>>,
+
+    /// CHECK: Receives the rent of the pool and its vaults
+    #[account(mut)]
+    pub recipient: UncheckedAccount<'info>,
+
+    pub token_program_a: Interface<'info, TokenInterface>,
+    pub token_program_b: Interface<'info, TokenInterface>,
+    pub token_program_lp: Interface<'info, TokenInterface>,
+}
+
+pub fn close_pool(ctx: Context<ClosePool>) -> Result<()> {
+    let pool = &ctx.accounts.pool;
+    let recipient = &ctx.accounts.recipient.to_account_info();
+
+    // Close the vaults while the pool can still sign, the pool account itself
+    // is closed by Anchor on exit
+    close_pool_token_account(
+        pool,
+        &ctx.accounts.pool_ata_a,
+        recipient,
+        &ctx.accounts.token_program_a,
+    )?;
+    close_pool_token_account(
+        pool,
+        &ctx.accounts.pool_ata_b,
+        recipient,
+        &ctx.accounts.token_program_b,
+    )?;
+    if let Some(lock_vault) = &ctx.accounts.lock_vault {
+        close_pool_token_account(pool, lock_vault, recipient, &ctx.accounts.token_program_lp)?;
+    }
+
+    remove_from_pair_index(&mut ctx.accounts.pair_index, &pool.key());
+
+    Ok(())
+}
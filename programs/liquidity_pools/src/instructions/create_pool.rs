@@ -1,15 +1,31 @@
 use crate::error::ErrorCode;
-use crate::state::Pool;
+use crate::helpers::registry::add_to_registry;
+use crate::helpers::transfer::{transfer_lamports, transfer_token_to_pool};
+use crate::state::{Config, PairIndex, PairIndexHeader, Pool, PoolCreator, PoolRegistry};
 use anchor_lang::prelude::*;
 use anchor_spl::associated_token::AssociatedToken;
 use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 
 #[derive(Accounts)]
-#[instruction(fee_bp: u16)]
+#[instruction(index: u16)]
 pub struct CreatePool<'info> {
     #[account(mut)]
     pub authority: Signer<'info>,
 
+    #[account(
+        mut,
+        seeds = [Config::SEED],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+
+    // Allowlist entry of the signer, only needed in allowlist mode
+    #[account(
+        seeds = [PoolCreator::SEED, authority.key().as_ref()],
+        bump = pool_creator.bump,
+    )]
+    pub pool_creator: Option<Account<'info, PoolCreator>>,
+
     #[account(
         init,
         payer = authority,
@@ -18,7 +34,7 @@ pub struct CreatePool<'info> {
             Pool::SEED,
             token_mint_a.key().as_ref(),
             token_mint_b.key().as_ref(),
-            fee_bp.to_le_bytes().as_ref(),
+            index.to_le_bytes().as_ref(),
         ],
         constraint = token_mint_a.key() > token_mint_b.key() @ ErrorCode::InvalidTokenOrder,
         bump,
@@ -65,12 +81,51 @@ pub struct CreatePool<'info> {
             Pool::MINT_SEED,
             token_mint_a.key().as_ref(),
             token_mint_b.key().as_ref(),
-            fee_bp.to_le_bytes().as_ref(),
+            index.to_le_bytes().as_ref(),
         ],
         bump,
     )]
     pub lp_mint: InterfaceAccount<'info, Mint>,
 
+    // Registry page the pool is appended to
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + PoolRegistry::INIT_SPACE,
+        seeds = [PoolRegistry::SEED, config.registry_page().to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub registry: Box<Account<'info, PoolRegistry>>,
+
+    // Pair index pages of the mint pair
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + PairIndexHeader::INIT_SPACE,
+        seeds = [
+            PairIndexHeader::SEED,
+            token_mint_a.key().as_ref(),
+            token_mint_b.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub pair_index_header: Box<Account<'info, PairIndexHeader>>,
+
+    // Pair index page the pool is appended to
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + PairIndex::INIT_SPACE,
+        seeds = [
+            PairIndex::SEED,
+            token_mint_a.key().as_ref(),
+            token_mint_b.key().as_ref(),
+            pair_index_header.next_page().to_le_bytes().as_ref(),
+        ],
+        bump,
+    )]
+    pub pair_index: Box<Account<'info, PairIndex>>,
+
     // Separate token programs for each token (support for tokens with different token programs)
     pub token_program_a: Interface<'info, TokenInterface>,
     pub token_program_b: Interface<'info, TokenInterface>,
@@ -79,9 +134,47 @@ pub struct CreatePool<'info> {
 
     pub system_program: Program<'info, System>,
     pub associated_token_program: Program<'info, AssociatedToken>,
+
+    // Creation fee accounts, the recipient alone for a fee in lamports and
+    // the token accounts for a fee in pool_creation_fee_mint
+    /// CHECK: Receives lamports only, its address is the one of the config
+    #[account(
+        mut,
+        address = config.protocol_fee_recipient @ ErrorCode::InvalidFeeRecipient,
+    )]
+    pub protocol_fee_recipient: Option<UncheckedAccount<'info>>,
+    #[account(address = config.pool_creation_fee_mint)]
+    pub creation_fee_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+    #[account(
+        mut,
+        token::mint = creation_fee_mint,
+        token::authority = authority,
+        token::token_program = creation_fee_token_program,
+    )]
+    pub authority_fee_ata: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    #[account(
+        mut,
+        constraint = recipient_fee_ata.mint == config.pool_creation_fee_mint @ ErrorCode::InvalidFeeRecipient,
+        constraint = recipient_fee_ata.owner == config.protocol_fee_recipient @ ErrorCode::InvalidFeeRecipient,
+    )]
+    pub recipient_fee_ata: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    pub creation_fee_token_program: Option<Interface<'info, TokenInterface>>,
 }
 
-pub fn create_pool(ctx: Context<CreatePool>, fee_bp: u16) -> Result<()> {
+pub fn create_pool(ctx: Context<CreatePool>, index: u16, fee_bp: u16) -> Result<()> {
+    require!(
+        ctx.accounts.config.can_create_pools(
+            &ctx.accounts.authority.key(),
+            ctx.accounts.pool_creator.is_some()
+        ),
+        ErrorCode::PoolCreationNotAllowed
+    );
+    require!(
+        ctx.accounts.config.is_valid_pool_fee(fee_bp),
+        ErrorCode::InvalidPoolFee
+    );
+    charge_creation_fee(ctx.accounts)?;
+
     let pool = &mut ctx.accounts.pool;
 
     // Initialize pool state
@@ -89,8 +182,71 @@ pub fn create_pool(ctx: Context<CreatePool>, fee_bp: u16) -> Result<()> {
     pool.token_mint_b = ctx.accounts.token_mint_b.key();
     pool.token_vault_a = ctx.accounts.token_vault_a.key();
     pool.token_vault_b = ctx.accounts.token_vault_b.key();
+    pool.index = index;
     pool.fee_bp = fee_bp;
     pool.bump = ctx.bumps.pool;
+    pool.creator = ctx.accounts.authority.key();
+    pool.version = Pool::VERSION;
+
+    let registry = &mut ctx.accounts.registry;
+    registry.page = ctx.accounts.config.registry_page();
+    registry.bump = ctx.bumps.registry;
+    let pair_index_header = &mut ctx.accounts.pair_index_header;
+    pair_index_header.token_mint_a = pool.token_mint_a;
+    pair_index_header.token_mint_b = pool.token_mint_b;
+    pair_index_header.bump = ctx.bumps.pair_index_header;
+    let pair_index = &mut ctx.accounts.pair_index;
+    pair_index.token_mint_a = pool.token_mint_a;
+    pair_index.token_mint_b = pool.token_mint_b;
+    pair_index.page = pair_index_header.next_page();
+    pair_index.bump = ctx.bumps.pair_index;
+    let pool_key = pool.key();
+    add_to_registry(
+        &mut ctx.accounts.config,
+        registry,
+        pair_index_header,
+        pair_index,
+        pool,
+        pool_key,
+    )?;
 
     Ok(())
 }
+
+fn charge_creation_fee(accounts: &mut CreatePool) -> Result<()> {
+    let config = &accounts.config;
+    let fee = config.pool_creation_fee;
+    if fee == 0 {
+        return Ok(());
+    }
+    msg!("pool_creation_fee: {}", fee);
+
+    if config.charges_creation_fee_in_lamports() {
+        let Some(recipient) = &accounts.protocol_fee_recipient else {
+            return err!(ErrorCode::MissingCreationFeeAccounts);
+        };
+        return transfer_lamports(
+            &accounts.authority,
+            &recipient.to_account_info(),
+            &accounts.system_program,
+            fee,
+        );
+    }
+
+    let (Some(mint), Some(authority_ata), Some(recipient_ata), Some(token_program)) = (
+        accounts.creation_fee_mint.as_mut(),
+        accounts.authority_fee_ata.as_mut(),
+        accounts.recipient_fee_ata.as_mut(),
+        accounts.creation_fee_token_program.as_mut(),
+    ) else {
+        return err!(ErrorCode::MissingCreationFeeAccounts);
+    };
+    transfer_token_to_pool(
+        &mut accounts.authority,
+        mint,
+        authority_ata,
+        recipient_ata,
+        token_program,
+        fee,
+    )
+}
@@ -0,0 +1,69 @@
+use crate::error::ErrorCode;
+use crate::math::FEE_DENOMINATOR;
+use crate::state::{Config, ConfigChange, FeeRecipient};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct ExecuteConfigChange<'info> {
+    // CHECK: The signer is the fee manager or the authority of the program
+    #[account(
+        mut,
+        constraint = config.is_fee_manager(&authority.key()) @ ErrorCode::NotFeeManager,
+    )]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [Config::SEED],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        close = authority,
+        seeds = [ConfigChange::SEED],
+        bump = config_change.bump,
+    )]
+    pub config_change: Account<'info, ConfigChange>,
+}
+
+pub fn execute_config_change(ctx: Context<ExecuteConfigChange>) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    let config_change = &ctx.accounts.config_change;
+
+    let now = Clock::get()?.unix_timestamp;
+    require!(now >= config_change.eta, ErrorCode::TimelockNotExpired);
+
+    // A new recipient takes all of the claimed fees, a new split makes its first
+    // entry the recipient of the other protocol fees
+    if let Some(protocol_fee_recipient) = config_change.protocol_fee_recipient {
+        config.protocol_fee_recipient = protocol_fee_recipient;
+        config.set_fee_recipients(&[FeeRecipient {
+            recipient: protocol_fee_recipient,
+            weight_bp: FEE_DENOMINATOR as u16,
+        }]);
+    }
+    if let Some(first) = config_change.fee_recipients.first() {
+        config.protocol_fee_recipient = first.recipient;
+        config.set_fee_recipients(&config_change.fee_recipients);
+    }
+
+    if let Some(protocol_fee_bp) = config_change.protocol_fee_bp {
+        config.protocol_fee_bp = protocol_fee_bp;
+    }
+
+    if let Some(timelock_delay) = config_change.timelock_delay {
+        config.timelock_delay = timelock_delay;
+    }
+
+    if let Some(protocol_fee_mode) = config_change.protocol_fee_mode {
+        config.protocol_fee_mode = protocol_fee_mode;
+    }
+
+    if let Some(max_protocol_fee_bp_override) = config_change.max_protocol_fee_bp_override {
+        config.max_protocol_fee_bp_override = max_protocol_fee_bp_override;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,177 @@
+use crate::error::ErrorCode;
+use crate::helpers::transfer::transfer_token_from_pool;
+use crate::state::{Config, Pool};
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar::instructions::{
+    load_current_index_checked, load_instruction_at_checked,
+};
+use anchor_lang::Discriminator;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+#[derive(Accounts)]
+pub struct FlashLoan<'info> {
+    #[account(mut)]
+    pub borrower: Signer<'info>,
+
+    // Pool accounts
+    // The pool must stay the second account, flash_repay is matched against it
+    #[account(
+        mut,
+        seeds = [
+            Pool::SEED,
+            pool.token_mint_a.key().as_ref(),
+            pool.token_mint_b.key().as_ref(),
+            pool.index.to_le_bytes().as_ref(),
+        ],
+        bump = pool.bump,
+        constraint = !pool.flash_loan_active @ ErrorCode::FlashLoanActive,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    // Pool Token A accounts
+    pub token_mint_a: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        associated_token::mint = token_mint_a,
+        associated_token::authority = pool,
+        associated_token::token_program = token_program_a,
+    )]
+    pub pool_ata_a: InterfaceAccount<'info, TokenAccount>,
+
+    // Pool Token B accounts
+    pub token_mint_b: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        associated_token::mint = token_mint_b,
+        associated_token::authority = pool,
+        associated_token::token_program = token_program_b,
+    )]
+    pub pool_ata_b: InterfaceAccount<'info, TokenAccount>,
+
+    // Borrower accounts
+    #[account(
+        mut,
+        associated_token::mint = token_mint_a,
+        associated_token::authority = borrower,
+        associated_token::token_program = token_program_a,
+    )]
+    pub borrower_ata_a: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        associated_token::mint = token_mint_b,
+        associated_token::authority = borrower,
+        associated_token::token_program = token_program_b,
+    )]
+    pub borrower_ata_b: InterfaceAccount<'info, TokenAccount>,
+
+    // Pause flag
+    #[account(
+        seeds = [Config::SEED],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::Paused,
+    )]
+    pub config: Box<Account<'info, Config>>,
+
+    /// CHECK: Instructions sysvar, used to find the matching flash_repay
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: UncheckedAccount<'info>,
+
+    // System accounts
+    // Separate token programs for each token (support for tokens with different token programs)
+    pub token_program_a: Interface<'info, TokenInterface>,
+    pub token_program_b: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+pub fn flash_loan(ctx: Context<FlashLoan>, amount_a: u64, amount_b: u64) -> Result<()> {
+    let pool = &mut ctx.accounts.pool;
+    let token_mint_a = &ctx.accounts.token_mint_a;
+    let token_mint_b = &ctx.accounts.token_mint_b;
+    let pool_ata_a = &mut ctx.accounts.pool_ata_a;
+    let pool_ata_b = &mut ctx.accounts.pool_ata_b;
+    let borrower_ata_a = &mut ctx.accounts.borrower_ata_a;
+    let borrower_ata_b = &mut ctx.accounts.borrower_ata_b;
+    let token_program_a = &ctx.accounts.token_program_a;
+    let token_program_b = &ctx.accounts.token_program_b;
+
+    // Validate amounts
+    require!(
+        amount_a > 0 || amount_b > 0,
+        ErrorCode::MustBeGreaterThanZero
+    );
+
+    // Get current pool reserves
+    let (reserve_a, reserve_b) = pool.reserves(pool_ata_a.amount, pool_ata_b.amount)?;
+
+    // Check pool has liquidity and the loan does not drain it
+    require!(reserve_a > 0 && reserve_b > 0, ErrorCode::EmptyPool);
+    require!(
+        amount_a < reserve_a && amount_b < reserve_b,
+        ErrorCode::InsufficientLiquidity
+    );
+
+    // The loan is only handed out if it is repaid later in this transaction
+    require_flash_repay(&ctx.accounts.instructions_sysvar, &pool.key())?;
+
+    // Lock the pool until flash_repay
+    pool.flash_loan_active = true;
+    pool.flash_loan_amount_a = amount_a;
+    pool.flash_loan_amount_b = amount_b;
+    pool.flash_loan_reserve_a = reserve_a;
+    pool.flash_loan_reserve_b = reserve_b;
+
+    if amount_a > 0 {
+        transfer_token_from_pool(
+            pool,
+            token_mint_a,
+            pool_ata_a,
+            borrower_ata_a,
+            token_program_a,
+            amount_a,
+        )?;
+    }
+
+    if amount_b > 0 {
+        transfer_token_from_pool(
+            pool,
+            token_mint_b,
+            pool_ata_b,
+            borrower_ata_b,
+            token_program_b,
+            amount_b,
+        )?;
+    }
+
+    Ok(())
+}
+
+// Scans the instructions following the current one for a flash_repay of this program on the same pool
+fn require_flash_repay(instructions_sysvar: &AccountInfo, pool: &Pubkey) -> Result<()> {
+    let current_index = load_current_index_checked(instructions_sysvar)? as usize;
+    let current_ix = load_instruction_at_checked(current_index, instructions_sysvar)?;
+
+    // Introspection only sees top level instructions
+    require_keys_eq!(
+        current_ix.program_id,
+        crate::ID,
+        ErrorCode::FlashLoanCpiNotAllowed
+    );
+
+    let mut index = current_index + 1;
+    while let Ok(ix) = load_instruction_at_checked(index, instructions_sysvar) {
+        if ix.program_id == crate::ID
+            && ix
+                .data
+                .starts_with(crate::instruction::FlashRepay::DISCRIMINATOR)
+            && ix.accounts.get(1).map(|meta| meta.pubkey) == Some(*pool)
+        {
+            return Ok(());
+        }
+
+        index += 1;
+    }
+
+    err!(ErrorCode::FlashLoanNotRepaid)
+}
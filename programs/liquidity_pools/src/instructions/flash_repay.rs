@@ -0,0 +1,170 @@
+use crate::error::ErrorCode;
+use crate::helpers::fees::Fees;
+use crate::helpers::limits::check_trade_limits;
+use crate::helpers::transfer::transfer_token_to_pool;
+use crate::state::{Config, Pool};
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+#[derive(Accounts)]
+pub struct FlashRepay<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    // Pool accounts
+    // The pool must stay the second account, flash_loan matches it against the borrowed pool
+    #[account(
+        mut,
+        seeds = [
+            Pool::SEED,
+            pool.token_mint_a.key().as_ref(),
+            pool.token_mint_b.key().as_ref(),
+            pool.index.to_le_bytes().as_ref(),
+        ],
+        bump = pool.bump,
+        constraint = pool.flash_loan_active @ ErrorCode::FlashLoanNotActive,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    // Pool Token A accounts
+    pub token_mint_a: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        associated_token::mint = token_mint_a,
+        associated_token::authority = pool,
+        associated_token::token_program = token_program_a,
+    )]
+    pub pool_ata_a: InterfaceAccount<'info, TokenAccount>,
+
+    // Pool Token B accounts
+    pub token_mint_b: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        associated_token::mint = token_mint_b,
+        associated_token::authority = pool,
+        associated_token::token_program = token_program_b,
+    )]
+    pub pool_ata_b: InterfaceAccount<'info, TokenAccount>,
+
+    // Payer accounts
+    #[account(
+        mut,
+        associated_token::mint = token_mint_a,
+        associated_token::authority = payer,
+        associated_token::token_program = token_program_a,
+    )]
+    pub payer_ata_a: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        associated_token::mint = token_mint_b,
+        associated_token::authority = payer,
+        associated_token::token_program = token_program_b,
+    )]
+    pub payer_ata_b: InterfaceAccount<'info, TokenAccount>,
+
+    // Pause flag
+    #[account(
+        seeds = [Config::SEED],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::Paused,
+    )]
+    pub config: Box<Account<'info, Config>>,
+
+    // System accounts
+    // Separate token programs for each token (support for tokens with different token programs)
+    pub token_program_a: Interface<'info, TokenInterface>,
+    pub token_program_b: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+// Repayment may be made in either token (flash swap), as long as k does not decrease
+// and the net trade stays within the pool swap limits
+pub fn flash_repay(ctx: Context<FlashRepay>, amount_a: u64, amount_b: u64) -> Result<()> {
+    let payer = &mut ctx.accounts.payer;
+    let pool = &mut ctx.accounts.pool;
+    let token_mint_a = &mut ctx.accounts.token_mint_a;
+    let token_mint_b = &mut ctx.accounts.token_mint_b;
+    let pool_ata_a = &mut ctx.accounts.pool_ata_a;
+    let pool_ata_b = &mut ctx.accounts.pool_ata_b;
+    let payer_ata_a = &mut ctx.accounts.payer_ata_a;
+    let payer_ata_b = &mut ctx.accounts.payer_ata_b;
+    let token_program_a = &mut ctx.accounts.token_program_a;
+    let token_program_b = &mut ctx.accounts.token_program_b;
+
+    if amount_a > 0 {
+        transfer_token_to_pool(
+            payer,
+            token_mint_a,
+            payer_ata_a,
+            pool_ata_a,
+            token_program_a,
+            amount_a,
+        )?;
+    }
+
+    if amount_b > 0 {
+        transfer_token_to_pool(
+            payer,
+            token_mint_b,
+            payer_ata_b,
+            pool_ata_b,
+            token_program_b,
+            amount_b,
+        )?;
+    }
+
+    pool_ata_a.reload()?;
+    pool_ata_b.reload()?;
+
+    // The flash fee is charged on each borrowed amount and excluded from the reserves checked against k
+    let fee_a = Fees::calculate_fee_tokens(pool.flash_loan_amount_a as u128, pool.fee_bp)?;
+    let fee_b = Fees::calculate_fee_tokens(pool.flash_loan_amount_b as u128, pool.fee_bp)?;
+
+    let (reserve_a, reserve_b) = pool.reserves(pool_ata_a.amount, pool_ata_b.amount)?;
+    let reserve_a_adjusted = (reserve_a as u128).saturating_sub(fee_a);
+    let reserve_b_adjusted = (reserve_b as u128).saturating_sub(fee_b);
+
+    let k_before = (pool.flash_loan_reserve_a as u128)
+        .checked_mul(pool.flash_loan_reserve_b as u128)
+        .ok_or(ErrorCode::Overflow)?;
+    let k_after = reserve_a_adjusted
+        .checked_mul(reserve_b_adjusted)
+        .ok_or(ErrorCode::Overflow)?;
+
+    msg!("k_before: {}", k_before);
+    msg!("k_after: {}", k_after);
+
+    require!(k_after >= k_before, ErrorCode::InvariantViolated);
+
+    // Repaying in the other token is a swap of the net amounts, it gets the swap limits
+    let (reserve_a_before, reserve_b_before) =
+        (pool.flash_loan_reserve_a, pool.flash_loan_reserve_b);
+    if reserve_a > reserve_a_before && reserve_b < reserve_b_before {
+        check_trade_limits(
+            pool,
+            reserve_a - reserve_a_before,
+            reserve_b_before - reserve_b,
+            reserve_a_before,
+            reserve_b_before,
+        )?;
+    } else if reserve_b > reserve_b_before && reserve_a < reserve_a_before {
+        check_trade_limits(
+            pool,
+            reserve_b - reserve_b_before,
+            reserve_a_before - reserve_a,
+            reserve_b_before,
+            reserve_a_before,
+        )?;
+    }
+
+    // Unlock the pool
+    pool.flash_loan_active = false;
+    pool.flash_loan_amount_a = 0;
+    pool.flash_loan_amount_b = 0;
+    pool.flash_loan_reserve_a = 0;
+    pool.flash_loan_reserve_b = 0;
+
+    Ok(())
+}
@@ -1,4 +1,6 @@
-use crate::state::config::*;
+use crate::error::ErrorCode;
+use crate::math::FEE_DENOMINATOR;
+use crate::state::{Config, FeeRecipient};
 use anchor_lang::prelude::*;
 
 #[derive(Accounts)]
@@ -34,10 +36,30 @@ pub fn initialize(
     let config = &mut ctx.accounts.config;
     let authority = &mut ctx.accounts.authority;
 
+    require!(
+        protocol_fee_bp as u128 <= FEE_DENOMINATOR,
+        ErrorCode::InvalidProtocolFee
+    );
+
     config.authority = authority.key();
     config.protocol_fee_recipient = protocol_fee_recipient;
     config.protocol_fee_bp = protocol_fee_bp;
     config.bump = ctx.bumps.config;
+    // Every role starts with the authority
+    config.fee_manager = authority.key();
+    config.pause_guardian = authority.key();
+    config.pool_admin = authority.key();
+    config.rewards_admin = authority.key();
+    config.pool_creation_admin = authority.key();
+    // Any pool fee is allowed until the fee manager narrows the bounds
+    config.min_pool_fee_bp = 0;
+    config.max_pool_fee_bp = FEE_DENOMINATOR as u16;
+    // The recipient takes all of the claimed fees until the fee manager splits them
+    config.set_fee_recipients(&[FeeRecipient {
+        recipient: protocol_fee_recipient,
+        weight_bp: FEE_DENOMINATOR as u16,
+    }]);
+    config.version = Config::VERSION;
 
     Ok(())
 }
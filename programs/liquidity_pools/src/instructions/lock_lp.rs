@@ -0,0 +1,124 @@
+use crate::error::ErrorCode;
+use crate::helpers::transfer::transfer_token_to_pool;
+use crate::state::{Config, LockDuration, LockPosition, Pool};
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+#[derive(Accounts)]
+#[instruction(id: u64)]
+pub struct LockLp<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    #[account(
+        mut,
+        associated_token::mint = lp_mint,
+        associated_token::authority = owner,
+        associated_token::token_program = token_program_lp,
+    )]
+    pub owner_ata_lp: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    // Early exit penalty the position is opened with
+    #[account(
+        seeds = [Config::SEED],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+
+    #[account(
+        mut,
+        seeds = [
+            Pool::SEED,
+            pool.token_mint_a.key().as_ref(),
+            pool.token_mint_b.key().as_ref(),
+            pool.index.to_le_bytes().as_ref(),
+        ],
+        bump = pool.bump,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    #[account(
+        seeds = [
+            Pool::MINT_SEED,
+            pool.token_mint_a.key().as_ref(),
+            pool.token_mint_b.key().as_ref(),
+            pool.index.to_le_bytes().as_ref(),
+        ],
+        bump,
+    )]
+    pub lp_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    // Holds the LP tokens of every lock position of the pool
+    #[account(
+        init_if_needed,
+        payer = owner,
+        associated_token::mint = lp_mint,
+        associated_token::authority = pool,
+        associated_token::token_program = token_program_lp,
+    )]
+    pub lock_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + LockPosition::INIT_SPACE,
+        seeds = [
+            LockPosition::SEED,
+            pool.key().as_ref(),
+            owner.key().as_ref(),
+            id.to_le_bytes().as_ref(),
+        ],
+        bump,
+    )]
+    pub lock_position: Account<'info, LockPosition>,
+
+    pub token_program_lp: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+pub fn lock_lp(ctx: Context<LockLp>, id: u64, amount: u64, duration: LockDuration) -> Result<()> {
+    require!(amount > 0, ErrorCode::MustBeGreaterThanZero);
+
+    transfer_token_to_pool(
+        &mut ctx.accounts.owner,
+        &mut ctx.accounts.lp_mint,
+        &mut ctx.accounts.owner_ata_lp,
+        &mut ctx.accounts.lock_vault,
+        &mut ctx.accounts.token_program_lp,
+        amount,
+    )?;
+
+    let now = Clock::get()?.unix_timestamp;
+    let boosted_amount = duration.boosted_amount(amount)?;
+
+    let pool = &mut ctx.accounts.pool;
+    pool.total_locked_lp = pool
+        .total_locked_lp
+        .checked_add(amount)
+        .ok_or(ErrorCode::Overflow)?;
+    pool.total_boosted_lp = pool
+        .total_boosted_lp
+        .checked_add(boosted_amount)
+        .ok_or(ErrorCode::Overflow)?;
+
+    let lock_position = &mut ctx.accounts.lock_position;
+    lock_position.pool = pool.key();
+    lock_position.owner = ctx.accounts.owner.key();
+    lock_position.id = id;
+    lock_position.amount = amount;
+    lock_position.boosted_amount = boosted_amount;
+    lock_position.duration = duration;
+    lock_position.locked_at = now;
+    lock_position.unlock_at = now
+        .checked_add(duration.seconds())
+        .ok_or(ErrorCode::Overflow)?;
+    lock_position.early_exit_penalty_bp = ctx.accounts.config.early_exit_penalty_bp;
+    lock_position.bump = ctx.bumps.lock_position;
+
+    msg!("amount: {}", amount);
+    msg!("boosted_amount: {}", boosted_amount);
+    msg!("unlock_at: {}", lock_position.unlock_at);
+
+    Ok(())
+}
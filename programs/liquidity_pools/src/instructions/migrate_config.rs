@@ -0,0 +1,69 @@
+use crate::error::ErrorCode;
+use crate::helpers::migration::grow_account;
+use crate::math::FEE_DENOMINATOR;
+use crate::state::{Config, FeeRecipient};
+use anchor_lang::prelude::*;
+
+// Moves the config written by an older layout to the current version in place
+#[derive(Accounts)]
+pub struct MigrateConfig<'info> {
+    // The authority is checked once the config can be deserialized
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// CHECK: Config in an older layout, deserialized once it has the current size
+    #[account(
+        mut,
+        owner = crate::ID,
+        seeds = [Config::SEED],
+        bump,
+    )]
+    pub config: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn migrate_config(ctx: Context<MigrateConfig>) -> Result<()> {
+    let config_info = ctx.accounts.config.to_account_info();
+    let old_len = config_info.data_len();
+
+    // Fields missing from the old layout read as zero
+    grow_account(
+        &config_info,
+        &ctx.accounts.authority,
+        &ctx.accounts.system_program,
+        8 + Config::INIT_SPACE,
+    )?;
+    let mut config = Config::try_deserialize(&mut &config_info.try_borrow_data()?[..])?;
+    require_keys_eq!(
+        config.authority,
+        ctx.accounts.authority.key(),
+        ErrorCode::InvalidAuthority
+    );
+    require!(config.version < Config::VERSION, ErrorCode::AlreadyMigrated);
+
+    // Configs created before the operational roles get the initialize defaults
+    if old_len == Config::LEGACY_LEN {
+        config.fee_manager = config.authority;
+        config.pause_guardian = config.authority;
+        config.pool_admin = config.authority;
+        config.rewards_admin = config.authority;
+        config.max_pool_fee_bp = FEE_DENOMINATOR as u16;
+    }
+    // Configs created before the pool creation admin read it as zero, like initialize
+    // it starts as the authority
+    if config.pool_creation_admin == Pubkey::default() {
+        config.pool_creation_admin = config.authority;
+    }
+    // Configs created before the fee split pay everything to the fee recipient
+    if config.fee_recipient_count == 0 {
+        config.set_fee_recipients(&[FeeRecipient {
+            recipient: config.protocol_fee_recipient,
+            weight_bp: FEE_DENOMINATOR as u16,
+        }]);
+    }
+    config.version = Config::VERSION;
+    config.try_serialize(&mut &mut config_info.try_borrow_mut_data()?[..])?;
+
+    Ok(())
+}
@@ -0,0 +1,53 @@
+use crate::error::ErrorCode;
+use crate::helpers::migration::grow_account;
+use crate::state::{Config, Pool};
+use anchor_lang::prelude::*;
+
+// Moves a pool written by an older layout to the current version in place. The
+// pool keeps its address, vaults and LP mint
+#[derive(Accounts)]
+pub struct MigratePool<'info> {
+    // CHECK: The signer is the pool admin or the authority of the program
+    #[account(
+        mut,
+        constraint = config.is_pool_admin(&authority.key()) @ ErrorCode::NotPoolAdmin,
+    )]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [Config::SEED],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, Config>,
+
+    /// CHECK: Pool in an older layout, deserialized once it has the current size
+    #[account(mut, owner = crate::ID)]
+    pub pool: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn migrate_pool(ctx: Context<MigratePool>) -> Result<()> {
+    let pool_info = ctx.accounts.pool.to_account_info();
+    let old_len = pool_info.data_len();
+
+    // Fields missing from the old layout read as zero
+    grow_account(
+        &pool_info,
+        &ctx.accounts.authority,
+        &ctx.accounts.system_program,
+        8 + Pool::INIT_SPACE,
+    )?;
+    let mut pool = Pool::try_deserialize(&mut &pool_info.try_borrow_data()?[..])?;
+    require!(pool.version < Pool::VERSION, ErrorCode::AlreadyMigrated);
+
+    // Pools created before the pool index have their fee as index, the creator
+    // is unknown so only the pool admin can close them
+    if old_len == Pool::LEGACY_LEN {
+        pool.fee_bp = pool.index;
+    }
+    pool.version = Pool::VERSION;
+    pool.try_serialize(&mut &mut pool_info.try_borrow_mut_data()?[..])?;
+
+    Ok(())
+}
@@ -4,9 +4,48 @@ pub use initialize::*;
 pub mod update_config;
 pub use update_config::*;
 
+pub mod queue_config_change;
+pub use queue_config_change::*;
+
+pub mod execute_config_change;
+pub use execute_config_change::*;
+
+pub mod cancel_config_change;
+pub use cancel_config_change::*;
+
+pub mod update_roles;
+pub use update_roles::*;
+
+pub mod set_paused;
+pub use set_paused::*;
+
+pub mod migrate_config;
+pub use migrate_config::*;
+
 pub mod create_pool;
 pub use create_pool::*;
 
+pub mod update_pool;
+pub use update_pool::*;
+
+pub mod close_pool;
+pub use close_pool::*;
+
+pub mod migrate_pool;
+pub use migrate_pool::*;
+
+pub mod register_pool;
+pub use register_pool::*;
+
+pub mod set_pool_creation_mode;
+pub use set_pool_creation_mode::*;
+
+pub mod add_pool_creator;
+pub use add_pool_creator::*;
+
+pub mod remove_pool_creator;
+pub use remove_pool_creator::*;
+
 pub mod add_liquidity;
 pub use add_liquidity::*;
 
@@ -15,3 +54,33 @@ pub use remove_liquidity::*;
 
 pub mod swap;
 pub use swap::*;
+
+pub mod flash_loan;
+pub use flash_loan::*;
+
+pub mod flash_repay;
+pub use flash_repay::*;
+
+pub mod lock_lp;
+pub use lock_lp::*;
+
+pub mod unlock_lp;
+pub use unlock_lp::*;
+
+pub mod set_early_exit_penalty;
+pub use set_early_exit_penalty::*;
+
+pub mod claim_fees;
+pub use claim_fees::*;
+
+pub mod set_max_creator_fee;
+pub use set_max_creator_fee::*;
+
+pub mod set_creator_fee;
+pub use set_creator_fee::*;
+
+pub mod claim_creator_fees;
+pub use claim_creator_fees::*;
+
+pub mod quote;
+pub use quote::*;
@@ -0,0 +1,89 @@
+use crate::error::ErrorCode;
+use crate::math::FEE_DENOMINATOR;
+use crate::state::{Config, ConfigChange, FeeRecipient, ProtocolFeeMode};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct QueueConfigChange<'info> {
+    // CHECK: The signer is the fee manager or the authority of the program
+    #[account(
+        mut,
+        constraint = config.is_fee_manager(&authority.key()) @ ErrorCode::NotFeeManager,
+    )]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [Config::SEED],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, Config>,
+
+    // Fails if a change is already queued, it has to be executed or cancelled first
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + ConfigChange::INIT_SPACE,
+        seeds = [ConfigChange::SEED],
+        bump,
+    )]
+    pub config_change: Account<'info, ConfigChange>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn queue_config_change(
+    ctx: Context<QueueConfigChange>,
+    protocol_fee_recipient: Option<Pubkey>,
+    protocol_fee_bp: Option<u16>,
+    timelock_delay: Option<i64>,
+    protocol_fee_mode: Option<ProtocolFeeMode>,
+    max_protocol_fee_bp_override: Option<u16>,
+    fee_recipients: Option<Vec<FeeRecipient>>,
+) -> Result<()> {
+    let config = &ctx.accounts.config;
+    let config_change = &mut ctx.accounts.config_change;
+
+    if let Some(protocol_fee_bp) = protocol_fee_bp {
+        require!(
+            protocol_fee_bp as u128 <= FEE_DENOMINATOR,
+            ErrorCode::InvalidProtocolFee
+        );
+    }
+
+    // The recipient is the first entry of the split, only one of them is changed
+    // at a time
+    if let Some(fee_recipients) = &fee_recipients {
+        require!(
+            protocol_fee_recipient.is_none() && Config::is_valid_fee_split(fee_recipients),
+            ErrorCode::InvalidFeeRecipients
+        );
+    }
+
+    if let Some(max_protocol_fee_bp_override) = max_protocol_fee_bp_override {
+        require!(
+            max_protocol_fee_bp_override as u128 <= FEE_DENOMINATOR,
+            ErrorCode::InvalidProtocolFee
+        );
+    }
+
+    if let Some(timelock_delay) = timelock_delay {
+        require!(timelock_delay >= 0, ErrorCode::InvalidTimelockDelay);
+    }
+
+    let now = Clock::get()?.unix_timestamp;
+
+    config_change.protocol_fee_recipient = protocol_fee_recipient;
+    config_change.protocol_fee_bp = protocol_fee_bp;
+    config_change.timelock_delay = timelock_delay;
+    config_change.protocol_fee_mode = protocol_fee_mode;
+    config_change.max_protocol_fee_bp_override = max_protocol_fee_bp_override;
+    config_change.fee_recipients = fee_recipients.unwrap_or_default();
+    config_change.eta = now
+        .checked_add(config.timelock_delay)
+        .ok_or(ErrorCode::Overflow)?;
+    config_change.bump = ctx.bumps.config_change;
+
+    msg!("eta: {}", config_change.eta);
+
+    Ok(())
+}
@@ -0,0 +1,184 @@
+use crate::error::ErrorCode;
+use crate::helpers::limits::check_trade_limits;
+use crate::helpers::protocol_fee::pending_protocol_lp_fee;
+use crate::math;
+use crate::state::{Config, Pool};
+use crate::types::{AddLiquidityQuote, PoolState, RemoveLiquidityQuote, SwapDirection, SwapQuote};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount};
+
+// Read-only view of a pool shared by the quote instructions, nothing is mutated
+// and results are returned with set_return_data
+#[derive(Accounts)]
+pub struct Quote<'info> {
+    #[account(
+        seeds = [
+            Pool::SEED,
+            pool.token_mint_a.key().as_ref(),
+            pool.token_mint_b.key().as_ref(),
+            pool.index.to_le_bytes().as_ref(),
+        ],
+        bump = pool.bump,
+        // Reserves are lent out during a flash loan
+        constraint = !pool.flash_loan_active @ ErrorCode::FlashLoanActive,
+    )]
+    pub pool: Account<'info, Pool>,
+
+    #[account(address = pool.token_vault_a)]
+    pub pool_ata_a: InterfaceAccount<'info, TokenAccount>,
+    #[account(address = pool.token_vault_b)]
+    pub pool_ata_b: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        seeds = [
+            Pool::MINT_SEED,
+            pool.token_mint_a.key().as_ref(),
+            pool.token_mint_b.key().as_ref(),
+            pool.index.to_le_bytes().as_ref(),
+        ],
+        bump,
+    )]
+    pub lp_mint: InterfaceAccount<'info, Mint>,
+
+    // Protocol fee mode, for the LP tokens minted to the fee recipients
+    #[account(
+        seeds = [Config::SEED],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+}
+
+impl Quote<'_> {
+    // Vault balances minus the unclaimed protocol and creator fees
+    fn reserves(&self) -> Result<(u64, u64)> {
+        self.pool
+            .reserves(self.pool_ata_a.amount, self.pool_ata_b.amount)
+    }
+
+    // (reserve_in, reserve_out) for a swap in `direction`
+    fn swap_reserves(&self, direction: SwapDirection) -> Result<(u64, u64)> {
+        let (reserve_a, reserve_b) = self.reserves()?;
+        let (reserve_in, reserve_out) = match direction {
+            SwapDirection::AToB => (reserve_a, reserve_b),
+            SwapDirection::BToA => (reserve_b, reserve_a),
+        };
+        require!(reserve_in > 0 && reserve_out > 0, ErrorCode::EmptyPool);
+        Ok((reserve_in, reserve_out))
+    }
+
+    // LP supply a liquidity change prices against, including the protocol fee
+    // it mints first when the fee is collected as LP tokens
+    fn lp_supply(&self, reserve_a: u64, reserve_b: u64) -> Result<u64> {
+        let supply = self.lp_mint.supply;
+        let protocol_lp_fee =
+            pending_protocol_lp_fee(&self.config, &self.pool, supply, reserve_a, reserve_b)?;
+        Ok(supply
+            .checked_add(protocol_lp_fee)
+            .ok_or(ErrorCode::Overflow)?)
+    }
+}
+
+// Same math as instructions::swap
+pub fn quote_swap(
+    ctx: Context<Quote>,
+    amount_in: u64,
+    direction: SwapDirection,
+) -> Result<SwapQuote> {
+    require!(amount_in > 0, ErrorCode::MustBeGreaterThanZero);
+
+    let (reserve_in, reserve_out) = ctx.accounts.swap_reserves(direction)?;
+    let amounts =
+        math::get_amount_out(amount_in, reserve_in, reserve_out, ctx.accounts.pool.fee_bp)
+            .map_err(ErrorCode::from)?;
+    check_trade_limits(
+        &ctx.accounts.pool,
+        amounts.amount_in,
+        amounts.amount_out,
+        reserve_in,
+        reserve_out,
+    )?;
+
+    Ok(amounts.into())
+}
+
+// Smallest amount_in for which instructions::swap returns at least amount_out
+pub fn quote_swap_exact_out(
+    ctx: Context<Quote>,
+    amount_out: u64,
+    direction: SwapDirection,
+) -> Result<SwapQuote> {
+    require!(amount_out > 0, ErrorCode::MustBeGreaterThanZero);
+
+    let (reserve_in, reserve_out) = ctx.accounts.swap_reserves(direction)?;
+    let amounts = math::get_amount_in(
+        amount_out,
+        reserve_in,
+        reserve_out,
+        ctx.accounts.pool.fee_bp,
+    )
+    .map_err(ErrorCode::from)?;
+    check_trade_limits(
+        &ctx.accounts.pool,
+        amounts.amount_in,
+        amounts.amount_out,
+        reserve_in,
+        reserve_out,
+    )?;
+
+    Ok(amounts.into())
+}
+
+// Same math as instructions::add_liquidity
+pub fn quote_add_liquidity(
+    ctx: Context<Quote>,
+    amount_a_desired: u64,
+    amount_b_desired: u64,
+) -> Result<AddLiquidityQuote> {
+    require!(amount_a_desired > 0, ErrorCode::MustBeGreaterThanZero);
+    require!(amount_b_desired > 0, ErrorCode::MustBeGreaterThanZero);
+
+    let (reserve_a, reserve_b) = ctx.accounts.reserves()?;
+    let lp_supply = ctx.accounts.lp_supply(reserve_a, reserve_b)?;
+
+    let (amount_a, amount_b) =
+        math::quote_deposit(amount_a_desired, amount_b_desired, reserve_a, reserve_b)
+            .map_err(ErrorCode::from)?;
+    let lp_amount = math::lp_to_mint(amount_a, amount_b, reserve_a, reserve_b, lp_supply)
+        .map_err(ErrorCode::from)?;
+
+    Ok(AddLiquidityQuote {
+        amount_a,
+        amount_b,
+        lp_amount,
+    })
+}
+
+// Same math as instructions::remove_liquidity
+pub fn quote_remove_liquidity(ctx: Context<Quote>, lp_amount: u64) -> Result<RemoveLiquidityQuote> {
+    require!(lp_amount > 0, ErrorCode::MustBeGreaterThanZero);
+
+    let (reserve_a, reserve_b) = ctx.accounts.reserves()?;
+    require!(reserve_a > 0 && reserve_b > 0, ErrorCode::EmptyPool);
+    let lp_supply = ctx.accounts.lp_supply(reserve_a, reserve_b)?;
+
+    let (amount_a, amount_b) =
+        math::lp_to_burn(lp_amount, reserve_a, reserve_b, lp_supply).map_err(ErrorCode::from)?;
+
+    Ok(RemoveLiquidityQuote { amount_a, amount_b })
+}
+
+pub fn get_pool_state(ctx: Context<Quote>) -> Result<PoolState> {
+    let pool = &ctx.accounts.pool;
+    let (reserve_a, reserve_b) = ctx.accounts.reserves()?;
+    let lp_supply = ctx.accounts.lp_supply(reserve_a, reserve_b)?;
+
+    Ok(PoolState {
+        token_mint_a: pool.token_mint_a,
+        token_mint_b: pool.token_mint_b,
+        lp_mint: ctx.accounts.lp_mint.key(),
+        reserve_a,
+        reserve_b,
+        lp_supply,
+        fee_bp: pool.fee_bp,
+    })
+}
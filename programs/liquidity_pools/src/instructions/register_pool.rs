@@ -0,0 +1,94 @@
+use crate::helpers::registry::add_to_registry;
+use crate::state::{Config, PairIndex, PairIndexHeader, Pool, PoolRegistry};
+use anchor_lang::prelude::*;
+
+// Adds a pool created before the registry, anyone can pay for it. Registered
+// pools are left as they are
+#[derive(Accounts)]
+pub struct RegisterPool<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [Config::SEED],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+
+    #[account(
+        mut,
+        seeds = [
+            Pool::SEED,
+            pool.token_mint_a.key().as_ref(),
+            pool.token_mint_b.key().as_ref(),
+            pool.index.to_le_bytes().as_ref(),
+        ],
+        bump = pool.bump,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + PoolRegistry::INIT_SPACE,
+        seeds = [PoolRegistry::SEED, config.registry_page().to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub registry: Box<Account<'info, PoolRegistry>>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + PairIndexHeader::INIT_SPACE,
+        seeds = [
+            PairIndexHeader::SEED,
+            pool.token_mint_a.key().as_ref(),
+            pool.token_mint_b.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub pair_index_header: Box<Account<'info, PairIndexHeader>>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + PairIndex::INIT_SPACE,
+        seeds = [
+            PairIndex::SEED,
+            pool.token_mint_a.key().as_ref(),
+            pool.token_mint_b.key().as_ref(),
+            pair_index_header.next_page().to_le_bytes().as_ref(),
+        ],
+        bump,
+    )]
+    pub pair_index: Box<Account<'info, PairIndex>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn register_pool(ctx: Context<RegisterPool>) -> Result<()> {
+    let pool = &mut ctx.accounts.pool;
+
+    let registry = &mut ctx.accounts.registry;
+    registry.page = ctx.accounts.config.registry_page();
+    registry.bump = ctx.bumps.registry;
+    let pair_index_header = &mut ctx.accounts.pair_index_header;
+    pair_index_header.token_mint_a = pool.token_mint_a;
+    pair_index_header.token_mint_b = pool.token_mint_b;
+    pair_index_header.bump = ctx.bumps.pair_index_header;
+    let pair_index = &mut ctx.accounts.pair_index;
+    pair_index.token_mint_a = pool.token_mint_a;
+    pair_index.token_mint_b = pool.token_mint_b;
+    pair_index.page = pair_index_header.next_page();
+    pair_index.bump = ctx.bumps.pair_index;
+    let pool_key = pool.key();
+    add_to_registry(
+        &mut ctx.accounts.config,
+        registry,
+        pair_index_header,
+        pair_index,
+        pool,
+        pool_key,
+    )
+}
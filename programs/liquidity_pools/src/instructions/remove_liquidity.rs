@@ -1,15 +1,17 @@
 use crate::error::ErrorCode as CustomErrorCode;
-use crate::helpers::transfer::{mint_lp_tokens, transfer_token_to_pool};
-use crate::state::Pool;
+use crate::helpers::deadline::check_deadline;
+use crate::helpers::native_sol::{is_native_mint, side_token_account, unwrap_sol, WSOL_SEED};
+use crate::helpers::protocol_fee::{mint_protocol_lp_fee, update_k_last};
+use crate::helpers::transfer::{burn_lp_tokens, transfer_token_from_pool};
+use crate::math;
+use crate::state::{Config, Pool};
 use anchor_lang::prelude::*;
 use anchor_spl::associated_token::AssociatedToken;
 use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
-use spl_math::approximations::sqrt;
-use spl_math::precise_number::PreciseNumber;
 
 #[derive(Accounts)]
 pub struct RemoveLiquidity<'info> {
-    // Provider accounts
+    // Provider accounts, the token account of a side paid in native SOL is left out
     #[account(mut)]
     pub provider: Signer<'info>,
 
@@ -19,23 +21,45 @@ pub struct RemoveLiquidity<'info> {
         associated_token::authority = provider,
         associated_token::token_program = token_program_a,
     )]
-    pub provider_ata_a: InterfaceAccount<'info, TokenAccount>,
+    pub provider_ata_a: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
     #[account(
         mut,
         associated_token::mint = token_mint_b,
         associated_token::authority = provider,
         associated_token::token_program = token_program_b,
     )]
-    pub provider_ata_b: InterfaceAccount<'info, TokenAccount>,
+    pub provider_ata_b: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
     #[account(
-        init_if_needed,
-        payer = provider,
+        mut,
         associated_token::mint = lp_mint,
         associated_token::authority = provider,
         associated_token::token_program = token_program_lp,
     )]
     pub provider_ata_lp: InterfaceAccount<'info, TokenAccount>,
 
+    // Temporary wrapped SOL accounts of the provider for the sides paid in native
+    // SOL, closed again before the instruction returns
+    #[account(
+        init,
+        payer = provider,
+        seeds = [WSOL_SEED, provider.key().as_ref(), token_mint_a.key().as_ref()],
+        bump,
+        token::mint = token_mint_a,
+        token::authority = provider,
+        token::token_program = token_program_a,
+    )]
+    pub wsol_a: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    #[account(
+        init,
+        payer = provider,
+        seeds = [WSOL_SEED, provider.key().as_ref(), token_mint_b.key().as_ref()],
+        bump,
+        token::mint = token_mint_b,
+        token::authority = provider,
+        token::token_program = token_program_b,
+    )]
+    pub wsol_b: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
     // Pool accounts
     #[account(
         mut,
@@ -43,9 +67,10 @@ pub struct RemoveLiquidity<'info> {
             Pool::SEED,
             pool.token_mint_a.key().as_ref(),
             pool.token_mint_b.key().as_ref(),
-            pool.fee_bp.to_le_bytes().as_ref(),
+            pool.index.to_le_bytes().as_ref(),
         ],
         bump = pool.bump,
+        constraint = !pool.flash_loan_active @ CustomErrorCode::FlashLoanActive,
     )]
     pub pool: Box<Account<'info, Pool>>,
 
@@ -78,7 +103,7 @@ pub struct RemoveLiquidity<'info> {
             Pool::MINT_SEED,
             pool.token_mint_a.key().as_ref(),
             pool.token_mint_b.key().as_ref(),
-            pool.fee_bp.to_le_bytes().as_ref(),
+            pool.index.to_le_bytes().as_ref(),
         ],
         bump,
     )]
@@ -91,17 +116,26 @@ pub struct RemoveLiquidity<'info> {
     pub token_program_lp: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
     pub associated_token_program: Program<'info, AssociatedToken>,
+
+    // Protocol fee mode and fee split. When the protocol fee is minted as LP
+    // tokens the LP token accounts of the fee recipients follow as remaining
+    // accounts, in fee split order
+    #[account(
+        seeds = [Config::SEED],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
 }
 
-pub fn remove_liquidity(
-    ctx: Context<RemoveLiquidity>,
+pub fn remove_liquidity<'info>(
+    ctx: Context<'_, '_, 'info, 'info, RemoveLiquidity<'info>>,
     lp_amount: u64,
     amount_a_min: u64,
     amount_b_min: u64,
+    deadline: Option<i64>,
+    native_sol: bool,
 ) -> Result<()> {
     let provider = &mut ctx.accounts.provider;
-    let provider_ata_a = &mut ctx.accounts.provider_ata_a;
-    let provider_ata_b = &mut ctx.accounts.provider_ata_b;
     let provider_ata_lp = &mut ctx.accounts.provider_ata_lp;
 
     let token_mint_a = &mut ctx.accounts.token_mint_a;
@@ -116,17 +150,101 @@ pub fn remove_liquidity(
     let token_program_b = &mut ctx.accounts.token_program_b;
     let token_program_lp = &mut ctx.accounts.token_program_lp;
 
+    let config = &ctx.accounts.config;
+
     // Validate amounts
+    check_deadline(deadline)?;
     require!(lp_amount > 0, CustomErrorCode::MustBeGreaterThanZero);
     require!(amount_a_min > 0, CustomErrorCode::MustBeGreaterThanZero);
     require!(amount_b_min > 0, CustomErrorCode::MustBeGreaterThanZero);
 
+    // Native SOL is paid out to the provider lamports by closing the temporary
+    // wrapped SOL account it is withdrawn to
+    let unwraps_a = native_sol && is_native_mint(&token_mint_a.key());
+    let unwraps_b = native_sol && is_native_mint(&token_mint_b.key());
+    require!(
+        !native_sol || unwraps_a || unwraps_b,
+        CustomErrorCode::NotNativeMintPool
+    );
+    let provider_ata_a = side_token_account(
+        unwraps_a,
+        &mut ctx.accounts.wsol_a,
+        &mut ctx.accounts.provider_ata_a,
+    )?;
+    let provider_ata_b = side_token_account(
+        unwraps_b,
+        &mut ctx.accounts.wsol_b,
+        &mut ctx.accounts.provider_ata_b,
+    )?;
+
     // Get current pool reserves
-    let reserve_a = pool_ata_a.amount;
-    let reserve_b = pool_ata_b.amount;
+    let (reserve_a, reserve_b) = pool.reserves(pool_ata_a.amount, pool_ata_b.amount)?;
 
     // Check pool has liquidity
     require!(reserve_a > 0 && reserve_b > 0, CustomErrorCode::EmptyPool);
 
+    // Protocol share of the fees earned since the last liquidity change, minted
+    // before pricing the withdrawal so the provider does not keep it
+    let lp_supply = mint_protocol_lp_fee(
+        config,
+        pool,
+        lp_mint,
+        ctx.remaining_accounts,
+        token_program_lp,
+        reserve_a,
+        reserve_b,
+    )?;
+
+    // Tokens owed are proportional to the share of LP supply burned (rounded down in favor of the pool)
+    // https://github.com/Uniswap/v2-core/blob/master/contracts/UniswapV2Pair.sol#L134
+    let (amount_a, amount_b) = math::lp_to_burn(lp_amount, reserve_a, reserve_b, lp_supply)
+        .map_err(CustomErrorCode::from)?;
+
+    require!(
+        amount_a >= amount_a_min,
+        CustomErrorCode::InsufficientAAmount
+    );
+    require!(
+        amount_b >= amount_b_min,
+        CustomErrorCode::InsufficientBAmount
+    );
+
+    // Burn the provider LP tokens
+    burn_lp_tokens(
+        provider,
+        lp_mint,
+        provider_ata_lp,
+        lp_amount,
+        token_program_lp,
+    )?;
+
+    // Transfer token A to provider
+    transfer_token_from_pool(
+        pool,
+        token_mint_a,
+        pool_ata_a,
+        provider_ata_a,
+        token_program_a,
+        amount_a,
+    )?;
+    if unwraps_a {
+        unwrap_sol(provider, provider_ata_a, token_program_a)?;
+    }
+
+    // Transfer token B to provider
+    transfer_token_from_pool(
+        pool,
+        token_mint_b,
+        pool_ata_b,
+        provider_ata_b,
+        token_program_b,
+        amount_b,
+    )?;
+    if unwraps_b {
+        unwrap_sol(provider, provider_ata_b, token_program_b)?;
+    }
+
+    update_k_last(config, pool, reserve_a - amount_a, reserve_b - amount_b);
+
     Ok(())
 }
@@ -0,0 +1,32 @@
+use crate::error::ErrorCode;
+use crate::state::{Config, PoolCreator};
+use anchor_lang::prelude::*;
+
+// Pools already created by the creator are not affected
+#[derive(Accounts)]
+pub struct RemovePoolCreator<'info> {
+    // CHECK: The signer is the pool creation admin or the authority of the program
+    #[account(
+        mut,
+        constraint = config.is_pool_creation_admin(&authority.key()) @ ErrorCode::NotPoolCreationAdmin,
+    )]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [Config::SEED],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        close = authority,
+        seeds = [PoolCreator::SEED, pool_creator.creator.as_ref()],
+        bump = pool_creator.bump,
+    )]
+    pub pool_creator: Account<'info, PoolCreator>,
+}
+
+pub fn remove_pool_creator(_ctx: Context<RemovePoolCreator>) -> Result<()> {
+    Ok(())
+}
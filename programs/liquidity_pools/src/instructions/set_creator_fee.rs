@@ -0,0 +1,40 @@
+use crate::error::ErrorCode;
+use crate::state::{Config, Pool};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetCreatorFee<'info> {
+    #[account(address = pool.creator @ ErrorCode::InvalidAuthority)]
+    pub creator: Signer<'info>,
+
+    #[account(
+        seeds = [Config::SEED],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        seeds = [
+            Pool::SEED,
+            pool.token_mint_a.key().as_ref(),
+            pool.token_mint_b.key().as_ref(),
+            pool.index.to_le_bytes().as_ref(),
+        ],
+        bump = pool.bump,
+    )]
+    pub pool: Account<'info, Pool>,
+}
+
+pub fn set_creator_fee(ctx: Context<SetCreatorFee>, creator_fee_bp: u16) -> Result<()> {
+    require!(
+        creator_fee_bp <= ctx.accounts.config.max_creator_fee_bp,
+        ErrorCode::InvalidCreatorFee
+    );
+
+    ctx.accounts.pool.creator_fee_bp = creator_fee_bp;
+
+    msg!("creator_fee_bp: {}", creator_fee_bp);
+
+    Ok(())
+}
@@ -0,0 +1,36 @@
+use crate::error::ErrorCode;
+use crate::math::FEE_DENOMINATOR;
+use crate::state::Config;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetEarlyExitPenalty<'info> {
+    // CHECK: The signer is the rewards admin or the authority of the program
+    #[account(
+        constraint = config.is_rewards_admin(&authority.key()) @ ErrorCode::NotRewardsAdmin,
+    )]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [Config::SEED],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, Config>,
+}
+
+pub fn set_early_exit_penalty(
+    ctx: Context<SetEarlyExitPenalty>,
+    early_exit_penalty_bp: u16,
+) -> Result<()> {
+    require!(
+        early_exit_penalty_bp as u128 <= FEE_DENOMINATOR,
+        ErrorCode::InvalidEarlyExitPenalty
+    );
+
+    ctx.accounts.config.early_exit_penalty_bp = early_exit_penalty_bp;
+
+    msg!("early_exit_penalty_bp: {}", early_exit_penalty_bp);
+
+    Ok(())
+}
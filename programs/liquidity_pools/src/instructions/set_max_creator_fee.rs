@@ -0,0 +1,34 @@
+use crate::error::ErrorCode;
+use crate::math::FEE_DENOMINATOR;
+use crate::state::Config;
+use anchor_lang::prelude::*;
+
+// Pools keep their creator fee when the bound is lowered, it applies to the next change
+#[derive(Accounts)]
+pub struct SetMaxCreatorFee<'info> {
+    // CHECK: The signer is the fee manager or the authority of the program
+    #[account(
+        constraint = config.is_fee_manager(&authority.key()) @ ErrorCode::NotFeeManager,
+    )]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [Config::SEED],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, Config>,
+}
+
+pub fn set_max_creator_fee(ctx: Context<SetMaxCreatorFee>, max_creator_fee_bp: u16) -> Result<()> {
+    require!(
+        max_creator_fee_bp as u128 <= FEE_DENOMINATOR,
+        ErrorCode::InvalidCreatorFee
+    );
+
+    ctx.accounts.config.max_creator_fee_bp = max_creator_fee_bp;
+
+    msg!("max_creator_fee_bp: {}", max_creator_fee_bp);
+
+    Ok(())
+}
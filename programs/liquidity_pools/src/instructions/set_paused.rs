@@ -0,0 +1,27 @@
+use crate::error::ErrorCode;
+use crate::state::Config;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetPaused<'info> {
+    // CHECK: The signer is the pause guardian or the authority of the program
+    #[account(
+        constraint = config.is_pause_guardian(&authority.key()) @ ErrorCode::NotPauseGuardian,
+    )]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [Config::SEED],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, Config>,
+}
+
+pub fn set_paused(ctx: Context<SetPaused>, paused: bool) -> Result<()> {
+    ctx.accounts.config.paused = paused;
+
+    msg!("paused: {}", paused);
+
+    Ok(())
+}
@@ -0,0 +1,30 @@
+use crate::error::ErrorCode;
+use crate::state::{Config, PoolCreationMode};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetPoolCreationMode<'info> {
+    // CHECK: The signer is the pool creation admin or the authority of the program
+    #[account(
+        constraint = config.is_pool_creation_admin(&authority.key()) @ ErrorCode::NotPoolCreationAdmin,
+    )]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [Config::SEED],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, Config>,
+}
+
+pub fn set_pool_creation_mode(
+    ctx: Context<SetPoolCreationMode>,
+    pool_creation_mode: PoolCreationMode,
+) -> Result<()> {
+    ctx.accounts.config.pool_creation_mode = pool_creation_mode;
+
+    msg!("pool_creation_mode: {:?}", pool_creation_mode);
+
+    Ok(())
+}
@@ -1,11 +1,16 @@
 use crate::error::ErrorCode;
-use crate::helpers::fees::Fees;
+use crate::events::SwapEvent;
+use crate::helpers::deadline::check_deadline;
+use crate::helpers::limits::check_trade_limits;
+use crate::helpers::native_sol::{
+    is_native_mint, side_token_account, unwrap_sol, wrap_sol, WSOL_SEED,
+};
 use crate::helpers::transfer::{transfer_token_from_pool, transfer_token_to_pool};
-use crate::state::Pool;
+use crate::math;
+use crate::state::{Config, Pool};
 use anchor_lang::prelude::*;
 use anchor_spl::associated_token::AssociatedToken;
 use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
-use spl_math::precise_number::PreciseNumber;
 
 #[derive(Accounts)]
 pub struct Swap<'info> {
@@ -18,39 +23,77 @@ pub struct Swap<'info> {
             Pool::SEED,
             pool.token_mint_a.key().as_ref(),
             pool.token_mint_b.key().as_ref(),
-            pool.fee_bp.to_le_bytes().as_ref(),
+            pool.index.to_le_bytes().as_ref(),
         ],
         bump = pool.bump,
+        constraint = !pool.flash_loan_active @ ErrorCode::FlashLoanActive,
     )]
     pub pool: Account<'info, Pool>,
 
-    // Payer accounts
+    // Payer accounts, left out when the input is paid in native SOL
     #[account(
         mut,
-        constraint = payer_ata_input.mint == pool.token_mint_a || payer_ata_input.mint == pool.token_mint_b,
-        constraint = payer_ata_input.mint != payer_ata_output.mint,
+        constraint = payer_ata_input.mint == token_mint_input.key(),
     )]
-    pub payer_ata_input: InterfaceAccount<'info, TokenAccount>,
+    pub payer_ata_input: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    /// CHECK: Any owner, the output goes to its associated token account
+    pub recipient: UncheckedAccount<'info>,
+    // Created by the payer when the recipient does not hold the output token yet,
+    // left out when the output is paid out in native SOL
     #[account(
-        mut,
-        constraint = payer_ata_output.mint == pool.token_mint_a || payer_ata_output.mint == pool.token_mint_b,
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = token_mint_output,
+        associated_token::authority = recipient,
+        associated_token::token_program = token_program_output,
+    )]
+    pub recipient_ata_output: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    // Temporary wrapped SOL accounts of the payer for a native SOL input or output,
+    // closed again before the swap returns
+    #[account(
+        init,
+        payer = payer,
+        seeds = [WSOL_SEED, payer.key().as_ref(), token_mint_input.key().as_ref()],
+        bump,
+        token::mint = token_mint_input,
+        token::authority = payer,
+        token::token_program = token_program_input,
     )]
-    pub payer_ata_output: InterfaceAccount<'info, TokenAccount>,
+    pub wsol_input: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    #[account(
+        init,
+        payer = payer,
+        seeds = [WSOL_SEED, payer.key().as_ref(), token_mint_output.key().as_ref()],
+        bump,
+        token::mint = token_mint_output,
+        token::authority = payer,
+        token::token_program = token_program_output,
+    )]
+    pub wsol_output: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
 
     // Pool accounts
     #[account(
         mut,
-        constraint = pool_ata_input.mint == payer_ata_input.mint,
+        address = pool.vault(&token_mint_input.key()),
     )]
     pub pool_ata_input: InterfaceAccount<'info, TokenAccount>,
     #[account(
         mut,
-        constraint = pool_ata_output.mint == payer_ata_output.mint,
+        address = pool.vault(&token_mint_output.key()),
     )]
     pub pool_ata_output: InterfaceAccount<'info, TokenAccount>,
 
     // Token mints for transfer_checked calls
+    #[account(
+        constraint = token_mint_input.key() == pool.token_mint_a || token_mint_input.key() == pool.token_mint_b,
+        constraint = token_mint_input.key() != token_mint_output.key(),
+    )]
     pub token_mint_input: InterfaceAccount<'info, Mint>,
+    #[account(
+        constraint = token_mint_output.key() == pool.token_mint_a || token_mint_output.key() == pool.token_mint_b,
+    )]
     pub token_mint_output: InterfaceAccount<'info, Mint>,
 
     // Separate token programs for each token (support for tokens with different token programs)
@@ -61,65 +104,142 @@ pub struct Swap<'info> {
 
     pub system_program: Program<'info, System>,
     pub associated_token_program: Program<'info, AssociatedToken>,
+
+    // Protocol fee, referral fee cap and pause flag
+    #[account(
+        seeds = [Config::SEED],
+        bump = config.bump,
+        constraint = !config.paused @ ErrorCode::Paused,
+    )]
+    pub config: Box<Account<'info, Config>>,
+    #[account(
+        mut,
+        constraint = referrer_ata.mint == token_mint_input.key() @ ErrorCode::InvalidReferrer,
+    )]
+    pub referrer_ata: Option<InterfaceAccount<'info, TokenAccount>>,
 }
 
-pub fn swap(ctx: Context<Swap>, amount_in: u64, min_amount_out: u64) -> Result<()> {
-    let pool = &ctx.accounts.pool;
+pub fn swap(
+    ctx: Context<Swap>,
+    amount_in: u64,
+    min_amount_out: u64,
+    deadline: Option<i64>,
+    referral_fee_bp: u16,
+    native_sol: bool,
+) -> Result<()> {
+    let pool = &mut ctx.accounts.pool;
+    let config = &ctx.accounts.config;
     let payer = &mut ctx.accounts.payer;
-    let payer_ata_input = &mut ctx.accounts.payer_ata_input;
-    let payer_ata_output = &mut ctx.accounts.payer_ata_output;
+    let recipient = &ctx.accounts.recipient;
     let pool_ata_input = &mut ctx.accounts.pool_ata_input;
     let pool_ata_output = &mut ctx.accounts.pool_ata_output;
     let token_mint_input = &mut ctx.accounts.token_mint_input;
     let token_mint_output = &mut ctx.accounts.token_mint_output;
     let token_program_input = &mut ctx.accounts.token_program_input;
     let token_program_output = &mut ctx.accounts.token_program_output;
+    let referrer_ata = &mut ctx.accounts.referrer_ata;
+    let system_program = &ctx.accounts.system_program;
 
     // Validate inputs
+    check_deadline(deadline)?;
     require!(amount_in > 0, ErrorCode::MustBeGreaterThanZero);
     require!(min_amount_out > 0, ErrorCode::MustBeGreaterThanZero);
 
+    // Native SOL is paid in from the payer lamports, or paid out to them by
+    // closing the temporary wrapped SOL account, which only its owner can sign for
+    let wraps_input = native_sol && is_native_mint(&token_mint_input.key());
+    let unwraps_output = native_sol && is_native_mint(&token_mint_output.key());
+    require!(
+        !native_sol || wraps_input || unwraps_output,
+        ErrorCode::NotNativeMintPool
+    );
+    require!(
+        !unwraps_output || recipient.key() == payer.key(),
+        ErrorCode::NativeSolRecipient
+    );
+    let payer_ata_input = side_token_account(
+        wraps_input,
+        &mut ctx.accounts.wsol_input,
+        &mut ctx.accounts.payer_ata_input,
+    )?;
+    let recipient_ata_output = side_token_account(
+        unwraps_output,
+        &mut ctx.accounts.wsol_output,
+        &mut ctx.accounts.recipient_ata_output,
+    )?;
+
     // Get current pool reserves
-    let reserve_in = pool_ata_input.amount;
-    let reserve_out = pool_ata_output.amount;
+    let reserve_in = pool.reserve(&token_mint_input.key(), pool_ata_input.amount)?;
+    let reserve_out = pool.reserve(&token_mint_output.key(), pool_ata_output.amount)?;
 
     // Check pool has liquidity
     require!(reserve_in > 0 && reserve_out > 0, ErrorCode::EmptyPool);
 
-    // Calculate fee amount and amount after fee
-    let fee_amount = Fees::calculate_fee_tokens(amount_in as u128, pool.fee_bp)? as u64;
-    let amount_in_after_fee = amount_in - fee_amount;
+    // Constant Product Formula: amount_out = (amount_in_after_fee * reserve_out) / (reserve_in + amount_in_after_fee)
+    let math::SwapAmounts {
+        amount_out,
+        fee_amount,
+        ..
+    } = math::get_amount_out(amount_in, reserve_in, reserve_out, pool.fee_bp)
+        .map_err(ErrorCode::from)?;
 
     msg!("amount_in: {}", amount_in);
     msg!("fee_amount: {}", fee_amount);
-    msg!("amount_in_after_fee: {}", amount_in_after_fee);
+    msg!("amount_out: {}", amount_out);
 
-    // Constant Product Formula: amount_out = (amount_in_after_fee * reserve_out) / (reserve_in + amount_in_after_fee)
-    let amount_in_after_fee_precise = PreciseNumber::new(amount_in_after_fee as u128).unwrap();
-    let reserve_in_precise = PreciseNumber::new(reserve_in as u128).unwrap();
-    let reserve_out_precise = PreciseNumber::new(reserve_out as u128).unwrap();
-
-    let numerator = amount_in_after_fee_precise
-        .checked_mul(&reserve_out_precise)
-        .unwrap();
-    let denominator = reserve_in_precise
-        .checked_add(&amount_in_after_fee_precise)
-        .unwrap();
-    let amount_out = numerator
-        .checked_div(&denominator)
-        .unwrap()
-        .to_imprecise()
-        .unwrap() as u64;
+    // Check the pool size and price impact limits
+    check_trade_limits(pool, amount_in, amount_out, reserve_in, reserve_out)?;
 
-    msg!("amount_out: {}", amount_out);
     // Check slippage protection
     require!(
         amount_out >= min_amount_out,
         ErrorCode::InsufficientOutputAmount
     );
 
-    // Ensure we don't drain the pool
-    require!(amount_out < reserve_out, ErrorCode::InsufficientLiquidity);
+    // Referral share of the fee, paid by the payer instead of going to the pool
+    let referral_fee = match referrer_ata {
+        Some(_) => {
+            require!(
+                referral_fee_bp <= config.max_referral_fee_bp,
+                ErrorCode::ReferralFeeTooHigh
+            );
+            math::split_fee(fee_amount, referral_fee_bp)
+                .map_err(ErrorCode::from)?
+                .share
+        }
+        None => 0,
+    };
+
+    // Protocol share of the remaining fee, kept in the pool vault until claim_fees.
+    // When it is minted as LP tokens the whole fee stays in the reserves instead
+    let protocol_fee = if pool.mints_protocol_fee(config) {
+        0
+    } else {
+        math::split_fee(fee_amount - referral_fee, pool.protocol_fee_bp(config))
+            .map_err(ErrorCode::from)?
+            .share
+    };
+    pool.accrue_protocol_fee(&token_mint_input.key(), protocol_fee)?;
+
+    // Creator share of what is left for liquidity providers, kept in the pool vault
+    // until claim_creator_fees
+    let creator_fee = math::split_fee(
+        fee_amount - referral_fee - protocol_fee,
+        pool.creator_fee_bp,
+    )
+    .map_err(ErrorCode::from)?
+    .share;
+    pool.accrue_creator_fee(&token_mint_input.key(), creator_fee)?;
+
+    if wraps_input {
+        wrap_sol(
+            payer,
+            payer_ata_input,
+            token_program_input,
+            system_program,
+            amount_in,
+        )?;
+    }
 
     // Transfer tokens from payer to pool
     transfer_token_to_pool(
@@ -128,18 +248,53 @@ pub fn swap(ctx: Context<Swap>, amount_in: u64, min_amount_out: u64) -> Result<(
         payer_ata_input,
         pool_ata_input,
         token_program_input,
-        amount_in,
+        amount_in - referral_fee,
     )?;
 
-    // Transfer tokens from pool to payer
+    // Transfer the referral fee from payer to referrer
+    if let Some(referrer_ata) = referrer_ata {
+        if referral_fee > 0 {
+            transfer_token_to_pool(
+                payer,
+                token_mint_input,
+                payer_ata_input,
+                referrer_ata,
+                token_program_input,
+                referral_fee,
+            )?;
+        }
+    }
+
+    // Transfer tokens from pool to recipient
     transfer_token_from_pool(
         pool,
         token_mint_output,
         pool_ata_output,
-        payer_ata_output,
+        recipient_ata_output,
         token_program_output,
         amount_out,
     )?;
 
+    if wraps_input {
+        unwrap_sol(payer, payer_ata_input, token_program_input)?;
+    }
+    if unwraps_output {
+        unwrap_sol(payer, recipient_ata_output, token_program_output)?;
+    }
+
+    emit!(SwapEvent {
+        pool: pool.key(),
+        payer: payer.key(),
+        recipient: recipient.key(),
+        token_mint_input: token_mint_input.key(),
+        amount_in,
+        amount_out,
+        fee_amount,
+        protocol_fee,
+        creator_fee,
+        referrer: referrer_ata.as_ref().map(|referrer_ata| referrer_ata.key()),
+        referral_fee,
+    });
+
     Ok(())
 }
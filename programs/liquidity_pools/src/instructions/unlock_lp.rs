@@ -0,0 +1,149 @@
+use crate::error::ErrorCode;
+use crate::helpers::fees::Fees;
+use crate::helpers::protocol_fee::{fee_recipient_account, weighted_share};
+use crate::helpers::transfer::transfer_token_from_pool;
+use crate::state::{Config, LockPosition, Pool};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+#[derive(Accounts)]
+pub struct UnlockLp<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    #[account(
+        mut,
+        associated_token::mint = lp_mint,
+        associated_token::authority = owner,
+        associated_token::token_program = token_program_lp,
+    )]
+    pub owner_ata_lp: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        seeds = [Config::SEED],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+
+    #[account(
+        mut,
+        seeds = [
+            Pool::SEED,
+            pool.token_mint_a.key().as_ref(),
+            pool.token_mint_b.key().as_ref(),
+            pool.index.to_le_bytes().as_ref(),
+        ],
+        bump = pool.bump,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    #[account(
+        seeds = [
+            Pool::MINT_SEED,
+            pool.token_mint_a.key().as_ref(),
+            pool.token_mint_b.key().as_ref(),
+            pool.index.to_le_bytes().as_ref(),
+        ],
+        bump,
+    )]
+    pub lp_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        associated_token::mint = lp_mint,
+        associated_token::authority = pool,
+        associated_token::token_program = token_program_lp,
+    )]
+    pub lock_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        close = owner,
+        seeds = [
+            LockPosition::SEED,
+            pool.key().as_ref(),
+            owner.key().as_ref(),
+            lock_position.id.to_le_bytes().as_ref(),
+        ],
+        bump = lock_position.bump,
+    )]
+    pub lock_position: Account<'info, LockPosition>,
+
+    pub token_program_lp: Interface<'info, TokenInterface>,
+}
+
+// An early exit splits the penalty between the LP token accounts of the fee recipients,
+// passed as remaining accounts in the order of Config::fee_recipients
+pub fn unlock_lp<'info>(ctx: Context<'_, '_, 'info, 'info, UnlockLp<'info>>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let amount = ctx.accounts.lock_position.amount;
+    let boosted_amount = ctx.accounts.lock_position.boosted_amount;
+
+    // Early exits are only possible when the position was opened with a penalty
+    let penalty = if now < ctx.accounts.lock_position.unlock_at {
+        let early_exit_penalty_bp = ctx.accounts.lock_position.early_exit_penalty_bp;
+        require!(early_exit_penalty_bp > 0, ErrorCode::LockNotExpired);
+        Fees::calculate_fee_tokens(amount as u128, early_exit_penalty_bp)? as u64
+    } else {
+        0
+    };
+
+    let pool = &mut ctx.accounts.pool;
+    pool.total_locked_lp = pool
+        .total_locked_lp
+        .checked_sub(amount)
+        .ok_or(ErrorCode::Underflow)?;
+    pool.total_boosted_lp = pool
+        .total_boosted_lp
+        .checked_sub(boosted_amount)
+        .ok_or(ErrorCode::Underflow)?;
+
+    if penalty > 0 {
+        let recipients = ctx.accounts.config.active_fee_recipients();
+        require!(
+            ctx.remaining_accounts.len() == recipients.len(),
+            ErrorCode::InvalidFeeRecipient
+        );
+
+        let lp_mint_key = ctx.accounts.lp_mint.key();
+        let mut remaining = penalty;
+        for (i, (entry, info)) in recipients.iter().zip(ctx.remaining_accounts).enumerate() {
+            let mut recipient_ata_lp = fee_recipient_account(info, &lp_mint_key, &entry.recipient)?;
+
+            // The last recipient takes the rounding dust
+            let share = if i + 1 == recipients.len() {
+                remaining
+            } else {
+                weighted_share(penalty, entry.weight_bp)?
+            };
+            remaining -= share;
+
+            if share > 0 {
+                transfer_token_from_pool(
+                    pool,
+                    &ctx.accounts.lp_mint,
+                    &mut ctx.accounts.lock_vault,
+                    &mut recipient_ata_lp,
+                    &ctx.accounts.token_program_lp,
+                    share,
+                )?;
+            }
+        }
+    }
+
+    let withdrawn = amount - penalty;
+    if withdrawn > 0 {
+        transfer_token_from_pool(
+            pool,
+            &ctx.accounts.lp_mint,
+            &mut ctx.accounts.lock_vault,
+            &mut ctx.accounts.owner_ata_lp,
+            &ctx.accounts.token_program_lp,
+            withdrawn,
+        )?;
+    }
+
+    msg!("withdrawn: {}", withdrawn);
+    msg!("penalty: {}", penalty);
+
+    Ok(())
+}
@@ -1,13 +1,15 @@
 use crate::error::ErrorCode;
-use crate::state::config::*;
+use crate::math::FEE_DENOMINATOR;
+use crate::state::Config;
 use anchor_lang::prelude::*;
 
+// Protocol fee, recipient and fee split changes go through queue_config_change
 #[derive(Accounts)]
 pub struct UpdateConfig<'info> {
-    // CHECK: The signer is the authority of the program
+    // CHECK: The signer is the fee manager or the authority of the program
     #[account(
         mut,
-        constraint = authority.key() == config.authority @ ErrorCode::InvalidAuthority,
+        constraint = config.is_fee_manager(&authority.key()) @ ErrorCode::NotFeeManager,
     )]
     pub authority: Signer<'info>,
 
@@ -24,17 +26,40 @@ pub struct UpdateConfig<'info> {
 
 pub fn update_config(
     ctx: Context<UpdateConfig>,
-    protocol_fee_recipient: Option<Pubkey>,
-    protocol_fee_bp: Option<u16>,
+    max_referral_fee_bp: Option<u16>,
+    min_pool_fee_bp: Option<u16>,
+    max_pool_fee_bp: Option<u16>,
+    pool_creation_fee: Option<u64>,
+    pool_creation_fee_mint: Option<Pubkey>,
 ) -> Result<()> {
     let config = &mut ctx.accounts.config;
 
-    if let Some(protocol_fee_recipient) = protocol_fee_recipient {
-        config.protocol_fee_recipient = protocol_fee_recipient;
+    if let Some(max_referral_fee_bp) = max_referral_fee_bp {
+        require!(
+            max_referral_fee_bp as u128 <= FEE_DENOMINATOR,
+            ErrorCode::ReferralFeeTooHigh
+        );
+        config.max_referral_fee_bp = max_referral_fee_bp;
     }
 
-    if let Some(protocol_fee_bp) = protocol_fee_bp {
-        config.protocol_fee_bp = protocol_fee_bp;
+    // Existing pools keep their fee, the bounds apply to the next fee change
+    if let Some(min_pool_fee_bp) = min_pool_fee_bp {
+        config.min_pool_fee_bp = min_pool_fee_bp;
+    }
+    if let Some(max_pool_fee_bp) = max_pool_fee_bp {
+        config.max_pool_fee_bp = max_pool_fee_bp;
+    }
+    require!(
+        config.min_pool_fee_bp <= config.max_pool_fee_bp
+            && config.max_pool_fee_bp as u128 <= FEE_DENOMINATOR,
+        ErrorCode::InvalidPoolFeeBounds
+    );
+
+    if let Some(pool_creation_fee) = pool_creation_fee {
+        config.pool_creation_fee = pool_creation_fee;
+    }
+    if let Some(pool_creation_fee_mint) = pool_creation_fee_mint {
+        config.pool_creation_fee_mint = pool_creation_fee_mint;
     }
 
     Ok(())
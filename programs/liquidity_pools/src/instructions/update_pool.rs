@@ -0,0 +1,82 @@
+use crate::error::ErrorCode;
+use crate::math::FEE_DENOMINATOR;
+use crate::state::{Config, Pool};
+use anchor_lang::prelude::*;
+
+// Per-pool settings apply immediately, outside of the config timelock, but only
+// within bounds set by the fee manager: the swap fee within the pool fee bounds
+// (it is paid to the LPs and traders are covered by their minimum output), the
+// protocol fee override up to the timelocked Config::max_protocol_fee_bp_override
+#[derive(Accounts)]
+pub struct UpdatePool<'info> {
+    // CHECK: The signer is the pool admin or the authority of the program
+    #[account(
+        constraint = config.is_pool_admin(&authority.key()) @ ErrorCode::NotPoolAdmin,
+    )]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [Config::SEED],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        seeds = [
+            Pool::SEED,
+            pool.token_mint_a.key().as_ref(),
+            pool.token_mint_b.key().as_ref(),
+            pool.index.to_le_bytes().as_ref(),
+        ],
+        bump = pool.bump,
+        constraint = !pool.flash_loan_active @ ErrorCode::FlashLoanActive,
+    )]
+    pub pool: Account<'info, Pool>,
+}
+
+pub fn update_pool(
+    ctx: Context<UpdatePool>,
+    max_price_impact_bp: Option<u16>,
+    max_trade_size_bp: Option<u16>,
+    protocol_fee_bp_override: Option<Option<u16>>,
+    fee_bp: Option<u16>,
+) -> Result<()> {
+    let config = &ctx.accounts.config;
+    let pool = &mut ctx.accounts.pool;
+
+    if let Some(max_price_impact_bp) = max_price_impact_bp {
+        require!(
+            max_price_impact_bp as u128 <= FEE_DENOMINATOR,
+            ErrorCode::InvalidTradeLimit
+        );
+        pool.max_price_impact_bp = max_price_impact_bp;
+    }
+
+    if let Some(max_trade_size_bp) = max_trade_size_bp {
+        require!(
+            max_trade_size_bp as u128 <= FEE_DENOMINATOR,
+            ErrorCode::InvalidTradeLimit
+        );
+        pool.max_trade_size_bp = max_trade_size_bp;
+    }
+
+    // Some(None) removes the override and falls back to Config::protocol_fee_bp
+    if let Some(protocol_fee_bp_override) = protocol_fee_bp_override {
+        if let Some(protocol_fee_bp) = protocol_fee_bp_override {
+            require!(
+                config.is_valid_protocol_fee_override(protocol_fee_bp),
+                ErrorCode::InvalidProtocolFee
+            );
+        }
+        pool.protocol_fee_bp_override = protocol_fee_bp_override;
+    }
+
+    // The pool address does not depend on the fee, liquidity stays in place
+    if let Some(fee_bp) = fee_bp {
+        require!(config.is_valid_pool_fee(fee_bp), ErrorCode::InvalidPoolFee);
+        pool.fee_bp = fee_bp;
+    }
+
+    Ok(())
+}
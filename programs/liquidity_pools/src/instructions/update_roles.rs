@@ -0,0 +1,52 @@
+use crate::error::ErrorCode;
+use crate::state::Config;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct UpdateRoles<'info> {
+    // CHECK: Only the authority of the program assigns roles
+    #[account(
+        constraint = authority.key() == config.authority @ ErrorCode::InvalidAuthority,
+    )]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [Config::SEED],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, Config>,
+}
+
+pub fn update_roles(
+    ctx: Context<UpdateRoles>,
+    fee_manager: Option<Pubkey>,
+    pause_guardian: Option<Pubkey>,
+    pool_admin: Option<Pubkey>,
+    rewards_admin: Option<Pubkey>,
+    pool_creation_admin: Option<Pubkey>,
+) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+
+    if let Some(fee_manager) = fee_manager {
+        config.fee_manager = fee_manager;
+    }
+
+    if let Some(pause_guardian) = pause_guardian {
+        config.pause_guardian = pause_guardian;
+    }
+
+    if let Some(pool_admin) = pool_admin {
+        config.pool_admin = pool_admin;
+    }
+
+    if let Some(rewards_admin) = rewards_admin {
+        config.rewards_admin = rewards_admin;
+    }
+
+    if let Some(pool_creation_admin) = pool_creation_admin {
+        config.pool_creation_admin = pool_creation_admin;
+    }
+
+    Ok(())
+}
@@ -1,10 +1,16 @@
-mod error;
-mod helpers;
+pub mod error;
+pub mod events;
+pub mod helpers;
 mod instructions;
-mod state;
+pub mod state;
+pub mod types;
+
+pub use liquidity_pools_math as math;
 
 use anchor_lang::prelude::*;
 use instructions::*;
+use state::{FeeRecipient, LockDuration, PoolCreationMode, ProtocolFeeMode};
+use types::*;
 
 declare_id!("DEWi9FJQE9tjqvTxPtLiEQ9yyHT7JnR5FXLA3GMpx3Np");
 
@@ -31,55 +37,265 @@ pub mod liquidity_pools {
 
     pub fn update_config(
         ctx: Context<UpdateConfig>,
+        max_referral_fee_bp: Option<u16>,
+        min_pool_fee_bp: Option<u16>,
+        max_pool_fee_bp: Option<u16>,
+        pool_creation_fee: Option<u64>,
+        pool_creation_fee_mint: Option<Pubkey>,
+    ) -> Result<()> {
+        instructions::update_config(
+            ctx,
+            max_referral_fee_bp,
+            min_pool_fee_bp,
+            max_pool_fee_bp,
+            pool_creation_fee,
+            pool_creation_fee_mint,
+        )
+    }
+
+    pub fn queue_config_change(
+        ctx: Context<QueueConfigChange>,
         protocol_fee_recipient: Option<Pubkey>,
         protocol_fee_bp: Option<u16>,
+        timelock_delay: Option<i64>,
+        protocol_fee_mode: Option<ProtocolFeeMode>,
+        max_protocol_fee_bp_override: Option<u16>,
+        fee_recipients: Option<Vec<FeeRecipient>>,
     ) -> Result<()> {
-        instructions::update_config(ctx, protocol_fee_recipient, protocol_fee_bp)
+        instructions::queue_config_change(
+            ctx,
+            protocol_fee_recipient,
+            protocol_fee_bp,
+            timelock_delay,
+            protocol_fee_mode,
+            max_protocol_fee_bp_override,
+            fee_recipients,
+        )
+    }
+
+    pub fn execute_config_change(ctx: Context<ExecuteConfigChange>) -> Result<()> {
+        instructions::execute_config_change(ctx)
     }
 
-    pub fn create_pool(ctx: Context<CreatePool>, fee_bp: u16) -> Result<()> {
-        instructions::create_pool(ctx, fee_bp)
+    pub fn cancel_config_change(ctx: Context<CancelConfigChange>) -> Result<()> {
+        instructions::cancel_config_change(ctx)
     }
 
-    pub fn add_liquidity(
-        ctx: Context<AddLiquidity>,
+    pub fn update_roles(
+        ctx: Context<UpdateRoles>,
+        fee_manager: Option<Pubkey>,
+        pause_guardian: Option<Pubkey>,
+        pool_admin: Option<Pubkey>,
+        rewards_admin: Option<Pubkey>,
+        pool_creation_admin: Option<Pubkey>,
+    ) -> Result<()> {
+        instructions::update_roles(
+            ctx,
+            fee_manager,
+            pause_guardian,
+            pool_admin,
+            rewards_admin,
+            pool_creation_admin,
+        )
+    }
+
+    pub fn set_paused(ctx: Context<SetPaused>, paused: bool) -> Result<()> {
+        instructions::set_paused(ctx, paused)
+    }
+
+    pub fn migrate_config(ctx: Context<MigrateConfig>) -> Result<()> {
+        instructions::migrate_config(ctx)
+    }
+
+    pub fn set_pool_creation_mode(
+        ctx: Context<SetPoolCreationMode>,
+        pool_creation_mode: PoolCreationMode,
+    ) -> Result<()> {
+        instructions::set_pool_creation_mode(ctx, pool_creation_mode)
+    }
+
+    pub fn add_pool_creator(ctx: Context<AddPoolCreator>, creator: Pubkey) -> Result<()> {
+        instructions::add_pool_creator(ctx, creator)
+    }
+
+    pub fn remove_pool_creator(ctx: Context<RemovePoolCreator>) -> Result<()> {
+        instructions::remove_pool_creator(ctx)
+    }
+
+    pub fn create_pool(ctx: Context<CreatePool>, index: u16, fee_bp: u16) -> Result<()> {
+        instructions::create_pool(ctx, index, fee_bp)
+    }
+
+    pub fn update_pool(
+        ctx: Context<UpdatePool>,
+        max_price_impact_bp: Option<u16>,
+        max_trade_size_bp: Option<u16>,
+        protocol_fee_bp_override: Option<Option<u16>>,
+        fee_bp: Option<u16>,
+    ) -> Result<()> {
+        instructions::update_pool(
+            ctx,
+            max_price_impact_bp,
+            max_trade_size_bp,
+            protocol_fee_bp_override,
+            fee_bp,
+        )
+    }
+
+    pub fn close_pool(ctx: Context<ClosePool>) -> Result<()> {
+        instructions::close_pool(ctx)
+    }
+
+    pub fn migrate_pool(ctx: Context<MigratePool>) -> Result<()> {
+        instructions::migrate_pool(ctx)
+    }
+
+    pub fn register_pool(ctx: Context<RegisterPool>) -> Result<()> {
+        instructions::register_pool(ctx)
+    }
+
+    pub fn add_liquidity<'info>(
+        ctx: Context<'_, '_, 'info, 'info, AddLiquidity<'info>>,
         amount_a: u64,
         amount_b: u64,
         amount_a_min: u64,
         amount_b_min: u64,
+        deadline: Option<i64>,
+        native_sol: bool,
     ) -> Result<()> {
-        instructions::add_liquidity(ctx, amount_a, amount_b, amount_a_min, amount_b_min)
+        instructions::add_liquidity(
+            ctx,
+            amount_a,
+            amount_b,
+            amount_a_min,
+            amount_b_min,
+            deadline,
+            native_sol,
+        )
     }
 
-    pub fn remove_liquidity(_ctx: Context<RemoveLiquidity>) -> Result<()> {
-        Ok(())
+    pub fn remove_liquidity<'info>(
+        ctx: Context<'_, '_, 'info, 'info, RemoveLiquidity<'info>>,
+        lp_amount: u64,
+        amount_a_min: u64,
+        amount_b_min: u64,
+        deadline: Option<i64>,
+        native_sol: bool,
+    ) -> Result<()> {
+        instructions::remove_liquidity(
+            ctx,
+            lp_amount,
+            amount_a_min,
+            amount_b_min,
+            deadline,
+            native_sol,
+        )
     }
 
-    pub fn swap(ctx: Context<Swap>, amount_in: u64, min_amount_out: u64) -> Result<()> {
-        instructions::swap(ctx, amount_in, min_amount_out)
+    pub fn swap(
+        ctx: Context<Swap>,
+        amount_in: u64,
+        min_amount_out: u64,
+        deadline: Option<i64>,
+        referral_fee_bp: u16,
+        native_sol: bool,
+    ) -> Result<()> {
+        instructions::swap(
+            ctx,
+            amount_in,
+            min_amount_out,
+            deadline,
+            referral_fee_bp,
+            native_sol,
+        )
     }
 
-    pub fn claim_fees(_ctx: Context<ClaimFees>) -> Result<()> {
-        Ok(())
+    pub fn flash_loan(ctx: Context<FlashLoan>, amount_a: u64, amount_b: u64) -> Result<()> {
+        instructions::flash_loan(ctx, amount_a, amount_b)
     }
 
-    pub fn update_pool(_ctx: Context<UpdatePool>) -> Result<()> {
-        Ok(())
+    pub fn flash_repay(ctx: Context<FlashRepay>, amount_a: u64, amount_b: u64) -> Result<()> {
+        instructions::flash_repay(ctx, amount_a, amount_b)
     }
 
-    pub fn claim_rewards(_ctx: Context<ClaimRewards>) -> Result<()> {
-        Ok(())
+    pub fn quote_swap(
+        ctx: Context<Quote>,
+        amount_in: u64,
+        direction: SwapDirection,
+    ) -> Result<SwapQuote> {
+        instructions::quote_swap(ctx, amount_in, direction)
     }
-}
 
-#[derive(Accounts)]
-pub struct RemoveLiquidity {}
+    pub fn quote_swap_exact_out(
+        ctx: Context<Quote>,
+        amount_out: u64,
+        direction: SwapDirection,
+    ) -> Result<SwapQuote> {
+        instructions::quote_swap_exact_out(ctx, amount_out, direction)
+    }
 
-#[derive(Accounts)]
-pub struct ClaimFees {}
+    pub fn quote_add_liquidity(
+        ctx: Context<Quote>,
+        amount_a: u64,
+        amount_b: u64,
+    ) -> Result<AddLiquidityQuote> {
+        instructions::quote_add_liquidity(ctx, amount_a, amount_b)
+    }
 
-#[derive(Accounts)]
-pub struct UpdatePool {}
+    pub fn quote_remove_liquidity(
+        ctx: Context<Quote>,
+        lp_amount: u64,
+    ) -> Result<RemoveLiquidityQuote> {
+        instructions::quote_remove_liquidity(ctx, lp_amount)
+    }
+
+    pub fn get_pool_state(ctx: Context<Quote>) -> Result<PoolState> {
+        instructions::get_pool_state(ctx)
+    }
+
+    pub fn claim_fees<'info>(ctx: Context<'_, '_, 'info, 'info, ClaimFees<'info>>) -> Result<()> {
+        instructions::claim_fees(ctx)
+    }
+
+    pub fn set_max_creator_fee(
+        ctx: Context<SetMaxCreatorFee>,
+        max_creator_fee_bp: u16,
+    ) -> Result<()> {
+        instructions::set_max_creator_fee(ctx, max_creator_fee_bp)
+    }
+
+    pub fn set_creator_fee(ctx: Context<SetCreatorFee>, creator_fee_bp: u16) -> Result<()> {
+        instructions::set_creator_fee(ctx, creator_fee_bp)
+    }
+
+    pub fn claim_creator_fees(ctx: Context<ClaimCreatorFees>) -> Result<()> {
+        instructions::claim_creator_fees(ctx)
+    }
+
+    pub fn lock_lp(
+        ctx: Context<LockLp>,
+        id: u64,
+        amount: u64,
+        duration: LockDuration,
+    ) -> Result<()> {
+        instructions::lock_lp(ctx, id, amount, duration)
+    }
+
+    pub fn unlock_lp<'info>(ctx: Context<'_, '_, 'info, 'info, UnlockLp<'info>>) -> Result<()> {
+        instructions::unlock_lp(ctx)
+    }
+
+    pub fn set_early_exit_penalty(
+        ctx: Context<SetEarlyExitPenalty>,
+        early_exit_penalty_bp: u16,
+    ) -> Result<()> {
+        instructions::set_early_exit_penalty(ctx, early_exit_penalty_bp)
+    }
+
+    pub fn claim_rewards(_ctx: Context<ClaimRewards>) -> Result<()> {
+        Ok(())
+    }
+}
 
 #[derive(Accounts)]
 pub struct ClaimRewards {}
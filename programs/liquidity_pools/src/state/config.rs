@@ -1,14 +1,173 @@
+use crate::math::FEE_DENOMINATOR;
+use crate::state::PoolRegistry;
 use anchor_lang::prelude::*;
 
 #[account]
-#[derive(Default, Debug, InitSpace)]
+#[derive(Debug, InitSpace)]
 pub struct Config {
     pub authority: Pubkey,              // Address able to update config account
     pub protocol_fee_recipient: Pubkey, // Address able to claim protocol fees
-    pub protocol_fee_bp: u16,           // Basis points for protocol fees (100 = 1%)
+    pub protocol_fee_bp: u16,           // Share of the swap fee for the protocol (100 = 1%)
     pub bump: u8,
+    pub max_referral_fee_bp: u16, // Max share of the swap fee (100 = 1%) a swap can pay to its referrer
+    // Operational roles set by the authority, the authority can act as any of them
+    pub fee_manager: Pubkey,    // Updates the fee fields of the config
+    pub pause_guardian: Pubkey, // Pauses and unpauses swaps
+    pub pool_admin: Pubkey,     // Updates and closes pools
+    pub rewards_admin: Pubkey,  // Manages liquidity rewards
+    pub paused: bool,           // Swaps are rejected while set
+    // Seconds between queue_config_change and execute_config_change
+    pub timelock_delay: i64,
+    // Bounds of the pool swap fees (100 = 1%), checked by create_pool and update_pool
+    pub min_pool_fee_bp: u16,
+    pub max_pool_fee_bp: u16,
+    // Layout version, configs written before it read as 0 and go through migrate_config
+    pub version: u8,
+    // Pools in the registry, the next one goes to page pool_count / PAGE_SIZE
+    pub pool_count: u64,
+    // Who can call create_pool
+    pub pool_creation_mode: PoolCreationMode,
+    // Charged by create_pool to the protocol fee recipient, 0 disables it
+    pub pool_creation_fee: u64,
+    // Token of the creation fee, the default pubkey charges lamports
+    pub pool_creation_fee_mint: Pubkey,
+    // Share of the LP tokens (100 = 1%) paid to the fee recipients to unlock a position
+    // early, 0 keeps positions locked until they expire. Positions keep the value they
+    // were opened with
+    pub early_exit_penalty_bp: u16,
+    // How the protocol share of the swap fees is collected
+    pub protocol_fee_mode: ProtocolFeeMode,
+    // Split of the protocol fees paid out by claim_fees, the first
+    // fee_recipient_count entries are set and their weights add up to 10000
+    pub fee_recipients: [FeeRecipient; MAX_FEE_RECIPIENTS],
+    pub fee_recipient_count: u8,
+    // Highest share of the swap fee (100 = 1%) a pool creator can take, 0 disables creator fees
+    pub max_creator_fee_bp: u16,
+    // Highest protocol fee override (100 = 1%) the pool admin can set above
+    // protocol_fee_bp, changed through the timelock
+    pub max_protocol_fee_bp_override: u16,
+    // Operational role like fee_manager, sets the pool creation mode and manages
+    // the creator allowlist
+    pub pool_creation_admin: Pubkey,
+    // Zeroed space for future fields. Fields that fit take over as many bytes as they
+    // use so the account size stays 8 + INIT_SPACE = 484, larger ones (like the fee
+    // split) bump VERSION and grow the account through migrate_config
+    pub padding: [u8; 40],
+}
+
+pub const MAX_FEE_RECIPIENTS: usize = 4;
+
+#[derive(
+    AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq, InitSpace,
+)]
+pub struct FeeRecipient {
+    pub recipient: Pubkey,
+    pub weight_bp: u16, // Share of the claimed protocol fees (100 = 1%)
+}
+
+// Zero is permissionless so configs written before the mode stay open
+#[derive(
+    AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq, InitSpace,
+)]
+pub enum PoolCreationMode {
+    #[default]
+    Permissionless,
+    AuthorityOnly,
+    // The pool creation admins and the creators holding a PoolCreator account
+    Allowlist,
+}
+
+// Zero skims the fees in swap so configs written before the mode keep their behavior
+#[derive(
+    AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq, InitSpace,
+)]
+pub enum ProtocolFeeMode {
+    // Accrued in the input token by swap and claimed with claim_fees
+    #[default]
+    Swap,
+    // Minted as LP tokens to the fee recipient by add_liquidity and remove_liquidity,
+    // out of the growth of sqrt(k) since Pool::k_last
+    LpTokens,
 }
 
 impl Config {
     pub const SEED: &[u8] = b"config";
+    pub const VERSION: u8 = 2;
+    // Size of the configs created before the operational roles
+    pub const LEGACY_LEN: usize = 8 + 32 * 2 + 2 + 1;
+
+    pub fn is_fee_manager(&self, key: &Pubkey) -> bool {
+        *key == self.fee_manager || *key == self.authority
+    }
+
+    pub fn is_pause_guardian(&self, key: &Pubkey) -> bool {
+        *key == self.pause_guardian || *key == self.authority
+    }
+
+    pub fn is_pool_admin(&self, key: &Pubkey) -> bool {
+        *key == self.pool_admin || *key == self.authority
+    }
+
+    pub fn is_rewards_admin(&self, key: &Pubkey) -> bool {
+        *key == self.rewards_admin || *key == self.authority
+    }
+
+    pub fn is_pool_creation_admin(&self, key: &Pubkey) -> bool {
+        *key == self.pool_creation_admin || *key == self.authority
+    }
+
+    // Registry page the next registered pool is appended to
+    pub fn registry_page(&self) -> u32 {
+        (self.pool_count / PoolRegistry::PAGE_SIZE as u64) as u32
+    }
+
+    pub fn can_create_pools(&self, key: &Pubkey, allowlisted: bool) -> bool {
+        match self.pool_creation_mode {
+            PoolCreationMode::Permissionless => true,
+            PoolCreationMode::AuthorityOnly => self.is_pool_creation_admin(key),
+            PoolCreationMode::Allowlist => self.is_pool_creation_admin(key) || allowlisted,
+        }
+    }
+
+    pub fn charges_creation_fee_in_lamports(&self) -> bool {
+        self.pool_creation_fee_mint == Pubkey::default()
+    }
+
+    pub fn mints_protocol_fee(&self) -> bool {
+        self.protocol_fee_mode == ProtocolFeeMode::LpTokens
+    }
+
+    pub fn active_fee_recipients(&self) -> &[FeeRecipient] {
+        &self.fee_recipients[..self.fee_recipient_count as usize]
+    }
+
+    // Callers check the split with is_valid_fee_split first
+    pub fn set_fee_recipients(&mut self, recipients: &[FeeRecipient]) {
+        self.fee_recipients = [FeeRecipient::default(); MAX_FEE_RECIPIENTS];
+        self.fee_recipients[..recipients.len()].copy_from_slice(recipients);
+        self.fee_recipient_count = recipients.len() as u8;
+    }
+
+    pub fn is_valid_fee_split(recipients: &[FeeRecipient]) -> bool {
+        let total: u32 = recipients.iter().map(|entry| entry.weight_bp as u32).sum();
+        let distinct = recipients.iter().enumerate().all(|(i, entry)| {
+            recipients[..i]
+                .iter()
+                .all(|other| other.recipient != entry.recipient)
+        });
+        (1..=MAX_FEE_RECIPIENTS).contains(&recipients.len())
+            && recipients.iter().all(|entry| entry.weight_bp > 0)
+            && total as u128 == FEE_DENOMINATOR
+            && distinct
+    }
+
+    // Lowering the protocol fee of a pool is always allowed, raising it is bounded
+    // by the timelocked cap
+    pub fn is_valid_protocol_fee_override(&self, protocol_fee_bp: u16) -> bool {
+        protocol_fee_bp <= self.protocol_fee_bp.max(self.max_protocol_fee_bp_override)
+    }
+
+    pub fn is_valid_pool_fee(&self, fee_bp: u16) -> bool {
+        (self.min_pool_fee_bp..=self.max_pool_fee_bp).contains(&fee_bp)
+    }
 }
@@ -0,0 +1,22 @@
+use crate::state::{FeeRecipient, ProtocolFeeMode, MAX_FEE_RECIPIENTS};
+use anchor_lang::prelude::*;
+
+// Pending update of the timelocked config fields, a single change can be queued at a time
+#[account]
+#[derive(Default, Debug, InitSpace)]
+pub struct ConfigChange {
+    pub protocol_fee_recipient: Option<Pubkey>,
+    pub protocol_fee_bp: Option<u16>,
+    pub timelock_delay: Option<i64>,
+    pub protocol_fee_mode: Option<ProtocolFeeMode>,
+    pub max_protocol_fee_bp_override: Option<u16>,
+    // New split of the protocol fees, left unchanged when empty
+    #[max_len(MAX_FEE_RECIPIENTS)]
+    pub fee_recipients: Vec<FeeRecipient>,
+    pub eta: i64, // Unix timestamp from which the change can be executed
+    pub bump: u8,
+}
+
+impl ConfigChange {
+    pub const SEED: &[u8] = b"config_change";
+}
@@ -0,0 +1,66 @@
+use crate::error::ErrorCode;
+use crate::math::FEE_DENOMINATOR;
+use anchor_lang::prelude::*;
+
+const DAY: i64 = 24 * 60 * 60;
+
+// Lock periods offered to LPs, longer locks get a larger boost
+#[derive(
+    AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq, InitSpace,
+)]
+pub enum LockDuration {
+    #[default]
+    OneMonth,
+    ThreeMonths,
+    SixMonths,
+    TwelveMonths,
+}
+
+impl LockDuration {
+    pub fn seconds(&self) -> i64 {
+        match self {
+            LockDuration::OneMonth => 30 * DAY,
+            LockDuration::ThreeMonths => 90 * DAY,
+            LockDuration::SixMonths => 180 * DAY,
+            LockDuration::TwelveMonths => 360 * DAY,
+        }
+    }
+
+    // Multiplier of the reward share (10000 = 1x)
+    pub fn boost_bp(&self) -> u16 {
+        match self {
+            LockDuration::OneMonth => 11_000,
+            LockDuration::ThreeMonths => 12_500,
+            LockDuration::SixMonths => 15_000,
+            LockDuration::TwelveMonths => 20_000,
+        }
+    }
+
+    // `amount` weighted by the boost, rounded down
+    pub fn boosted_amount(&self, amount: u64) -> Result<u64> {
+        let boosted = amount as u128 * self.boost_bp() as u128 / FEE_DENOMINATOR;
+        Ok(u64::try_from(boosted).map_err(|_| ErrorCode::Overflow)?)
+    }
+}
+
+// LP tokens of a pool locked by `owner` until `unlock_at`. The reward share of
+// the position is boosted_amount / Pool::total_boosted_lp
+#[account]
+#[derive(Default, Debug, InitSpace)]
+pub struct LockPosition {
+    pub pool: Pubkey,
+    pub owner: Pubkey,
+    pub id: u64, // Chosen by the owner, lets an owner hold several positions in a pool
+    pub amount: u64,
+    pub boosted_amount: u64,
+    pub duration: LockDuration,
+    pub locked_at: i64,
+    pub unlock_at: i64,
+    // Config::early_exit_penalty_bp when the position was opened, 0 allows no early exit
+    pub early_exit_penalty_bp: u16,
+    pub bump: u8,
+}
+
+impl LockPosition {
+    pub const SEED: &[u8] = b"lock_position";
+}
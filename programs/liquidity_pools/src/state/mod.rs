@@ -1,5 +1,17 @@
 pub mod config;
 pub use config::*;
 
+pub mod config_change;
+pub use config_change::*;
+
+pub mod lock_position;
+pub use lock_position::*;
+
 pub mod pool;
 pub use pool::*;
+
+pub mod pool_creator;
+pub use pool_creator::*;
+
+pub mod registry;
+pub use registry::*;
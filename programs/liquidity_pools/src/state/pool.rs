@@ -1,17 +1,130 @@
+use crate::error::ErrorCode;
+use crate::state::Config;
 use anchor_lang::prelude::*;
 
 #[account]
-#[derive(Default, Debug, InitSpace)]
+#[derive(Debug, InitSpace)]
 pub struct Pool {
     pub token_mint_a: Pubkey,
     pub token_mint_b: Pubkey,
     pub token_vault_a: Pubkey,
     pub token_vault_b: Pubkey,
-    pub fee_bp: u16,
+    // Part of the pool seeds, lets a mint pair have several pools. Pools created
+    // before fee_bp was decoupled from the address have their original fee here
+    pub index: u16,
     pub bump: u8,
+    pub creator: Pubkey, // Allowed to close the pool once it is empty
+    // Outstanding flash loan, the pool is locked while it is active
+    pub flash_loan_active: bool,
+    pub flash_loan_amount_a: u64,
+    pub flash_loan_amount_b: u64,
+    // Reserves before the flash loan, used to verify k on repayment
+    pub flash_loan_reserve_a: u64,
+    pub flash_loan_reserve_b: u64,
+    // Swap limits set by the config authority, 0 disables the limit
+    pub max_price_impact_bp: u16, // Max move of the spot price caused by one swap
+    pub max_trade_size_bp: u16,   // Max amount_in as a fraction of the input reserve
+    // Replaces Config::protocol_fee_bp for this pool when set
+    pub protocol_fee_bp_override: Option<u16>,
+    // Protocol fees held in the vaults until claim_fees, excluded from the reserves
+    pub protocol_fees_a: u64,
+    pub protocol_fees_b: u64,
+    // Swap fee (100 = 1%), within the Config fee bounds
+    pub fee_bp: u16,
+    // Layout version, pools written before it read as 0 and go through migrate_pool
+    pub version: u8,
+    // LP tokens held by lock positions
+    pub total_locked_lp: u64,
+    // reserve_a * reserve_b after the last liquidity change, 0 unless the protocol
+    // fee is minted as LP tokens
+    pub k_last: u128,
+    // Share of the swap fee left after the protocol fee (100 = 1%) accrued to the
+    // creator, set by the creator within Config::max_creator_fee_bp
+    pub creator_fee_bp: u16,
+    // Creator fees held in the vaults until claim_creator_fees, excluded from the reserves
+    pub creator_fees_a: u64,
+    pub creator_fees_b: u64,
+    // Set once the pool is listed by the registry, along with its pair index page
+    pub registered: bool,
+    pub pair_index_page: u16,
+    // Sum of LockPosition::boosted_amount over the lock positions of the pool
+    pub total_boosted_lp: u64,
+    // Zeroed space for future fields, new fields take over as many bytes as they use
+    // so the account size stays 8 + INIT_SPACE = 358 without a realloc
+    pub padding: [u8; 75],
 }
 
 impl Pool {
     pub const SEED: &'static [u8] = b"pool";
     pub const MINT_SEED: &'static [u8] = b"mint";
+    pub const VERSION: u8 = 1;
+    // Size of the pools created before the pool index, their seed is the fee
+    pub const LEGACY_LEN: usize = 8 + 32 * 4 + 2 + 1;
+
+    // Share of the swap fee (100 = 1%) accrued to the protocol
+    pub fn protocol_fee_bp(&self, config: &Config) -> u16 {
+        self.protocol_fee_bp_override
+            .unwrap_or(config.protocol_fee_bp)
+    }
+
+    // Swaps leave the protocol fee in the reserves while k_last is set. A pool keeps doing
+    // it after the config switches to skimming until its next liquidity change mints the
+    // growth since k_last
+    pub fn mints_protocol_fee(&self, config: &Config) -> bool {
+        config.mints_protocol_fee() || self.k_last != 0
+    }
+
+    // Vault holding `mint`, one of the pool mints
+    pub fn vault(&self, mint: &Pubkey) -> Pubkey {
+        if *mint == self.token_mint_a {
+            self.token_vault_a
+        } else {
+            self.token_vault_b
+        }
+    }
+
+    // Vault balance of `mint` minus its unclaimed protocol and creator fees
+    pub fn reserve(&self, mint: &Pubkey, vault_amount: u64) -> Result<u64> {
+        let (protocol_fees, creator_fees) = if *mint == self.token_mint_a {
+            (self.protocol_fees_a, self.creator_fees_a)
+        } else {
+            (self.protocol_fees_b, self.creator_fees_b)
+        };
+        Ok(vault_amount
+            .checked_sub(protocol_fees)
+            .and_then(|amount| amount.checked_sub(creator_fees))
+            .ok_or(ErrorCode::Underflow)?)
+    }
+
+    // (reserve_a, reserve_b) from the vault balances
+    pub fn reserves(&self, vault_a: u64, vault_b: u64) -> Result<(u64, u64)> {
+        Ok((
+            self.reserve(&self.token_mint_a, vault_a)?,
+            self.reserve(&self.token_mint_b, vault_b)?,
+        ))
+    }
+
+    pub fn accrue_protocol_fee(&mut self, mint: &Pubkey, amount: u64) -> Result<()> {
+        let protocol_fees = if *mint == self.token_mint_a {
+            &mut self.protocol_fees_a
+        } else {
+            &mut self.protocol_fees_b
+        };
+        *protocol_fees = protocol_fees
+            .checked_add(amount)
+            .ok_or(ErrorCode::Overflow)?;
+        Ok(())
+    }
+
+    pub fn accrue_creator_fee(&mut self, mint: &Pubkey, amount: u64) -> Result<()> {
+        let creator_fees = if *mint == self.token_mint_a {
+            &mut self.creator_fees_a
+        } else {
+            &mut self.creator_fees_b
+        };
+        *creator_fees = creator_fees
+            .checked_add(amount)
+            .ok_or(ErrorCode::Overflow)?;
+        Ok(())
+    }
 }
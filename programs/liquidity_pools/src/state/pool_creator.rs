@@ -0,0 +1,13 @@
+use anchor_lang::prelude::*;
+
+// Allowlist entry of a pool creator, checked by create_pool in allowlist mode
+#[account]
+#[derive(Default, Debug, InitSpace)]
+pub struct PoolCreator {
+    pub creator: Pubkey,
+    pub bump: u8,
+}
+
+impl PoolCreator {
+    pub const SEED: &[u8] = b"pool_creator";
+}
@@ -0,0 +1,65 @@
+use anchor_lang::prelude::*;
+
+pub const REGISTRY_PAGE_SIZE: usize = 64;
+pub const PAIR_INDEX_MAX_POOLS: usize = 32;
+
+// One page of the list of every registered pool, in registration order.
+// Page n holds the pools registered as n * PAGE_SIZE to (n + 1) * PAGE_SIZE - 1
+#[account]
+#[derive(Default, Debug, InitSpace)]
+pub struct PoolRegistry {
+    pub page: u32,
+    #[max_len(REGISTRY_PAGE_SIZE)]
+    pub pools: Vec<Pubkey>,
+    pub bump: u8,
+}
+
+impl PoolRegistry {
+    pub const SEED: &[u8] = b"registry";
+    pub const PAGE_SIZE: usize = REGISTRY_PAGE_SIZE;
+}
+
+// Pages of the pair index of a mint pair, pools fill them in registration order so
+// the pages in use are 0 to page_count() - 1
+#[account]
+#[derive(Default, Debug, InitSpace)]
+pub struct PairIndexHeader {
+    pub token_mint_a: Pubkey,
+    pub token_mint_b: Pubkey,
+    // Pools registered for the pair, closed pools included
+    pub pool_count: u32,
+    pub bump: u8,
+}
+
+impl PairIndexHeader {
+    pub const SEED: &[u8] = b"pair_index_header";
+
+    // Page listing the next registered pool of the pair
+    pub fn next_page(&self) -> u16 {
+        (self.pool_count / PairIndex::MAX_POOLS as u32) as u16
+    }
+
+    // Number of pair index pages holding a registered pool
+    pub fn page_count(&self) -> u16 {
+        (self.pool_count.div_ceil(PairIndex::MAX_POOLS as u32)) as u16
+    }
+}
+
+// One page of the registered pools of a mint pair, whatever their fee.
+// Page n holds the pools registered as n * MAX_POOLS to (n + 1) * MAX_POOLS - 1 for
+// the pair, see PairIndexHeader
+#[account]
+#[derive(Default, Debug, InitSpace)]
+pub struct PairIndex {
+    pub token_mint_a: Pubkey,
+    pub token_mint_b: Pubkey,
+    pub page: u16,
+    #[max_len(PAIR_INDEX_MAX_POOLS)]
+    pub pools: Vec<Pubkey>,
+    pub bump: u8,
+}
+
+impl PairIndex {
+    pub const SEED: &[u8] = b"pair_index";
+    pub const MAX_POOLS: usize = PAIR_INDEX_MAX_POOLS;
+}
@@ -0,0 +1,56 @@
+use anchor_lang::prelude::*;
+
+use crate::math::SwapAmounts;
+
+// Side of the pool sold in a swap
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SwapDirection {
+    AToB,
+    BToA,
+}
+
+// Returned by quote_swap and quote_swap_exact_out
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SwapQuote {
+    pub amount_in: u64,
+    pub amount_out: u64,
+    pub fee_amount: u64,
+}
+
+impl From<SwapAmounts> for SwapQuote {
+    fn from(amounts: SwapAmounts) -> Self {
+        Self {
+            amount_in: amounts.amount_in,
+            amount_out: amounts.amount_out,
+            fee_amount: amounts.fee_amount,
+        }
+    }
+}
+
+// Returned by quote_add_liquidity: amounts actually transferred and LP minted
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct AddLiquidityQuote {
+    pub amount_a: u64,
+    pub amount_b: u64,
+    pub lp_amount: u64,
+}
+
+// Returned by quote_remove_liquidity
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RemoveLiquidityQuote {
+    pub amount_a: u64,
+    pub amount_b: u64,
+}
+
+// Returned by get_pool_state
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PoolState {
+    pub token_mint_a: Pubkey,
+    pub token_mint_b: Pubkey,
+    pub lp_mint: Pubkey,
+    pub reserve_a: u64,
+    pub reserve_b: u64,
+    // Includes the protocol LP fee the next liquidity change mints first
+    pub lp_supply: u64,
+    pub fee_bp: u16,
+}
@@ -22,6 +22,9 @@ import {
   createTokenMint,
   mintTo,
   setUpEnv,
+  findPairIndexHeaderPda,
+  findPairIndexPda,
+  findRegistryPda,
 } from "./utils/helpers";
 import { CONFIG_SEED, POOL_MINT_SEED } from "./utils/seeds";
 import { ErrorCode } from "./errors";
@@ -149,6 +152,7 @@ describe("instructions::coverage", () => {
     let mintB = isFront ? tokenBMint : tokenAMint;
 
     const feeBp = 100; // 1%
+    const index = 0;
 
     // Generate pool PDA
     const [pool_pda, pool_bump] = PublicKey.findProgramAddressSync(
@@ -156,7 +160,7 @@ describe("instructions::coverage", () => {
         Buffer.from("pool"), // POOL_SEED
         mintA.toBuffer(),
         mintB.toBuffer(),
-        new BN(feeBp).toBuffer("le", 2), // 2 bytes for feeBp (u16)
+        new BN(index).toBuffer("le", 2), // 2 bytes for the pool index (u16)
       ],
       program.programId
     );
@@ -167,7 +171,7 @@ describe("instructions::coverage", () => {
         Buffer.from("mint"), // POOL_MINT_SEED
         mintA.toBuffer(),
         mintB.toBuffer(),
-        new BN(feeBp).toBuffer("le", 2), // 2 bytes for feeBp (u16)
+        new BN(index).toBuffer("le", 2), // 2 bytes for the pool index (u16)
       ],
       program.programId
     );
@@ -190,22 +194,41 @@ describe("instructions::coverage", () => {
       mintBAccount.owner
     );
 
+    const [config_pda] = PublicKey.findProgramAddressSync(
+      [Buffer.from(CONFIG_SEED)],
+      program.programId
+    );
+
     // Create pool instruction
     const instruction = await program.methods
-      .createPool(feeBp)
+      .createPool(index, feeBp)
       .accountsStrict({
         authority: poolCreator.publicKey,
+        config: config_pda,
+        poolCreator: null,
         pool: pool_pda,
         tokenMintA: mintA,
         tokenMintB: mintB,
         tokenVaultA: poolTokenVaultA,
         tokenVaultB: poolTokenVaultB,
         lpMint: lpMint_pda,
+        registry: findRegistryPda(program.programId),
+        pairIndexHeader: findPairIndexHeaderPda(
+          program.programId,
+          mintA,
+          mintB
+        ),
+        pairIndex: findPairIndexPda(program.programId, mintA, mintB),
         tokenProgramA: mintAAccount.owner,
         tokenProgramB: mintBAccount.owner,
         tokenProgramLp: TOKEN_2022_PROGRAM_ID,
         systemProgram: web3.SystemProgram.programId,
         associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        protocolFeeRecipient: null,
+        creationFeeMint: null,
+        authorityFeeAta: null,
+        recipientFeeAta: null,
+        creationFeeTokenProgram: null,
       })
       .instruction();
 
@@ -280,6 +303,7 @@ describe("instructions::coverage", () => {
     let mintB = isFront ? tokenBMint : tokenAMint;
 
     const feeBp = 100; // 1%
+    const index = 0;
 
     // Generate pool PDA
     const [pool_pda, pool_bump] = PublicKey.findProgramAddressSync(
@@ -287,7 +311,7 @@ describe("instructions::coverage", () => {
         Buffer.from("pool"), // POOL_SEED
         mintA.toBuffer(),
         mintB.toBuffer(),
-        new BN(feeBp).toBuffer("le", 2), // 2 bytes for feeBp (u16)
+        new BN(index).toBuffer("le", 2), // 2 bytes for the pool index (u16)
       ],
       program.programId
     );
@@ -298,7 +322,7 @@ describe("instructions::coverage", () => {
         Buffer.from("mint"), // POOL_MINT_SEED
         mintA.toBuffer(),
         mintB.toBuffer(),
-        new BN(feeBp).toBuffer("le", 2), // 2 bytes for feeBp (u16)
+        new BN(index).toBuffer("le", 2), // 2 bytes for the pool index (u16)
       ],
       program.programId
     );
@@ -321,22 +345,41 @@ describe("instructions::coverage", () => {
       mintBAccount.owner
     );
 
+    const [config_pda] = PublicKey.findProgramAddressSync(
+      [Buffer.from(CONFIG_SEED)],
+      program.programId
+    );
+
     // Create pool instruction that should fail
     const instruction = await program.methods
-      .createPool(feeBp)
+      .createPool(index, feeBp)
       .accountsStrict({
         authority: poolCreator.publicKey,
+        config: config_pda,
+        poolCreator: null,
         pool: pool_pda,
         tokenMintA: mintA,
         tokenMintB: mintB,
         tokenVaultA: poolTokenVaultA,
         tokenVaultB: poolTokenVaultB,
         lpMint: lpMint_pda,
+        registry: findRegistryPda(program.programId),
+        pairIndexHeader: findPairIndexHeaderPda(
+          program.programId,
+          mintA,
+          mintB
+        ),
+        pairIndex: findPairIndexPda(program.programId, mintA, mintB),
         tokenProgramA: mintAAccount.owner,
         tokenProgramB: mintBAccount.owner,
         tokenProgramLp: TOKEN_2022_PROGRAM_ID,
         systemProgram: web3.SystemProgram.programId,
         associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        protocolFeeRecipient: null,
+        creationFeeMint: null,
+        authorityFeeAta: null,
+        recipientFeeAta: null,
+        creationFeeTokenProgram: null,
       })
       .instruction();
 
@@ -418,6 +461,7 @@ describe("instructions::coverage", () => {
     });
 
     const feeBp = 100; // 1%
+    const index = 0;
 
     // Generate pool PDA
     const [pool_pda, pool_bump] = PublicKey.findProgramAddressSync(
@@ -425,7 +469,7 @@ describe("instructions::coverage", () => {
         Buffer.from("pool"), // POOL_SEED
         mintA.toBuffer(),
         mintB.toBuffer(),
-        new BN(feeBp).toBuffer("le", 2), // 2 bytes for feeBp (u16)
+        new BN(index).toBuffer("le", 2), // 2 bytes for the pool index (u16)
       ],
       program.programId
     );
@@ -436,7 +480,7 @@ describe("instructions::coverage", () => {
         Buffer.from(POOL_MINT_SEED), // POOL_MINT_SEED
         mintA.toBuffer(),
         mintB.toBuffer(),
-        new BN(feeBp).toBuffer("le", 2), // 2 bytes for feeBp (u16)
+        new BN(index).toBuffer("le", 2), // 2 bytes for the pool index (u16)
       ],
       program.programId
     );
@@ -455,22 +499,41 @@ describe("instructions::coverage", () => {
       mintBAccount.owner
     );
 
+    const [config_pda] = PublicKey.findProgramAddressSync(
+      [Buffer.from(CONFIG_SEED)],
+      program.programId
+    );
+
     // Create pool instruction
     const instruction = await program.methods
-      .createPool(feeBp)
+      .createPool(index, feeBp)
       .accountsStrict({
         authority: poolCreator.publicKey,
+        config: config_pda,
+        poolCreator: null,
         pool: pool_pda,
         tokenMintA: mintA,
         tokenMintB: mintB,
         tokenVaultA: poolTokenVaultA,
         tokenVaultB: poolTokenVaultB,
         lpMint: lpMint_pda,
+        registry: findRegistryPda(program.programId),
+        pairIndexHeader: findPairIndexHeaderPda(
+          program.programId,
+          mintA,
+          mintB
+        ),
+        pairIndex: findPairIndexPda(program.programId, mintA, mintB),
         tokenProgramA: mintAAccount.owner,
         tokenProgramB: mintBAccount.owner,
         tokenProgramLp: TOKEN_2022_PROGRAM_ID,
         systemProgram: web3.SystemProgram.programId,
         associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        protocolFeeRecipient: null,
+        creationFeeMint: null,
+        authorityFeeAta: null,
+        recipientFeeAta: null,
+        creationFeeTokenProgram: null,
       })
       .instruction();
 
@@ -510,12 +573,13 @@ describe("instructions::coverage", () => {
 
     // Add liquidity instruction
     const addLiquidityInstruction = await program.methods
-      .addLiquidity(amountA, amountB, amountAMin, amountBMin)
+      .addLiquidity(amountA, amountB, amountAMin, amountBMin, null, false)
       .accountsStrict({
         provider: poolCreator.publicKey,
         providerAtaA: creatorTokenAAssociatedAccount,
         providerAtaB: creatorTokenBAssociatedAccount,
-        providerAtaLp: providerAtaLp,
+        lpRecipient: poolCreator.publicKey,
+        lpRecipientAta: providerAtaLp,
         pool: pool_pda,
         lpMint: lpMint_pda,
         poolAtaA: poolTokenVaultA,
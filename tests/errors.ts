@@ -8,4 +8,39 @@ export enum ErrorCode {
   InvalidProtocolFeeRecipient = 6002,
   InvalidTokenProgram = 6003,
   InvalidTokenOrder = 6004,
+  Expired = 6014,
+  FlashLoanActive = 6015,
+  FlashLoanNotActive = 6016,
+  FlashLoanNotRepaid = 6017,
+  FlashLoanCpiNotAllowed = 6018,
+  InvariantViolated = 6019,
+  PriceImpactTooHigh = 6020,
+  TradeTooLarge = 6021,
+  InvalidTradeLimit = 6022,
+  PoolNotEmpty = 6023,
+  ReferralFeeTooHigh = 6024,
+  InvalidReferrer = 6025,
+  InvalidProtocolFee = 6026,
+  InvalidFeeRecipient = 6027,
+  NotFeeManager = 6028,
+  NotPauseGuardian = 6029,
+  NotPoolAdmin = 6030,
+  Paused = 6031,
+  TimelockNotExpired = 6032,
+  InvalidTimelockDelay = 6033,
+  InvalidPoolFee = 6034,
+  InvalidPoolFeeBounds = 6035,
+  AlreadyMigrated = 6036,
+  PairIndexFull = 6037,
+  PoolCreationNotAllowed = 6038,
+  MissingCreationFeeAccounts = 6039,
+  NotRewardsAdmin = 6040,
+  LockNotExpired = 6041,
+  InvalidEarlyExitPenalty = 6042,
+  InvalidFeeRecipients = 6043,
+  InvalidCreatorFee = 6044,
+  NotNativeMintPool = 6045,
+  NativeSolRecipient = 6046,
+  NotPoolCreationAdmin = 6047,
+  NativeSolAccounts = 6048,
 }
@@ -13,6 +13,11 @@ import {
   createTokenMint,
   createAssociatedTokenAccount,
   mintTo,
+  initializeConfig,
+  findPairIndexHeaderPda,
+  findPairIndexPda,
+  findConfigPda,
+  findRegistryPda,
 } from "../utils/helpers";
 import { ErrorCode } from "../errors";
 import {
@@ -90,12 +95,15 @@ describe("instructions::add_liquidity", () => {
     );
 
     const tx = await program.methods
-      .addLiquidity(amountA, amountB, amountAMin, amountBMin)
+      .addLiquidity(amountA, amountB, amountAMin, amountBMin, null, false)
       .accountsStrict({
         provider: poolCreator.publicKey,
         providerAtaA: creatorTokenAAssociatedAccount,
         providerAtaB: creatorTokenBAssociatedAccount,
-        providerAtaLp: providerAtaLp,
+        wsolA: null,
+        wsolB: null,
+        lpRecipient: poolCreator.publicKey,
+        lpRecipientAta: providerAtaLp,
         pool: pool_pda,
         lpMint: lpMint_pda,
         poolAtaA: poolTokenVaultA,
@@ -107,6 +115,7 @@ describe("instructions::add_liquidity", () => {
         tokenProgramLp: lpMintAccount.owner,
         associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
         systemProgram: SystemProgram.programId,
+        config: findConfigPda(program.programId),
       })
       .signers([poolCreator])
       .rpc();
@@ -292,12 +301,15 @@ describe("instructions::add_liquidity", () => {
       );
 
     const tx = await program.methods
-      .addLiquidity(amountA, amountB, amountAMin, amountBMin) // Use calculated optimal amounts
+      .addLiquidity(amountA, amountB, amountAMin, amountBMin, null, false) // Use calculated optimal amounts
       .accountsStrict({
         provider: randomUser.publicKey, // Use randomUser as provider
         providerAtaA: randomUserTokenAAssociatedAccount,
         providerAtaB: randomUserTokenBAssociatedAccount,
-        providerAtaLp: randomUserLpAta,
+        wsolA: null,
+        wsolB: null,
+        lpRecipient: randomUser.publicKey,
+        lpRecipientAta: randomUserLpAta,
         pool: pool_pda,
         lpMint: lpMint_pda,
         poolAtaA: poolTokenVaultA,
@@ -309,6 +321,7 @@ describe("instructions::add_liquidity", () => {
         tokenProgramLp: lpMintAccount.owner,
         associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
         systemProgram: SystemProgram.programId,
+        config: findConfigPda(program.programId),
       })
       .signers([randomUser]) // Use randomUser as signer
       .rpc();
@@ -358,6 +371,10 @@ async function setUpTest() {
     address: poolCreator.publicKey,
     amount: LAMPORTS_PER_SOL * 1000,
   });
+  const config_pda = await initializeConfig({
+    program,
+    authority: poolCreator,
+  });
 
   const tokenAMint = createTokenMint({
     context,
@@ -403,13 +420,14 @@ async function setUpTest() {
   let mintB = isFront ? tokenBMint : tokenAMint;
 
   const feeBp = 100; // 1%
+  const index = 0;
 
   const [pool_pda, pool_bump] = PublicKey.findProgramAddressSync(
     [
       Buffer.from(POOL_SEED),
       mintA.toBuffer(),
       mintB.toBuffer(),
-      new BN(feeBp).toBuffer("le", 2), // 2 bytes for feeBp (u16)
+      new BN(index).toBuffer("le", 2), // 2 bytes for the pool index (u16)
     ],
     program.programId
   );
@@ -422,7 +440,7 @@ async function setUpTest() {
       Buffer.from(POOL_MINT_SEED),
       mintA.toBuffer(),
       mintB.toBuffer(),
-      new BN(feeBp).toBuffer("le", 2), // 2 bytes for feeBp (u16)
+      new BN(index).toBuffer("le", 2), // 2 bytes for the pool index (u16)
     ],
     program.programId
   );
@@ -441,20 +459,30 @@ async function setUpTest() {
   );
 
   const tx = await program.methods
-    .createPool(feeBp)
+    .createPool(index, feeBp)
     .accountsStrict({
       authority: poolCreator.publicKey,
+      config: config_pda,
+      poolCreator: null,
       pool: pool_pda,
       tokenMintA: mintA,
       tokenMintB: mintB,
       tokenVaultA: poolTokenVaultA,
       tokenVaultB: poolTokenVaultB,
       lpMint: lpMint_pda,
+      registry: findRegistryPda(program.programId),
+      pairIndexHeader: findPairIndexHeaderPda(program.programId, mintA, mintB),
+      pairIndex: findPairIndexPda(program.programId, mintA, mintB),
       tokenProgramA: mintAAccount.owner,
       tokenProgramB: mintBAccount.owner,
       tokenProgramLp: TOKEN_2022_PROGRAM_ID,
       associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
       systemProgram: SystemProgram.programId,
+      protocolFeeRecipient: null,
+      creationFeeMint: null,
+      authorityFeeAta: null,
+      recipientFeeAta: null,
+      creationFeeTokenProgram: null,
     })
     .signers([poolCreator])
     .rpc();
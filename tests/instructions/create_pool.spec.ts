@@ -13,6 +13,10 @@ import {
   createTokenMint,
   createAssociatedTokenAccount,
   mintTo,
+  initializeConfig,
+  findPairIndexHeaderPda,
+  findPairIndexPda,
+  findRegistryPda,
 } from "../utils/helpers";
 import { ErrorCode } from "../errors";
 import {
@@ -26,8 +30,15 @@ import {
 
 describe("instructions::create_pool", () => {
   it("can create a pool", async () => {
-    const { context, program, provider, poolCreator, tokenAMint, tokenBMint } =
-      await setUpTest();
+    const {
+      context,
+      program,
+      provider,
+      poolCreator,
+      tokenAMint,
+      tokenBMint,
+      config_pda,
+    } = await setUpTest();
 
     const isFront = new BN(tokenAMint.toBuffer()).gt(
       new BN(tokenBMint.toBuffer())
@@ -36,13 +47,14 @@ describe("instructions::create_pool", () => {
     let mintB = isFront ? tokenBMint : tokenAMint;
 
     const feeBp = 100; // 1%
+    const index = 0;
 
     const [pool_pda, pool_bump] = PublicKey.findProgramAddressSync(
       [
         Buffer.from(POOL_SEED),
         mintA.toBuffer(),
         mintB.toBuffer(),
-        new BN(feeBp).toBuffer("le", 2), // 2 bytes for feeBp (u16)
+        new BN(index).toBuffer("le", 2), // 2 bytes for the pool index (u16)
       ],
       program.programId
     );
@@ -55,7 +67,7 @@ describe("instructions::create_pool", () => {
         Buffer.from(POOL_MINT_SEED),
         mintA.toBuffer(),
         mintB.toBuffer(),
-        new BN(feeBp).toBuffer("le", 2), // 2 bytes for feeBp (u16)
+        new BN(index).toBuffer("le", 2), // 2 bytes for the pool index (u16)
       ],
       program.programId
     );
@@ -74,20 +86,34 @@ describe("instructions::create_pool", () => {
     );
 
     const tx = await program.methods
-      .createPool(feeBp)
+      .createPool(index, feeBp)
       .accountsStrict({
         authority: poolCreator.publicKey,
+        config: config_pda,
+        poolCreator: null,
         pool: pool_pda,
         tokenMintA: mintA,
         tokenMintB: mintB,
         tokenVaultA: poolTokenVaultA,
         tokenVaultB: poolTokenVaultB,
         lpMint: lpMint_pda,
+        registry: findRegistryPda(program.programId),
+        pairIndexHeader: findPairIndexHeaderPda(
+          program.programId,
+          mintA,
+          mintB
+        ),
+        pairIndex: findPairIndexPda(program.programId, mintA, mintB),
         systemProgram: SystemProgram.programId,
         tokenProgramA: mintAAccount.owner,
         tokenProgramB: mintBAccount.owner,
         tokenProgramLp: TOKEN_2022_PROGRAM_ID,
         associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        protocolFeeRecipient: null,
+        creationFeeMint: null,
+        authorityFeeAta: null,
+        recipientFeeAta: null,
+        creationFeeTokenProgram: null,
       })
       .signers([poolCreator])
       .rpc();
@@ -105,6 +131,7 @@ describe("instructions::create_pool", () => {
       poolTokenVaultB.toBase58()
     );
     assert.equal(poolAccount.feeBp, feeBp);
+    assert.equal(poolAccount.index, index);
     assert.equal(poolAccount.bump, pool_bump);
 
     // LP Mint assertions
@@ -115,11 +142,34 @@ describe("instructions::create_pool", () => {
     assert.equal(poolLpMint.mintAuthority.toBase58(), pool_pda.toBase58());
     assert.equal(poolLpMint.freezeAuthority.toBase58(), pool_pda.toBase58());
     assert.equal(poolLpMint.isInitialized, true);
+
+    // Registry assertions
+    const registry = await program.account.poolRegistry.fetch(
+      findRegistryPda(program.programId)
+    );
+    assert.deepEqual(
+      registry.pools.map((pool) => pool.toBase58()),
+      [pool_pda.toBase58()]
+    );
+    const pairIndex = await program.account.pairIndex.fetch(
+      findPairIndexPda(program.programId, mintA, mintB)
+    );
+    assert.deepEqual(
+      pairIndex.pools.map((pool) => pool.toBase58()),
+      [pool_pda.toBase58()]
+    );
   });
 
   it("fails if token A < token B", async () => {
-    const { context, program, provider, poolCreator, tokenAMint, tokenBMint } =
-      await setUpTest();
+    const {
+      context,
+      program,
+      provider,
+      poolCreator,
+      tokenAMint,
+      tokenBMint,
+      config_pda,
+    } = await setUpTest();
 
     const isFront = new BN(tokenAMint.toBuffer()).lt(
       new BN(tokenBMint.toBuffer())
@@ -128,13 +178,14 @@ describe("instructions::create_pool", () => {
     let mintB = isFront ? tokenBMint : tokenAMint;
 
     const feeBp = 100; // 1%
+    const index = 0;
 
     const [pool_pda, pool_bump] = PublicKey.findProgramAddressSync(
       [
         Buffer.from(POOL_SEED),
         mintA.toBuffer(),
         mintB.toBuffer(),
-        new BN(feeBp).toBuffer("le", 2), // 2 bytes for feeBp (u16)
+        new BN(index).toBuffer("le", 2), // 2 bytes for the pool index (u16)
       ],
       program.programId
     );
@@ -147,7 +198,7 @@ describe("instructions::create_pool", () => {
         Buffer.from(POOL_MINT_SEED),
         mintA.toBuffer(),
         mintB.toBuffer(),
-        new BN(feeBp).toBuffer("le", 2), // 2 bytes for feeBp (u16)
+        new BN(index).toBuffer("le", 2), // 2 bytes for the pool index (u16)
       ],
       program.programId
     );
@@ -166,20 +217,34 @@ describe("instructions::create_pool", () => {
     );
 
     const tx = await program.methods
-      .createPool(feeBp)
+      .createPool(index, feeBp)
       .accountsStrict({
         authority: poolCreator.publicKey,
+        config: config_pda,
+        poolCreator: null,
         pool: pool_pda,
         tokenMintA: mintA,
         tokenMintB: mintB,
         tokenVaultA: poolTokenVaultA,
         tokenVaultB: poolTokenVaultB,
         lpMint: lpMint_pda,
+        registry: findRegistryPda(program.programId),
+        pairIndexHeader: findPairIndexHeaderPda(
+          program.programId,
+          mintA,
+          mintB
+        ),
+        pairIndex: findPairIndexPda(program.programId, mintA, mintB),
         systemProgram: SystemProgram.programId,
         tokenProgramA: mintAAccount.owner,
         tokenProgramB: mintBAccount.owner,
         tokenProgramLp: TOKEN_2022_PROGRAM_ID,
         associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        protocolFeeRecipient: null,
+        creationFeeMint: null,
+        authorityFeeAta: null,
+        recipientFeeAta: null,
+        creationFeeTokenProgram: null,
       })
       .signers([poolCreator])
       .rpc()
@@ -190,8 +255,15 @@ describe("instructions::create_pool", () => {
   });
 
   it("fails if seeds order is incorrect", async () => {
-    const { context, program, provider, poolCreator, tokenAMint, tokenBMint } =
-      await setUpTest();
+    const {
+      context,
+      program,
+      provider,
+      poolCreator,
+      tokenAMint,
+      tokenBMint,
+      config_pda,
+    } = await setUpTest();
     const isFront = new BN(tokenAMint.toBuffer()).gt(
       new BN(tokenBMint.toBuffer())
     );
@@ -199,13 +271,14 @@ describe("instructions::create_pool", () => {
     let mintB = isFront ? tokenBMint : tokenAMint;
 
     const feeBp = 100; // 1%
+    const index = 0;
 
     const [pool_pda, pool_bump] = PublicKey.findProgramAddressSync(
       [
         Buffer.from(POOL_SEED),
         mintA.toBuffer(),
         mintB.toBuffer(),
-        new BN(feeBp).toBuffer("le", 2), // 2 bytes for feeBp (u16)
+        new BN(index).toBuffer("le", 2), // 2 bytes for the pool index (u16)
       ],
       program.programId
     );
@@ -218,7 +291,7 @@ describe("instructions::create_pool", () => {
         Buffer.from(POOL_MINT_SEED),
         mintA.toBuffer(),
         mintB.toBuffer(),
-        new BN(feeBp).toBuffer("le", 2), // 2 bytes for feeBp (u16)
+        new BN(index).toBuffer("le", 2), // 2 bytes for the pool index (u16)
       ],
       program.programId
     );
@@ -237,20 +310,34 @@ describe("instructions::create_pool", () => {
     );
 
     const tx = await program.methods
-      .createPool(feeBp)
+      .createPool(index, feeBp)
       .accountsStrict({
         authority: poolCreator.publicKey,
+        config: config_pda,
+        poolCreator: null,
         pool: pool_pda,
         tokenMintA: mintB, // wrong order
         tokenMintB: mintA, // wrong order
         tokenVaultA: poolTokenVaultA,
         tokenVaultB: poolTokenVaultB,
         lpMint: lpMint_pda,
+        registry: findRegistryPda(program.programId),
+        pairIndexHeader: findPairIndexHeaderPda(
+          program.programId,
+          mintB,
+          mintA
+        ),
+        pairIndex: findPairIndexPda(program.programId, mintB, mintA),
         tokenProgramA: mintAAccount.owner,
         tokenProgramB: mintBAccount.owner,
         tokenProgramLp: TOKEN_2022_PROGRAM_ID,
         systemProgram: SystemProgram.programId,
         associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        protocolFeeRecipient: null,
+        creationFeeMint: null,
+        authorityFeeAta: null,
+        recipientFeeAta: null,
+        creationFeeTokenProgram: null,
       })
       .signers([poolCreator])
       .rpc()
@@ -260,33 +347,42 @@ describe("instructions::create_pool", () => {
       );
   });
 
-  it("can create multiple pools with the same tokens (different fee)", async () => {
-    const { context, program, provider, poolCreator, tokenAMint, tokenBMint } =
-      await setUpTest();
+  it("can create multiple pools with the same tokens (different index)", async () => {
+    const {
+      context,
+      program,
+      provider,
+      poolCreator,
+      tokenAMint,
+      tokenBMint,
+      config_pda,
+    } = await setUpTest();
     const isFront = new BN(tokenAMint.toBuffer()).gt(
       new BN(tokenBMint.toBuffer())
     );
     let mintA = isFront ? tokenAMint : tokenBMint;
     let mintB = isFront ? tokenBMint : tokenAMint;
 
+    const index1 = 0;
     const feeBp1 = 100; // 1%
     const [pool_pda_1, pool_bump_1] = PublicKey.findProgramAddressSync(
       [
         Buffer.from(POOL_SEED),
         mintA.toBuffer(),
         mintB.toBuffer(),
-        new BN(feeBp1).toBuffer("le", 2), // 2 bytes for feeBp (u16)
+        new BN(index1).toBuffer("le", 2), // 2 bytes for the pool index (u16)
       ],
       program.programId
     );
 
+    const index2 = 1;
     const feeBp2 = 200; // 2%
     const [pool_pda_2, pool_bump_2] = PublicKey.findProgramAddressSync(
       [
         Buffer.from(POOL_SEED),
         mintA.toBuffer(),
         mintB.toBuffer(),
-        new BN(feeBp2).toBuffer("le", 2), // 2 bytes for feeBp (u16)
+        new BN(index2).toBuffer("le", 2), // 2 bytes for the pool index (u16)
       ],
       program.programId
     );
@@ -299,7 +395,7 @@ describe("instructions::create_pool", () => {
         Buffer.from(POOL_MINT_SEED),
         mintA.toBuffer(),
         mintB.toBuffer(),
-        new BN(feeBp1).toBuffer("le", 2), // 2 bytes for feeBp (u16)
+        new BN(index1).toBuffer("le", 2), // 2 bytes for the pool index (u16)
       ],
       program.programId
     );
@@ -309,7 +405,7 @@ describe("instructions::create_pool", () => {
         Buffer.from(POOL_MINT_SEED),
         mintA.toBuffer(),
         mintB.toBuffer(),
-        new BN(feeBp2).toBuffer("le", 2), // 2 bytes for feeBp (u16)
+        new BN(index2).toBuffer("le", 2), // 2 bytes for the pool index (u16)
       ],
       program.programId
     );
@@ -340,39 +436,67 @@ describe("instructions::create_pool", () => {
     );
 
     const poolTx1 = await program.methods
-      .createPool(feeBp1)
+      .createPool(index1, feeBp1)
       .accountsStrict({
         authority: poolCreator.publicKey,
+        config: config_pda,
+        poolCreator: null,
         pool: pool_pda_1,
         tokenMintA: mintA,
         tokenMintB: mintB,
         tokenVaultA: pool1TokenVaultA,
         tokenVaultB: pool1TokenVaultB,
         lpMint: lpMint1_pda,
+        registry: findRegistryPda(program.programId),
+        pairIndexHeader: findPairIndexHeaderPda(
+          program.programId,
+          mintA,
+          mintB
+        ),
+        pairIndex: findPairIndexPda(program.programId, mintA, mintB),
         tokenProgramA: mintAAccount.owner,
         tokenProgramB: mintBAccount.owner,
         tokenProgramLp: TOKEN_2022_PROGRAM_ID,
         systemProgram: SystemProgram.programId,
         associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        protocolFeeRecipient: null,
+        creationFeeMint: null,
+        authorityFeeAta: null,
+        recipientFeeAta: null,
+        creationFeeTokenProgram: null,
       })
       .signers([poolCreator])
       .rpc();
 
     const poolTx2 = await program.methods
-      .createPool(feeBp2)
+      .createPool(index2, feeBp2)
       .accountsStrict({
         authority: poolCreator.publicKey,
+        config: config_pda,
+        poolCreator: null,
         pool: pool_pda_2,
         tokenMintA: mintA,
         tokenMintB: mintB,
         tokenVaultA: pool2TokenVaultA,
         tokenVaultB: pool2TokenVaultB,
         lpMint: lpMint2_pda,
+        registry: findRegistryPda(program.programId),
+        pairIndexHeader: findPairIndexHeaderPda(
+          program.programId,
+          mintA,
+          mintB
+        ),
+        pairIndex: findPairIndexPda(program.programId, mintA, mintB),
         tokenProgramA: mintAAccount.owner,
         tokenProgramB: mintBAccount.owner,
         tokenProgramLp: TOKEN_2022_PROGRAM_ID,
         systemProgram: SystemProgram.programId,
         associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        protocolFeeRecipient: null,
+        creationFeeMint: null,
+        authorityFeeAta: null,
+        recipientFeeAta: null,
+        creationFeeTokenProgram: null,
       })
       .signers([poolCreator])
       .rpc();
@@ -390,6 +514,8 @@ describe("instructions::create_pool", () => {
     );
     assert.equal(pool1Account.feeBp, feeBp1);
     assert.equal(pool2Account.feeBp, feeBp2);
+    assert.equal(pool1Account.index, index1);
+    assert.equal(pool2Account.index, index2);
     assert.equal(pool1Account.bump, pool_bump_1);
     assert.equal(pool2Account.bump, pool_bump_2);
   });
@@ -404,6 +530,10 @@ async function setUpTest() {
     address: poolCreator.publicKey,
     amount: LAMPORTS_PER_SOL * 1000,
   });
+  const config_pda = await initializeConfig({
+    program,
+    authority: poolCreator,
+  });
 
   const tokenAMint = createTokenMint({
     context,
@@ -448,5 +578,6 @@ async function setUpTest() {
     tokenBMint,
     creatorTokenAAssociatedAccount,
     creatorTokenBAssociatedAccount,
+    config_pda,
   };
 }
@@ -0,0 +1,362 @@
+import { assert } from "chai";
+import { BN } from "@coral-xyz/anchor";
+import {
+  Keypair,
+  LAMPORTS_PER_SOL,
+  PublicKey,
+  SystemProgram,
+  SYSVAR_INSTRUCTIONS_PUBKEY,
+} from "@solana/web3.js";
+import { POOL_MINT_SEED, POOL_SEED } from "../utils/seeds";
+import {
+  airdrop,
+  setUpEnv,
+  createTokenMint,
+  createAssociatedTokenAccount,
+  mintTo,
+  initializeConfig,
+  findPairIndexHeaderPda,
+  findPairIndexPda,
+  findRegistryPda,
+} from "../utils/helpers";
+import { ErrorCode } from "../errors";
+import {
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  getAssociatedTokenAddressSync,
+  TOKEN_2022_PROGRAM_ID,
+  TOKEN_PROGRAM_ID,
+  AccountLayout,
+} from "@solana/spl-token";
+import { addInitialLiquidity } from "./helpers";
+
+describe("instructions::flash_loan", () => {
+  it("borrower can take and repay a flash loan in one transaction", async () => {
+    const { context, program, poolCreator, accounts } = await setUpFlashLoan();
+
+    const reserveABefore = await getTokenAmount(context, accounts.poolAtaA);
+    const reserveBBefore = await getTokenAmount(context, accounts.poolAtaB);
+
+    const amountA = reserveABefore.div(new BN(10));
+    const feeA = calculateFlashFee(amountA, 100);
+
+    const repayInstruction = await program.methods
+      .flashRepay(amountA.add(feeA), new BN(0))
+      .accountsStrict({
+        payer: poolCreator.publicKey,
+        pool: accounts.pool,
+        tokenMintA: accounts.tokenMintA,
+        poolAtaA: accounts.poolAtaA,
+        tokenMintB: accounts.tokenMintB,
+        poolAtaB: accounts.poolAtaB,
+        payerAtaA: accounts.borrowerAtaA,
+        payerAtaB: accounts.borrowerAtaB,
+        config: accounts.config,
+        tokenProgramA: accounts.tokenProgramA,
+        tokenProgramB: accounts.tokenProgramB,
+        systemProgram: SystemProgram.programId,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      })
+      .instruction();
+
+    await program.methods
+      .flashLoan(amountA, new BN(0))
+      .accountsStrict({
+        borrower: poolCreator.publicKey,
+        ...accounts,
+        instructionsSysvar: SYSVAR_INSTRUCTIONS_PUBKEY,
+        systemProgram: SystemProgram.programId,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      })
+      .postInstructions([repayInstruction])
+      .signers([poolCreator])
+      .rpc();
+
+    // the pool earned the flash fee
+    assert(
+      (await getTokenAmount(context, accounts.poolAtaA)).eq(
+        reserveABefore.add(feeA)
+      )
+    );
+    assert(
+      (await getTokenAmount(context, accounts.poolAtaB)).eq(reserveBBefore)
+    );
+
+    // the pool is unlocked
+    const pool = await program.account.pool.fetch(accounts.pool);
+    assert.isFalse(pool.flashLoanActive);
+  });
+
+  it("fails if the loan is not repaid in the same transaction", async () => {
+    const { context, program, poolCreator, accounts } = await setUpFlashLoan();
+
+    await program.methods
+      .flashLoan(new BN(1_000), new BN(0))
+      .accountsStrict({
+        borrower: poolCreator.publicKey,
+        ...accounts,
+        instructionsSysvar: SYSVAR_INSTRUCTIONS_PUBKEY,
+        systemProgram: SystemProgram.programId,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      })
+      .signers([poolCreator])
+      .rpc()
+      .then(() => assert.fail("Transaction should have failed"))
+      .catch((err) =>
+        assert.equal(err.error.errorCode.number, ErrorCode.FlashLoanNotRepaid)
+      );
+  });
+
+  it("fails if the repayment does not cover the flash fee", async () => {
+    const { context, program, poolCreator, accounts } = await setUpFlashLoan();
+
+    const amountA = new BN(1_000_000);
+
+    const repayInstruction = await program.methods
+      .flashRepay(amountA, new BN(0))
+      .accountsStrict({
+        payer: poolCreator.publicKey,
+        pool: accounts.pool,
+        tokenMintA: accounts.tokenMintA,
+        poolAtaA: accounts.poolAtaA,
+        tokenMintB: accounts.tokenMintB,
+        poolAtaB: accounts.poolAtaB,
+        payerAtaA: accounts.borrowerAtaA,
+        payerAtaB: accounts.borrowerAtaB,
+        config: accounts.config,
+        tokenProgramA: accounts.tokenProgramA,
+        tokenProgramB: accounts.tokenProgramB,
+        systemProgram: SystemProgram.programId,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      })
+      .instruction();
+
+    await program.methods
+      .flashLoan(amountA, new BN(0))
+      .accountsStrict({
+        borrower: poolCreator.publicKey,
+        ...accounts,
+        instructionsSysvar: SYSVAR_INSTRUCTIONS_PUBKEY,
+        systemProgram: SystemProgram.programId,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      })
+      .postInstructions([repayInstruction])
+      .signers([poolCreator])
+      .rpc()
+      .then(() => assert.fail("Transaction should have failed"))
+      .catch((err) =>
+        assert.equal(err.error.errorCode.number, ErrorCode.InvariantViolated)
+      );
+  });
+});
+
+// Calculates: ceil((amount * fee_bp) / 10000)
+function calculateFlashFee(amount: BN, feeBp: number) {
+  return amount
+    .mul(new BN(feeBp))
+    .add(new BN(10000 - 1))
+    .div(new BN(10000));
+}
+
+async function getTokenAmount(context, address: PublicKey) {
+  const account = await context.banksClient.getAccount(address);
+  return new BN(AccountLayout.decode(account.data).amount.toString());
+}
+
+async function setUpFlashLoan() {
+  const {
+    context,
+    program,
+    poolCreator,
+    mintA,
+    mintB,
+    pool_pda,
+    lpMint_pda,
+    poolTokenVaultA,
+    poolTokenVaultB,
+    config_pda,
+  } = await setUpTest();
+
+  await addInitialLiquidity({
+    context,
+    program,
+    poolCreator,
+    mintA,
+    mintB,
+    pool_pda,
+    lpMint_pda,
+    poolTokenVaultA,
+    poolTokenVaultB,
+  });
+
+  const tokenProgramA = (await context.banksClient.getAccount(mintA)).owner;
+  const tokenProgramB = (await context.banksClient.getAccount(mintB)).owner;
+
+  return {
+    context,
+    program,
+    poolCreator,
+    accounts: {
+      pool: pool_pda,
+      tokenMintA: mintA,
+      poolAtaA: poolTokenVaultA,
+      tokenMintB: mintB,
+      poolAtaB: poolTokenVaultB,
+      borrowerAtaA: getAssociatedTokenAddressSync(
+        mintA,
+        poolCreator.publicKey,
+        false,
+        tokenProgramA
+      ),
+      borrowerAtaB: getAssociatedTokenAddressSync(
+        mintB,
+        poolCreator.publicKey,
+        false,
+        tokenProgramB
+      ),
+      config: config_pda,
+      tokenProgramA,
+      tokenProgramB,
+    },
+  };
+}
+
+async function setUpTest() {
+  const { context, program, provider } = await setUpEnv();
+
+  const poolCreator = Keypair.generate();
+  airdrop({
+    context,
+    address: poolCreator.publicKey,
+    amount: LAMPORTS_PER_SOL * 1000,
+  });
+  const config_pda = await initializeConfig({
+    program,
+    authority: poolCreator,
+  });
+
+  const tokenAMint = createTokenMint({
+    context,
+    tokenProgram: TOKEN_PROGRAM_ID,
+    decimals: 9,
+  });
+  const tokenBMint = createTokenMint({
+    context,
+    tokenProgram: TOKEN_2022_PROGRAM_ID,
+    decimals: 9,
+  });
+
+  const creatorTokenAAssociatedAccount = createAssociatedTokenAccount({
+    context,
+    mint: tokenAMint,
+    owner: poolCreator.publicKey,
+    tokenProgram: TOKEN_PROGRAM_ID,
+  });
+  const creatorTokenBAssociatedAccount = createAssociatedTokenAccount({
+    context,
+    mint: tokenBMint,
+    owner: poolCreator.publicKey,
+    tokenProgram: TOKEN_2022_PROGRAM_ID,
+  });
+
+  await mintTo({
+    context,
+    ata: creatorTokenAAssociatedAccount,
+    amount: 100_000_000 * 10 ** 9,
+  });
+
+  await mintTo({
+    context,
+    ata: creatorTokenBAssociatedAccount,
+    amount: 100_000_000 * 10 ** 9,
+  });
+
+  const isFront = new BN(tokenAMint.toBuffer()).gt(
+    new BN(tokenBMint.toBuffer())
+  );
+
+  let mintA = isFront ? tokenAMint : tokenBMint;
+  let mintB = isFront ? tokenBMint : tokenAMint;
+
+  const feeBp = 100; // 1%
+  const index = 0;
+
+  const [pool_pda, pool_bump] = PublicKey.findProgramAddressSync(
+    [
+      Buffer.from(POOL_SEED),
+      mintA.toBuffer(),
+      mintB.toBuffer(),
+      new BN(index).toBuffer("le", 2), // 2 bytes for the pool index (u16)
+    ],
+    program.programId
+  );
+
+  const mintAAccount = await context.banksClient.getAccount(mintA);
+  const mintBAccount = await context.banksClient.getAccount(mintB);
+
+  const [lpMint_pda, lpMintBump] = PublicKey.findProgramAddressSync(
+    [
+      Buffer.from(POOL_MINT_SEED),
+      mintA.toBuffer(),
+      mintB.toBuffer(),
+      new BN(index).toBuffer("le", 2), // 2 bytes for the pool index (u16)
+    ],
+    program.programId
+  );
+
+  const poolTokenVaultA = getAssociatedTokenAddressSync(
+    mintA,
+    pool_pda,
+    true,
+    mintAAccount.owner
+  );
+  const poolTokenVaultB = getAssociatedTokenAddressSync(
+    mintB,
+    pool_pda,
+    true,
+    mintBAccount.owner
+  );
+
+  const tx = await program.methods
+    .createPool(index, feeBp)
+    .accountsStrict({
+      authority: poolCreator.publicKey,
+      config: config_pda,
+      poolCreator: null,
+      pool: pool_pda,
+      tokenMintA: mintA,
+      tokenMintB: mintB,
+      tokenVaultA: poolTokenVaultA,
+      tokenVaultB: poolTokenVaultB,
+      lpMint: lpMint_pda,
+      registry: findRegistryPda(program.programId),
+      pairIndexHeader: findPairIndexHeaderPda(program.programId, mintA, mintB),
+      pairIndex: findPairIndexPda(program.programId, mintA, mintB),
+      tokenProgramA: mintAAccount.owner,
+      tokenProgramB: mintBAccount.owner,
+      tokenProgramLp: TOKEN_2022_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      systemProgram: SystemProgram.programId,
+      protocolFeeRecipient: null,
+      creationFeeMint: null,
+      authorityFeeAta: null,
+      recipientFeeAta: null,
+      creationFeeTokenProgram: null,
+    })
+    .signers([poolCreator])
+    .rpc();
+
+  return {
+    context,
+    program,
+    provider,
+    poolCreator,
+    mintA,
+    mintB,
+    pool_pda,
+    lpMint_pda,
+    poolTokenVaultA,
+    poolTokenVaultB,
+    config_pda,
+    feeBp,
+  };
+}
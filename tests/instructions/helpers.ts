@@ -6,6 +6,7 @@ import { Keypair, PublicKey } from "@solana/web3.js";
 import { getAssociatedTokenAddressSync } from "@solana/spl-token";
 import { ASSOCIATED_TOKEN_PROGRAM_ID } from "@solana/spl-token";
 import { SystemProgram } from "@solana/web3.js";
+import { findConfigPda } from "../utils/helpers";
 
 export async function addInitialLiquidity({
   context,
@@ -69,12 +70,22 @@ export async function addInitialLiquidity({
   );
 
   const tx = await program.methods
-    .addLiquidity(amountADesired, amountBDesired, amountAMin, amountBMin)
+    .addLiquidity(
+      amountADesired,
+      amountBDesired,
+      amountAMin,
+      amountBMin,
+      null,
+      false
+    )
     .accountsStrict({
       provider: poolCreator.publicKey,
       providerAtaA: creatorTokenAAssociatedAccount,
       providerAtaB: creatorTokenBAssociatedAccount,
-      providerAtaLp: providerAtaLp,
+      wsolA: null,
+      wsolB: null,
+      lpRecipient: poolCreator.publicKey,
+      lpRecipientAta: providerAtaLp,
       pool: pool_pda,
       lpMint: lpMint_pda,
       poolAtaA: poolTokenVaultA,
@@ -86,6 +97,7 @@ export async function addInitialLiquidity({
       tokenProgramLp: lpMintAccount.owner,
       associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
       systemProgram: SystemProgram.programId,
+      config: findConfigPda(program.programId),
     })
     .signers([poolCreator])
     .rpc();
@@ -13,6 +13,11 @@ import {
   createTokenMint,
   createAssociatedTokenAccount,
   mintTo,
+  initializeConfig,
+  findPairIndexHeaderPda,
+  findPairIndexPda,
+  findConfigPda,
+  findRegistryPda,
 } from "../utils/helpers";
 import {
   ASSOCIATED_TOKEN_PROGRAM_ID,
@@ -348,13 +353,18 @@ async function addInitialLiquidity(params: {
       amountADesired,
       amountBDesired,
       amountADesired,
-      amountBDesired
+      amountBDesired,
+      null,
+      false
     )
     .accountsStrict({
       provider: poolCreator.publicKey,
       providerAtaA: creatorTokenAAssociatedAccount,
       providerAtaB: creatorTokenBAssociatedAccount,
-      providerAtaLp: providerAtaLp,
+      wsolA: null,
+      wsolB: null,
+      lpRecipient: poolCreator.publicKey,
+      lpRecipientAta: providerAtaLp,
       pool: pool_pda,
       lpMint: lpMint_pda,
       poolAtaA: poolTokenVaultA,
@@ -366,6 +376,7 @@ async function addInitialLiquidity(params: {
       tokenProgramLp: lpMintAccount.owner,
       associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
       systemProgram: SystemProgram.programId,
+      config: findConfigPda(program.programId),
     })
     .signers([poolCreator])
     .rpc();
@@ -448,12 +459,22 @@ async function addLiquidity(params: {
   );
 
   await program.methods
-    .addLiquidity(optimalAmountA, optimalAmountB, amountAMin, amountBMin)
+    .addLiquidity(
+      optimalAmountA,
+      optimalAmountB,
+      amountAMin,
+      amountBMin,
+      null,
+      false
+    )
     .accountsStrict({
       provider: user.user.publicKey,
       providerAtaA: user.tokenAAccount,
       providerAtaB: user.tokenBAccount,
-      providerAtaLp: providerAtaLp,
+      wsolA: null,
+      wsolB: null,
+      lpRecipient: user.user.publicKey,
+      lpRecipientAta: providerAtaLp,
       pool: pool_pda,
       lpMint: lpMint_pda,
       poolAtaA: poolTokenVaultA,
@@ -465,6 +486,7 @@ async function addLiquidity(params: {
       tokenProgramLp: lpMintAccount.owner,
       associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
       systemProgram: SystemProgram.programId,
+      config: findConfigPda(program.programId),
     })
     .signers([user.user])
     .rpc();
@@ -479,6 +501,10 @@ async function setUpTest() {
     address: poolCreator.publicKey,
     amount: LAMPORTS_PER_SOL * 1000,
   });
+  const config_pda = await initializeConfig({
+    program,
+    authority: poolCreator,
+  });
 
   const tokenAMint = createTokenMint({
     context,
@@ -523,13 +549,14 @@ async function setUpTest() {
   let mintB = isFront ? tokenBMint : tokenAMint;
 
   const feeBp = 100; // 1%
+  const index = 0;
 
   const [pool_pda, pool_bump] = PublicKey.findProgramAddressSync(
     [
       Buffer.from(POOL_SEED),
       mintA.toBuffer(),
       mintB.toBuffer(),
-      new BN(feeBp).toBuffer("le", 2),
+      new BN(index).toBuffer("le", 2),
     ],
     program.programId
   );
@@ -542,7 +569,7 @@ async function setUpTest() {
       Buffer.from(POOL_MINT_SEED),
       mintA.toBuffer(),
       mintB.toBuffer(),
-      new BN(feeBp).toBuffer("le", 2),
+      new BN(index).toBuffer("le", 2),
     ],
     program.programId
   );
@@ -561,20 +588,30 @@ async function setUpTest() {
   );
 
   await program.methods
-    .createPool(feeBp)
+    .createPool(index, feeBp)
     .accountsStrict({
       authority: poolCreator.publicKey,
+      config: config_pda,
+      poolCreator: null,
       pool: pool_pda,
       tokenMintA: mintA,
       tokenMintB: mintB,
       tokenVaultA: poolTokenVaultA,
       tokenVaultB: poolTokenVaultB,
       lpMint: lpMint_pda,
+      registry: findRegistryPda(program.programId),
+      pairIndexHeader: findPairIndexHeaderPda(program.programId, mintA, mintB),
+      pairIndex: findPairIndexPda(program.programId, mintA, mintB),
       tokenProgramA: mintAAccount.owner,
       tokenProgramB: mintBAccount.owner,
       tokenProgramLp: TOKEN_2022_PROGRAM_ID,
       associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
       systemProgram: SystemProgram.programId,
+      protocolFeeRecipient: null,
+      creationFeeMint: null,
+      authorityFeeAta: null,
+      recipientFeeAta: null,
+      creationFeeTokenProgram: null,
     })
     .signers([poolCreator])
     .rpc();
@@ -0,0 +1,389 @@
+import { assert } from "chai";
+import { BN } from "@coral-xyz/anchor";
+import {
+  Keypair,
+  LAMPORTS_PER_SOL,
+  PublicKey,
+  SystemProgram,
+} from "@solana/web3.js";
+import { POOL_MINT_SEED, POOL_SEED } from "../utils/seeds";
+import {
+  airdrop,
+  setUpEnv,
+  createTokenMint,
+  createAssociatedTokenAccount,
+  mintTo,
+  initializeConfig,
+  findPairIndexHeaderPda,
+  findPairIndexPda,
+  findConfigPda,
+  findRegistryPda,
+} from "../utils/helpers";
+import { ErrorCode } from "../errors";
+import {
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  getAssociatedTokenAddressSync,
+  TOKEN_2022_PROGRAM_ID,
+  TOKEN_PROGRAM_ID,
+  AccountLayout,
+  MintLayout,
+} from "@solana/spl-token";
+import { addInitialLiquidity } from "./helpers";
+
+describe("instructions::remove_liquidity", () => {
+  it("provider can remove liquidity", async () => {
+    const {
+      context,
+      program,
+      poolCreator,
+      mintA,
+      mintB,
+      pool_pda,
+      lpMint_pda,
+      poolTokenVaultA,
+      poolTokenVaultB,
+    } = await setUpTest();
+
+    await addInitialLiquidity({
+      context,
+      program,
+      poolCreator,
+      mintA,
+      mintB,
+      pool_pda,
+      lpMint_pda,
+      poolTokenVaultA,
+      poolTokenVaultB,
+    });
+
+    const {
+      providerAtaA,
+      providerAtaB,
+      providerAtaLp,
+      tokenProgramA,
+      tokenProgramB,
+      tokenProgramLp,
+    } = await getProviderAccounts(
+      context,
+      poolCreator,
+      mintA,
+      mintB,
+      lpMint_pda
+    );
+
+    const reserveA = await getTokenAmount(context, poolTokenVaultA);
+    const reserveB = await getTokenAmount(context, poolTokenVaultB);
+    const lpBalance = await getTokenAmount(context, providerAtaLp);
+    const lpSupply = new BN(
+      MintLayout.decode(
+        (await context.banksClient.getAccount(lpMint_pda)).data
+      ).supply.toString()
+    );
+    const balanceABefore = await getTokenAmount(context, providerAtaA);
+    const balanceBBefore = await getTokenAmount(context, providerAtaB);
+
+    // remove half of the provider LP position
+    const lpAmount = lpBalance.div(new BN(2));
+    const expectedAmountA = lpAmount.mul(reserveA).div(lpSupply);
+    const expectedAmountB = lpAmount.mul(reserveB).div(lpSupply);
+
+    await program.methods
+      .removeLiquidity(lpAmount, expectedAmountA, expectedAmountB, null, false)
+      .accountsStrict({
+        provider: poolCreator.publicKey,
+        providerAtaA,
+        providerAtaB,
+        wsolA: null,
+        wsolB: null,
+        providerAtaLp,
+        pool: pool_pda,
+        lpMint: lpMint_pda,
+        poolAtaA: poolTokenVaultA,
+        poolAtaB: poolTokenVaultB,
+        tokenMintA: mintA,
+        tokenMintB: mintB,
+        tokenProgramA,
+        tokenProgramB,
+        tokenProgramLp,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        config: findConfigPda(program.programId),
+      })
+      .signers([poolCreator])
+      .rpc();
+
+    // provider received tokens proportional to the burned LP share
+    assert(
+      (await getTokenAmount(context, providerAtaA))
+        .sub(balanceABefore)
+        .eq(expectedAmountA)
+    );
+    assert(
+      (await getTokenAmount(context, providerAtaB))
+        .sub(balanceBBefore)
+        .eq(expectedAmountB)
+    );
+
+    // LP tokens were burned
+    assert(
+      (await getTokenAmount(context, providerAtaLp)).eq(
+        lpBalance.sub(lpAmount)
+      )
+    );
+  });
+
+  it("fails if the deadline has passed", async () => {
+    const {
+      context,
+      program,
+      poolCreator,
+      mintA,
+      mintB,
+      pool_pda,
+      lpMint_pda,
+      poolTokenVaultA,
+      poolTokenVaultB,
+    } = await setUpTest();
+
+    await addInitialLiquidity({
+      context,
+      program,
+      poolCreator,
+      mintA,
+      mintB,
+      pool_pda,
+      lpMint_pda,
+      poolTokenVaultA,
+      poolTokenVaultB,
+    });
+
+    const {
+      providerAtaA,
+      providerAtaB,
+      providerAtaLp,
+      tokenProgramA,
+      tokenProgramB,
+      tokenProgramLp,
+    } = await getProviderAccounts(
+      context,
+      poolCreator,
+      mintA,
+      mintB,
+      lpMint_pda
+    );
+
+    // deadline one second in the past
+    const clock = await context.banksClient.getClock();
+    const deadline = new BN((clock.unixTimestamp - BigInt(1)).toString());
+
+    await program.methods
+      .removeLiquidity(new BN(1_000), new BN(1), new BN(1), deadline, false)
+      .accountsStrict({
+        provider: poolCreator.publicKey,
+        providerAtaA,
+        providerAtaB,
+        wsolA: null,
+        wsolB: null,
+        providerAtaLp,
+        pool: pool_pda,
+        lpMint: lpMint_pda,
+        poolAtaA: poolTokenVaultA,
+        poolAtaB: poolTokenVaultB,
+        tokenMintA: mintA,
+        tokenMintB: mintB,
+        tokenProgramA,
+        tokenProgramB,
+        tokenProgramLp,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        config: findConfigPda(program.programId),
+      })
+      .signers([poolCreator])
+      .rpc()
+      .then(() => assert.fail("Transaction should have failed"))
+      .catch((err) =>
+        assert.equal(err.error.errorCode.number, ErrorCode.Expired)
+      );
+  });
+});
+
+async function getTokenAmount(context, address: PublicKey) {
+  const account = await context.banksClient.getAccount(address);
+  return new BN(AccountLayout.decode(account.data).amount.toString());
+}
+
+async function getProviderAccounts(
+  context,
+  provider: Keypair,
+  mintA: PublicKey,
+  mintB: PublicKey,
+  lpMint: PublicKey
+) {
+  const tokenProgramA = (await context.banksClient.getAccount(mintA)).owner;
+  const tokenProgramB = (await context.banksClient.getAccount(mintB)).owner;
+  const tokenProgramLp = (await context.banksClient.getAccount(lpMint)).owner;
+
+  return {
+    providerAtaA: getAssociatedTokenAddressSync(
+      mintA,
+      provider.publicKey,
+      false,
+      tokenProgramA
+    ),
+    providerAtaB: getAssociatedTokenAddressSync(
+      mintB,
+      provider.publicKey,
+      false,
+      tokenProgramB
+    ),
+    providerAtaLp: getAssociatedTokenAddressSync(
+      lpMint,
+      provider.publicKey,
+      false,
+      tokenProgramLp
+    ),
+    tokenProgramA,
+    tokenProgramB,
+    tokenProgramLp,
+  };
+}
+
+async function setUpTest() {
+  const { context, program, provider } = await setUpEnv();
+
+  const poolCreator = Keypair.generate();
+  airdrop({
+    context,
+    address: poolCreator.publicKey,
+    amount: LAMPORTS_PER_SOL * 1000,
+  });
+  const config_pda = await initializeConfig({
+    program,
+    authority: poolCreator,
+  });
+
+  const tokenAMint = createTokenMint({
+    context,
+    tokenProgram: TOKEN_PROGRAM_ID,
+    decimals: 9,
+  });
+  const tokenBMint = createTokenMint({
+    context,
+    tokenProgram: TOKEN_2022_PROGRAM_ID,
+    decimals: 9,
+  });
+
+  const creatorTokenAAssociatedAccount = createAssociatedTokenAccount({
+    context,
+    mint: tokenAMint,
+    owner: poolCreator.publicKey,
+    tokenProgram: TOKEN_PROGRAM_ID,
+  });
+  const creatorTokenBAssociatedAccount = createAssociatedTokenAccount({
+    context,
+    mint: tokenBMint,
+    owner: poolCreator.publicKey,
+    tokenProgram: TOKEN_2022_PROGRAM_ID,
+  });
+
+  await mintTo({
+    context,
+    ata: creatorTokenAAssociatedAccount,
+    amount: 100_000_000 * 10 ** 9,
+  });
+
+  await mintTo({
+    context,
+    ata: creatorTokenBAssociatedAccount,
+    amount: 100_000_000 * 10 ** 9,
+  });
+
+  const isFront = new BN(tokenAMint.toBuffer()).gt(
+    new BN(tokenBMint.toBuffer())
+  );
+
+  let mintA = isFront ? tokenAMint : tokenBMint;
+  let mintB = isFront ? tokenBMint : tokenAMint;
+
+  const feeBp = 100; // 1%
+  const index = 0;
+
+  const [pool_pda, pool_bump] = PublicKey.findProgramAddressSync(
+    [
+      Buffer.from(POOL_SEED),
+      mintA.toBuffer(),
+      mintB.toBuffer(),
+      new BN(index).toBuffer("le", 2), // 2 bytes for the pool index (u16)
+    ],
+    program.programId
+  );
+
+  const mintAAccount = await context.banksClient.getAccount(mintA);
+  const mintBAccount = await context.banksClient.getAccount(mintB);
+
+  const [lpMint_pda, lpMintBump] = PublicKey.findProgramAddressSync(
+    [
+      Buffer.from(POOL_MINT_SEED),
+      mintA.toBuffer(),
+      mintB.toBuffer(),
+      new BN(index).toBuffer("le", 2), // 2 bytes for the pool index (u16)
+    ],
+    program.programId
+  );
+
+  const poolTokenVaultA = getAssociatedTokenAddressSync(
+    mintA,
+    pool_pda,
+    true,
+    mintAAccount.owner
+  );
+  const poolTokenVaultB = getAssociatedTokenAddressSync(
+    mintB,
+    pool_pda,
+    true,
+    mintBAccount.owner
+  );
+
+  const tx = await program.methods
+    .createPool(index, feeBp)
+    .accountsStrict({
+      authority: poolCreator.publicKey,
+      config: config_pda,
+      poolCreator: null,
+      pool: pool_pda,
+      tokenMintA: mintA,
+      tokenMintB: mintB,
+      tokenVaultA: poolTokenVaultA,
+      tokenVaultB: poolTokenVaultB,
+      lpMint: lpMint_pda,
+      registry: findRegistryPda(program.programId),
+      pairIndexHeader: findPairIndexHeaderPda(program.programId, mintA, mintB),
+      pairIndex: findPairIndexPda(program.programId, mintA, mintB),
+      tokenProgramA: mintAAccount.owner,
+      tokenProgramB: mintBAccount.owner,
+      tokenProgramLp: TOKEN_2022_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      systemProgram: SystemProgram.programId,
+      protocolFeeRecipient: null,
+      creationFeeMint: null,
+      authorityFeeAta: null,
+      recipientFeeAta: null,
+      creationFeeTokenProgram: null,
+    })
+    .signers([poolCreator])
+    .rpc();
+
+  return {
+    context,
+    program,
+    provider,
+    poolCreator,
+    mintA,
+    mintB,
+    pool_pda,
+    lpMint_pda,
+    poolTokenVaultA,
+    poolTokenVaultB,
+    feeBp,
+  };
+}
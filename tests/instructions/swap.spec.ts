@@ -6,13 +6,16 @@ import {
   PublicKey,
   SystemProgram,
 } from "@solana/web3.js";
-import { POOL_MINT_SEED, POOL_SEED } from "../utils/seeds";
+import { CONFIG_SEED, POOL_MINT_SEED, POOL_SEED } from "../utils/seeds";
 import {
   airdrop,
   setUpEnv,
   createTokenMint,
   createAssociatedTokenAccount,
   mintTo,
+  findPairIndexHeaderPda,
+  findPairIndexPda,
+  findRegistryPda,
 } from "../utils/helpers";
 import { ErrorCode } from "../errors";
 import {
@@ -44,6 +47,7 @@ describe("instructions::swap", () => {
       poolTokenVaultA,
       poolTokenVaultB,
       feeBp,
+      config_pda,
     } = await setUpTest();
 
     await addInitialLiquidity({
@@ -153,12 +157,15 @@ describe("instructions::swap", () => {
     });
 
     const tx = await program.methods
-      .swap(amountIn, amountOutMinAfterSlippage)
+      .swap(amountIn, amountOutMinAfterSlippage, null, 0, false)
       .accountsStrict({
         payer: randomUser.publicKey,
         pool: pool_pda,
         payerAtaInput: randomUserTokenAAssociatedAccount,
-        payerAtaOutput: randomUserTokenBAssociatedAccount,
+        recipient: randomUser.publicKey,
+        recipientAtaOutput: randomUserTokenBAssociatedAccount,
+        wsolInput: null,
+        wsolOutput: null,
         poolAtaInput: poolTokenVaultA,
         poolAtaOutput: poolTokenVaultB,
         tokenMintInput: inputMint,
@@ -168,6 +175,8 @@ describe("instructions::swap", () => {
         tokenProgramLp: TOKEN_2022_PROGRAM_ID,
         associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
         systemProgram: SystemProgram.programId,
+        config: config_pda,
+        referrerAta: null,
       })
       .signers([randomUser])
       .rpc();
@@ -238,6 +247,82 @@ describe("instructions::swap", () => {
     // ratio has changed after swap
     assert(amountOutAfterSwap.amountOut.lte(amountOutBeforeSwap.amountOut));
   });
+
+  it("fails if the deadline has passed", async () => {
+    const {
+      context,
+      program,
+      poolCreator,
+      mintA,
+      mintB,
+      pool_pda,
+      lpMint_pda,
+      poolTokenVaultA,
+      poolTokenVaultB,
+      config_pda,
+    } = await setUpTest();
+
+    await addInitialLiquidity({
+      context,
+      program,
+      poolCreator,
+      mintA,
+      mintB,
+      pool_pda,
+      lpMint_pda,
+      poolTokenVaultA,
+      poolTokenVaultB,
+    });
+
+    const mintAAccount = await context.banksClient.getAccount(mintA);
+    const mintBAccount = await context.banksClient.getAccount(mintB);
+
+    const creatorTokenAAssociatedAccount = getAssociatedTokenAddressSync(
+      mintA,
+      poolCreator.publicKey,
+      false,
+      mintAAccount.owner
+    );
+    const creatorTokenBAssociatedAccount = getAssociatedTokenAddressSync(
+      mintB,
+      poolCreator.publicKey,
+      false,
+      mintBAccount.owner
+    );
+
+    // deadline one second in the past
+    const clock = await context.banksClient.getClock();
+    const deadline = new BN((clock.unixTimestamp - BigInt(1)).toString());
+
+    await program.methods
+      .swap(new BN(1_000_000), new BN(1), deadline, 0, false)
+      .accountsStrict({
+        payer: poolCreator.publicKey,
+        pool: pool_pda,
+        payerAtaInput: creatorTokenAAssociatedAccount,
+        recipient: poolCreator.publicKey,
+        recipientAtaOutput: creatorTokenBAssociatedAccount,
+        wsolInput: null,
+        wsolOutput: null,
+        poolAtaInput: poolTokenVaultA,
+        poolAtaOutput: poolTokenVaultB,
+        tokenMintInput: mintA,
+        tokenMintOutput: mintB,
+        tokenProgramInput: mintAAccount.owner,
+        tokenProgramOutput: mintBAccount.owner,
+        tokenProgramLp: TOKEN_2022_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        config: config_pda,
+        referrerAta: null,
+      })
+      .signers([poolCreator])
+      .rpc()
+      .then(() => assert.fail("Transaction should have failed"))
+      .catch((err) =>
+        assert.equal(err.error.errorCode.number, ErrorCode.Expired)
+      );
+  });
 });
 
 async function setUpTest() {
@@ -294,13 +379,29 @@ async function setUpTest() {
   let mintB = isFront ? tokenBMint : tokenAMint;
 
   const feeBp = 100; // 1%
+  const index = 0;
+
+  // Swaps read the protocol fee from the config, no protocol fee is charged
+  const [config_pda] = PublicKey.findProgramAddressSync(
+    [Buffer.from(CONFIG_SEED)],
+    program.programId
+  );
+  await program.methods
+    .initialize(poolCreator.publicKey, 0)
+    .accountsStrict({
+      authority: poolCreator.publicKey,
+      config: config_pda,
+      systemProgram: SystemProgram.programId,
+    })
+    .signers([poolCreator])
+    .rpc();
 
   const [pool_pda, pool_bump] = PublicKey.findProgramAddressSync(
     [
       Buffer.from(POOL_SEED),
       mintA.toBuffer(),
       mintB.toBuffer(),
-      new BN(feeBp).toBuffer("le", 2), // 2 bytes for feeBp (u16)
+      new BN(index).toBuffer("le", 2), // 2 bytes for the pool index (u16)
     ],
     program.programId
   );
@@ -313,7 +414,7 @@ async function setUpTest() {
       Buffer.from(POOL_MINT_SEED),
       mintA.toBuffer(),
       mintB.toBuffer(),
-      new BN(feeBp).toBuffer("le", 2), // 2 bytes for feeBp (u16)
+      new BN(index).toBuffer("le", 2), // 2 bytes for the pool index (u16)
     ],
     program.programId
   );
@@ -332,20 +433,30 @@ async function setUpTest() {
   );
 
   const tx = await program.methods
-    .createPool(feeBp)
+    .createPool(index, feeBp)
     .accountsStrict({
       authority: poolCreator.publicKey,
+      config: config_pda,
+      poolCreator: null,
       pool: pool_pda,
       tokenMintA: mintA,
       tokenMintB: mintB,
       tokenVaultA: poolTokenVaultA,
       tokenVaultB: poolTokenVaultB,
       lpMint: lpMint_pda,
+      registry: findRegistryPda(program.programId),
+      pairIndexHeader: findPairIndexHeaderPda(program.programId, mintA, mintB),
+      pairIndex: findPairIndexPda(program.programId, mintA, mintB),
       tokenProgramA: mintAAccount.owner,
       tokenProgramB: mintBAccount.owner,
       tokenProgramLp: TOKEN_2022_PROGRAM_ID,
       associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
       systemProgram: SystemProgram.programId,
+      protocolFeeRecipient: null,
+      creationFeeMint: null,
+      authorityFeeAta: null,
+      recipientFeeAta: null,
+      creationFeeTokenProgram: null,
     })
     .signers([poolCreator])
     .rpc();
@@ -362,5 +473,6 @@ async function setUpTest() {
     poolTokenVaultA,
     poolTokenVaultB,
     feeBp,
+    config_pda,
   };
 }
@@ -13,11 +13,10 @@ describe("instructions::update_config", () => {
   it("should update the program config account", async () => {
     const { context, program, provider, admin, config_pda } = await setUpTest();
 
-    const protocolFeeRecipientKeypair = Keypair.generate();
-    const protocolFeeBp = 50; // 0.5%
+    const maxReferralFeeBp = 2_000; // 20% of the swap fee
 
     const tx = await program.methods
-      .updateConfig(protocolFeeRecipientKeypair.publicKey, protocolFeeBp)
+      .updateConfig(maxReferralFeeBp, null, null, null, null)
       .accountsStrict({
         authority: admin.publicKey,
         config: config_pda,
@@ -29,17 +28,11 @@ describe("instructions::update_config", () => {
     // fetch the config account
     const configAccount = await program.account.config.fetch(config_pda);
 
-    // check that the protocol fee recipient is the new one
-    assert.equal(
-      configAccount.protocolFeeRecipient.toBase58(),
-      protocolFeeRecipientKeypair.publicKey.toBase58()
-    );
-
-    // check that the protocol fee basis points is the new one
-    assert.equal(configAccount.protocolFeeBp, protocolFeeBp);
+    // check that the referral fee cap is the new one
+    assert.equal(configAccount.maxReferralFeeBp, maxReferralFeeBp);
   });
 
-  it("should prevent updating the config account if the signer is not the admin", async () => {
+  it("should prevent updating the config account if the signer is not the fee manager", async () => {
     const { context, program, provider, admin, config_pda } = await setUpTest();
 
     const nonAdmin = Keypair.generate();
@@ -49,11 +42,8 @@ describe("instructions::update_config", () => {
       amount: LAMPORTS_PER_SOL * 1000,
     });
 
-    const protocolFeeRecipientKeypair = Keypair.generate();
-    const protocolFeeBp = 50; // 0.5%
-
     const tx = await program.methods
-      .updateConfig(protocolFeeRecipientKeypair.publicKey, protocolFeeBp)
+      .updateConfig(2_000, null, null, null, null)
       .accountsStrict({
         authority: nonAdmin.publicKey,
         config: config_pda,
@@ -63,7 +53,7 @@ describe("instructions::update_config", () => {
       .rpc()
       .then(() => assert.fail("Transaction should have failed"))
       .catch((err) =>
-        assert.equal(err.error.errorCode.number, ErrorCode.InvalidAuthority)
+        assert.equal(err.error.errorCode.number, ErrorCode.NotFeeManager)
       );
   });
 });
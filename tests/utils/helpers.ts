@@ -12,8 +12,19 @@ import { BankrunProvider } from "anchor-bankrun";
 import { ProgramTestContext, startAnchor } from "solana-bankrun";
 import IDL from "../../target/idl/liquidity_pools.json";
 import { LiquidityPools } from "../../target/types/liquidity_pools";
-import { Keypair, LAMPORTS_PER_SOL, PublicKey } from "@solana/web3.js";
+import {
+  Keypair,
+  LAMPORTS_PER_SOL,
+  PublicKey,
+  SystemProgram,
+} from "@solana/web3.js";
 import { LiteSVM } from "../../../litesvm/crates/node-litesvm/litesvm";
+import {
+  CONFIG_SEED,
+  PAIR_INDEX_HEADER_SEED,
+  PAIR_INDEX_SEED,
+  REGISTRY_SEED,
+} from "./seeds";
 
 export const setUpEnv = async () => {
   const context = await startAnchor("", [], []);
@@ -159,3 +170,68 @@ export const mintTo = async ({
     owner: accountData.owner,
   });
 };
+
+// create_pool checks the pool fee against the config, which must exist first
+export const initializeConfig = async ({
+  program,
+  authority,
+}: {
+  program: anchor.Program<LiquidityPools>;
+  authority: Keypair;
+}) => {
+  const [configPda] = PublicKey.findProgramAddressSync(
+    [Buffer.from(CONFIG_SEED)],
+    program.programId
+  );
+
+  await program.methods
+    .initialize(authority.publicKey, 0)
+    .accountsStrict({
+      authority: authority.publicKey,
+      config: configPda,
+      systemProgram: SystemProgram.programId,
+    })
+    .signers([authority])
+    .rpc();
+
+  return configPda;
+};
+
+export const findConfigPda = (programId: PublicKey) =>
+  PublicKey.findProgramAddressSync([Buffer.from(CONFIG_SEED)], programId)[0];
+
+// Registry page the next pool is appended to, page 0 until 64 pools exist
+export const findRegistryPda = (programId: PublicKey, page = 0) =>
+  PublicKey.findProgramAddressSync(
+    [Buffer.from(REGISTRY_SEED), new anchor.BN(page).toBuffer("le", 4)],
+    programId
+  )[0];
+
+// Pair index header of two sorted mints, holding the page count
+export const findPairIndexHeaderPda = (
+  programId: PublicKey,
+  mintA: PublicKey,
+  mintB: PublicKey
+) =>
+  PublicKey.findProgramAddressSync(
+    [Buffer.from(PAIR_INDEX_HEADER_SEED), mintA.toBuffer(), mintB.toBuffer()],
+    programId
+  )[0];
+
+// Pair index page `page` of two sorted mints, pools fill the pages in registration
+// order so the first 32 pools of a pair are on page 0
+export const findPairIndexPda = (
+  programId: PublicKey,
+  mintA: PublicKey,
+  mintB: PublicKey,
+  page = 0
+) =>
+  PublicKey.findProgramAddressSync(
+    [
+      Buffer.from(PAIR_INDEX_SEED),
+      mintA.toBuffer(),
+      mintB.toBuffer(),
+      new anchor.BN(page).toBuffer("le", 2),
+    ],
+    programId
+  )[0];
@@ -1,3 +1,6 @@
 export const CONFIG_SEED = "config";
 export const POOL_SEED = "pool";
 export const POOL_MINT_SEED = "mint";
+export const REGISTRY_SEED = "registry";
+export const PAIR_INDEX_SEED = "pair_index";
+export const PAIR_INDEX_HEADER_SEED = "pair_index_header";